@@ -0,0 +1,56 @@
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+// magnitudes of bins 0 to `fft_size / 2` of the first `fft_size` samples (zero-padded if short).
+// samples are Hann-windowed and scaled so that a sine of amplitude 1 peaks at about 1.0.
+// bin `k` is at `k * sample_rate / fft_size` Hz
+pub fn magnitude_spectrum(samples: &[f64], fft_size: usize) -> Vec<f64> {
+    if fft_size == 0 {
+        return Vec::new();
+    }
+
+    let window: Vec<f64> = (0..fft_size)
+        .map(|n| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * n as f64 / fft_size as f64).cos())
+        .collect();
+    let mut buffer: Vec<Complex<f64>> = (0..fft_size)
+        .map(|n| Complex::new(samples.get(n).copied().unwrap_or(0.0) * window[n], 0.0))
+        .collect();
+
+    let fft = FftPlanner::new().plan_fft_forward(fft_size);
+    fft.process(&mut buffer);
+
+    let scale = 2.0 / window.iter().sum::<f64>();
+    buffer[..fft_size / 2 + 1]
+        .iter()
+        .map(|c| c.norm() * scale)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::time::Transport;
+    use crate::ugens::core::Aug;
+    use crate::ugens::osc::Sine;
+
+    #[test]
+    fn spectrum_of_a_sine_peaks_at_its_frequency() {
+        let mut sine = Sine::new(Aug::val(0.0), Aug::val(1000.0));
+        let samples: Vec<f64> = sine
+            .proc_n(&mut Transport::new(48000), 4096)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+
+        let spectrum = magnitude_spectrum(&samples, 4096);
+        assert_eq!(spectrum.len(), 2049);
+        let (peak, mag) =
+            spectrum
+                .iter()
+                .enumerate()
+                .fold((0, 0.0), |p, (k, m)| if *m > p.1 { (k, *m) } else { p });
+        let bin_width = 48000.0 / 4096.0;
+        assert!((peak as f64 * bin_width - 1000.0).abs() < bin_width);
+        assert!(mag > 0.8 && mag < 1.1);
+    }
+}
@@ -1,15 +1,59 @@
+use std::error::Error;
+use std::fmt;
+use std::panic;
+use std::sync::Mutex;
+
 use cpal::Device;
 use cpal::EventLoop;
-use cpal::OutputBuffer;
 use cpal::SampleFormat;
 use cpal::SampleRate;
 use cpal::UnknownTypeOutputBuffer;
 
+// `run` calls back with each buffer to fill until the callback returns false
+pub trait AudioOutput {
+    fn run<F: FnMut(&mut [f32]) -> bool + Send>(&self, callback: F);
+}
+
 pub struct AudioDevice {
     pub event_loop: EventLoop,
     pub device: Device,
 }
 
+#[derive(Debug)]
+pub enum AudioDeviceError {
+    DeviceNotFound(String),
+    DeviceNotAvailable(String),
+    SampleRateNotSupported(String, u32),
+    CannotCreateStream(String, u32),
+}
+
+impl fmt::Display for AudioDeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AudioDeviceError::DeviceNotFound(name) => {
+                write!(f, "Output device '{}' is not found", name)
+            }
+            AudioDeviceError::DeviceNotAvailable(name) => {
+                write!(f, "Output device '{}' is not available", name)
+            }
+            AudioDeviceError::SampleRateNotSupported(name, rate) => write!(
+                f,
+                "Output device '{}' does not support stereo f32 output at {} Hz",
+                name, rate
+            ),
+            AudioDeviceError::CannotCreateStream(name, rate) => {
+                write!(f, "Cannot open output stream on '{}' at {} Hz", name, rate)
+            }
+        }
+    }
+}
+
+impl Error for AudioDeviceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
 impl AudioDevice {
     pub fn open(sample_rate: u32) -> AudioDevice {
         let device = cpal::default_output_device().unwrap();
@@ -30,13 +74,138 @@ impl AudioDevice {
         audio_device
     }
 
-    pub fn run<F: FnMut(OutputBuffer<f32>) + Send>(&self, mut callback: F) {
-        self.event_loop
-            .run(move |_stream_id, stream_data| match stream_data {
-                cpal::StreamData::Output {
-                    buffer: UnknownTypeOutputBuffer::F32(buffer),
-                } => callback(buffer),
-                _ => (),
+    pub fn list_devices() -> Vec<String> {
+        cpal::output_devices().map(|d| d.name()).collect()
+    }
+
+    pub fn with_device(name: &str, sample_rate: u32) -> Result<AudioDevice, AudioDeviceError> {
+        let device = match cpal::output_devices().find(|d| d.name() == name) {
+            Some(device) => device,
+            None => return Err(AudioDeviceError::DeviceNotFound(name.to_string())),
+        };
+
+        let supported = match device.supported_output_formats() {
+            Ok(mut formats) => formats.any(|f| {
+                f.channels == 2
+                    && f.data_type == SampleFormat::F32
+                    && f.min_sample_rate.0 <= sample_rate
+                    && sample_rate <= f.max_sample_rate.0
+            }),
+            Err(_) => return Err(AudioDeviceError::DeviceNotAvailable(name.to_string())),
+        };
+        if !supported {
+            return Err(AudioDeviceError::SampleRateNotSupported(
+                name.to_string(),
+                sample_rate,
+            ));
+        }
+
+        let format = cpal::Format {
+            channels: 2,
+            sample_rate: SampleRate(sample_rate),
+            data_type: SampleFormat::F32,
+        };
+        let event_loop = EventLoop::new();
+        let stream_id = match event_loop.build_output_stream(&device, &format) {
+            Ok(id) => id,
+            Err(_) => {
+                return Err(AudioDeviceError::CannotCreateStream(
+                    name.to_string(),
+                    sample_rate,
+                ))
+            }
+        };
+
+        let audio_device = AudioDevice { event_loop, device };
+        audio_device.event_loop.play_stream(stream_id);
+
+        Ok(audio_device)
+    }
+}
+
+// marks the unwinding out of cpal's event loop when the callback asks to stop
+struct Stopped;
+
+impl AudioOutput for AudioDevice {
+    // cpal's event loop never returns, so it is left by unwinding once the callback
+    // returns false. the stream is not fed anymore and the device has to be opened again
+    fn run<F: FnMut(&mut [f32]) -> bool + Send>(&self, mut callback: F) {
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            self.event_loop.run(move |_stream_id, stream_data| {
+                let running = match stream_data {
+                    cpal::StreamData::Output {
+                        buffer: UnknownTypeOutputBuffer::F32(mut buffer),
+                    } => callback(&mut buffer),
+                    _ => true,
+                };
+                if !running {
+                    panic::resume_unwind(Box::new(Stopped));
+                }
             });
+        }));
+        if let Err(payload) = result {
+            if !payload.is::<Stopped>() {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+// runs callbacks without any hardware and keeps everything written into buffers
+pub struct NullDevice {
+    pub sample_rate: u32,
+    pub buffer_size: usize,
+    pub count: usize,
+    output: Mutex<Vec<f32>>,
+}
+
+impl NullDevice {
+    pub fn new(sample_rate: u32, buffer_size: usize, count: usize) -> NullDevice {
+        NullDevice {
+            sample_rate,
+            buffer_size,
+            count,
+            output: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn output(&self) -> Vec<f32> {
+        self.output.lock().unwrap().clone()
+    }
+}
+
+impl AudioOutput for NullDevice {
+    fn run<F: FnMut(&mut [f32]) -> bool + Send>(&self, mut callback: F) {
+        let mut output = self.output.lock().unwrap();
+        let mut buffer = vec![0.0; self.buffer_size * 2];
+        for _ in 0..self.count {
+            if !callback(&mut buffer) {
+                break;
+            }
+            output.extend_from_slice(&buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_devices_includes_the_default() {
+        // machines without audio like CI servers have no default device or cannot open it
+        let default = match cpal::default_output_device() {
+            Some(ref device) if device.supported_output_formats().is_ok() => device.name(),
+            _ => return,
+        };
+        assert!(AudioDevice::list_devices().contains(&default));
+    }
+
+    #[test]
+    fn unknown_device_is_an_error() {
+        match AudioDevice::with_device("no such device", 44100) {
+            Err(AudioDeviceError::DeviceNotFound(name)) => assert_eq!(name, "no such device"),
+            _ => panic!("expected DeviceNotFound"),
+        }
     }
 }
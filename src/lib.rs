@@ -2,8 +2,16 @@ extern crate cpal;
 extern crate num;
 extern crate rand;
 
+#[cfg(feature = "analysis")]
+pub mod analysis;
 pub mod audiodevice;
+#[cfg(feature = "midi")]
+pub mod midi;
+#[cfg(feature = "midi_file")]
+pub mod midi_file;
 pub mod musical_time;
+#[cfg(feature = "osc_control")]
+pub mod osc_control;
 pub mod soundsystem;
 pub mod tapirlisp;
 pub mod ugens;
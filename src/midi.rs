@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use crate::musical_time::event::{Event, Freq, Velocity};
+use crate::musical_time::time::Pos;
+use crate::musical_time::utils::note_to_freq;
+use crate::ugens::core::{Aug, ADSR, UG};
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+
+// where ALSA puts raw MIDI ports, e.g. `/dev/snd/midiC1D0` for `hw:1,0`
+const RAW_MIDI_DIR: &str = "/dev/snd";
+
+#[derive(Debug)]
+pub enum MidiInputError {
+    PortNotFound(String),
+    CannotOpenPort(String),
+}
+
+impl fmt::Display for MidiInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiInputError::PortNotFound(name) => write!(f, "MIDI port '{}' is not found", name),
+            MidiInputError::CannotOpenPort(name) => {
+                write!(f, "Cannot open MIDI port '{}'", name)
+            }
+        }
+    }
+}
+
+impl Error for MidiInputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+// reads raw MIDI ports of ALSA on its own thread and sends note-on/note-off as events
+pub struct MidiInput;
+
+impl MidiInput {
+    pub fn ports() -> Vec<String> {
+        let mut ports: Vec<String> = match fs::read_dir(RAW_MIDI_DIR) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| match p.file_name().and_then(|n| n.to_str()) {
+                    Some(name) => name.starts_with("midi"),
+                    None => false,
+                })
+                .filter_map(|p| p.to_str().map(|s| s.to_string()))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        ports.sort();
+        ports
+    }
+
+    // `port` is a path of a raw MIDI port as listed by `ports()`. events are sent until
+    // the port is closed or the receiver is dropped
+    pub fn connect(port: &str) -> Result<Receiver<Event>, MidiInputError> {
+        let file = match fs::File::open(port) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(MidiInputError::PortNotFound(port.to_string()))
+            }
+            Err(_) => return Err(MidiInputError::CannotOpenPort(port.to_string())),
+        };
+        let (tx, rx) = channel();
+        thread::spawn(move || read_port(file, tx));
+        Ok(rx)
+    }
+}
+
+fn read_port(file: fs::File, tx: Sender<Event>) {
+    let mut parser = MessageParser::new();
+    for byte in BufReader::new(file).bytes() {
+        let byte = match byte {
+            Ok(byte) => byte,
+            Err(_) => return,
+        };
+        if let Some(message) = parser.push(byte) {
+            // live events are not placed in time, so they are at the start
+            let pos = Pos {
+                bar: 0,
+                beat: 0,
+                pos: 0.0,
+            };
+            if let Some(event) = to_event(&message, pos) {
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// assembles channel messages from a byte stream, following running status and
+// skipping system messages
+struct MessageParser {
+    status: Option<u8>,
+    data: Vec<u8>,
+}
+
+impl MessageParser {
+    fn new() -> MessageParser {
+        MessageParser {
+            status: None,
+            data: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> Option<Vec<u8>> {
+        if byte >= 0xf8 {
+            // real-time messages can come between any bytes
+            return None;
+        } else if byte >= 0xf0 {
+            self.status = None;
+            return None;
+        } else if byte >= 0x80 {
+            self.status = Some(byte);
+            self.data.clear();
+            return None;
+        }
+
+        let status = self.status?;
+        self.data.push(byte);
+        let len = match status & 0xf0 {
+            0xc0 | 0xd0 => 1,
+            _ => 2,
+        };
+        if self.data.len() < len {
+            return None;
+        }
+        let mut message = vec![status];
+        message.append(&mut self.data);
+        Some(message)
+    }
+}
+
+fn key_to_freq(key: u8) -> Freq {
+    // counted from the A below key 0 as note numbers can't go below A0
+    let n = key as u32 + 3;
+    note_to_freq(n % 12, n / 12) / 2.0
+}
+
+// translates a raw channel message into an event placed at `pos`
+pub fn to_event(message: &[u8], pos: Pos) -> Option<Event> {
+    if message.len() < 3 {
+        return None;
+    }
+    let (key, vel) = (message[1], message[2]);
+    match message[0] & 0xf0 {
+        NOTE_ON if vel > 0 => Some(Event::On(pos, key_to_freq(key), vel as Velocity)),
+        NOTE_ON | NOTE_OFF => Some(Event::Off(pos)),
+        _ => None,
+    }
+}
+
+// drives an oscillator and an envelope by an event like seq does
+pub fn apply_event(event: &Event, osc: &Aug, eg: &Aug) {
+    match event {
+        Event::On(_pos, freq, _vel) => {
+            if let UG::Osc(ref mut osc) = &mut osc.0.lock().unwrap().ug {
+                osc.set_freq(Aug::val(*freq));
+            }
+            if let UG::Eg(ref mut eg) = &mut eg.0.lock().unwrap().ug {
+                eg.set_state(ADSR::Attack, 0);
+            }
+        }
+        Event::Off(_pos) => {
+            if let UG::Eg(ref mut eg) = &mut eg.0.lock().unwrap().ug {
+                eg.set_state(ADSR::Release, 0);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn freq_of(event: &Event) -> Freq {
+        match event {
+            Event::On(_, freq, _) => *freq,
+            _ => panic!("expected a note-on"),
+        }
+    }
+
+    #[test]
+    fn note_on_maps_to_frequency() {
+        let pos = Pos {
+            bar: 0,
+            beat: 0,
+            pos: 0.0,
+        };
+        let a4 = to_event(&[NOTE_ON, 69, 100], pos.clone()).unwrap();
+        assert!((freq_of(&a4) - 440.0).abs() < 1e-9);
+        // on any channel
+        let c4 = to_event(&[NOTE_ON | 3, 60, 1], pos.clone()).unwrap();
+        assert!((freq_of(&c4) - 261.6256).abs() < 1e-3);
+        // zero velocity is a note-off by convention
+        assert!(matches!(
+            to_event(&[NOTE_ON, 60, 0], pos),
+            Some(Event::Off(_))
+        ));
+    }
+
+    #[test]
+    fn connect_reads_notes_with_running_status() {
+        let path = std::env::temp_dir().join(format!("tapirus-midi-{}", std::process::id()));
+        // clock ticks come between the bytes and the second note omits its status byte
+        fs::File::create(&path)
+            .unwrap()
+            .write_all(&[NOTE_ON, 69, 0xf8, 100, 81, 100, NOTE_OFF, 69, 0])
+            .unwrap();
+
+        let rx = MidiInput::connect(path.to_str().unwrap()).unwrap();
+        let events: Vec<Event> = rx.iter().collect();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert!((freq_of(&events[0]) - 440.0).abs() < 1e-9);
+        assert!((freq_of(&events[1]) - 880.0).abs() < 1e-9);
+        assert!(matches!(events[2], Event::Off(_)));
+    }
+
+    #[test]
+    fn connect_to_missing_port_is_an_error() {
+        assert!(matches!(
+            MidiInput::connect("/dev/snd/no-such-port"),
+            Err(MidiInputError::PortNotFound(_))
+        ));
+    }
+}
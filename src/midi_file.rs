@@ -0,0 +1,389 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use midly::num::{u15, u24, u28, u4, u7};
+use midly::{Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind};
+
+use crate::musical_time::event::{Message, Pitch, Velocity, MAX_VELOCITY};
+use crate::musical_time::time::{Measure, Pos};
+use crate::musical_time::utils::{midi_to_pitch, pitch_to_midi};
+use crate::ugens::core::Pattern;
+
+// microseconds per beat assumed when the file has no tempo (120 bpm)
+const DEFAULT_TEMPO: f64 = 500000.0;
+// resolution of exported files
+const TICKS_PER_BEAT: u16 = 480;
+
+#[derive(Debug)]
+pub enum MidiFileError {
+    CannotReadFile(String, String),
+    CannotWriteFile(String, String),
+    Malformed(String),
+    NoSuchTrack(usize),
+}
+
+impl fmt::Display for MidiFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiFileError::CannotReadFile(path, err) => {
+                write!(f, "cannot read MIDI file {:?}: {}", path, err)
+            }
+            MidiFileError::CannotWriteFile(path, err) => {
+                write!(f, "cannot write MIDI file {:?}: {}", path, err)
+            }
+            MidiFileError::Malformed(err) => write!(f, "malformed MIDI file: {}", err),
+            MidiFileError::NoSuchTrack(track) => write!(f, "there is no track #{}", track),
+        }
+    }
+}
+
+impl Error for MidiFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+struct Note {
+    key: u8,
+    vel: u8,
+    start: u64,
+    end: u64,
+}
+
+fn to_len(beats: f64) -> Pos {
+    Pos {
+        bar: 0,
+        beat: 0,
+        pos: beats,
+    }
+}
+
+// collects notes of a track in ticks; a note on a sounding key closes the previous one
+fn collect_notes(smf: &Smf, track: usize) -> Vec<Note> {
+    let mut notes: Vec<Note> = Vec::new();
+    let mut sounding: Vec<Note> = Vec::new();
+    let mut tick: u64 = 0;
+
+    for ev in smf.tracks[track].iter() {
+        tick += ev.delta.as_int() as u64;
+        if let TrackEventKind::Midi { message, .. } = ev.kind {
+            let (key, vel) = match message {
+                MidiMessage::NoteOn { key, vel } => (key.as_int(), vel.as_int()),
+                MidiMessage::NoteOff { key, .. } => (key.as_int(), 0),
+                _ => continue,
+            };
+            if let Some(idx) = sounding.iter().position(|n| n.key == key) {
+                let mut note = sounding.remove(idx);
+                note.end = tick;
+                notes.push(note);
+            }
+            if vel > 0 {
+                sounding.push(Note {
+                    key,
+                    vel,
+                    start: tick,
+                    end: tick,
+                });
+            }
+        }
+    }
+    for mut note in sounding.into_iter() {
+        note.end = tick;
+        notes.push(note);
+    }
+    notes.sort_by_key(|n| n.start);
+    notes
+}
+
+impl Pattern {
+    pub fn from_midi(path: &str, track: usize) -> Result<Pattern, MidiFileError> {
+        match fs::read(path) {
+            Ok(bytes) => Pattern::from_midi_bytes(&bytes, track),
+            Err(err) => Err(MidiFileError::CannotReadFile(
+                path.to_string(),
+                err.to_string(),
+            )),
+        }
+    }
+
+    // notes starting together become a chord and overlapping notes are cut at the next onset,
+    // so the pattern is monophonic apart from chords. gaps are filled with rests
+    pub fn from_midi_bytes(bytes: &[u8], track: usize) -> Result<Pattern, MidiFileError> {
+        let smf = match Smf::parse(bytes) {
+            Ok(smf) => smf,
+            Err(err) => return Err(MidiFileError::Malformed(err.to_string())),
+        };
+        if track >= smf.tracks.len() {
+            return Err(MidiFileError::NoSuchTrack(track));
+        }
+
+        let tempo = smf
+            .tracks
+            .iter()
+            .flat_map(|t| t.iter())
+            .find_map(|ev| match ev.kind {
+                TrackEventKind::Meta(MetaMessage::Tempo(t)) => Some(t.as_int() as f64),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_TEMPO);
+        let ticks_per_beat = match smf.header.timing {
+            Timing::Metrical(ppq) => ppq.as_int() as f64,
+            Timing::Timecode(fps, sub) => fps.as_f32() as f64 * sub as f64 * tempo / 1000000.0,
+        };
+        let beats = |ticks: u64| ticks as f64 / ticks_per_beat;
+
+        let notes = collect_notes(&smf, track);
+        let mut msgs = Vec::new();
+        let mut now: u64 = 0;
+        let mut idx = 0;
+        while idx < notes.len() {
+            let start = notes[idx].start;
+            let group_len = notes[idx..].iter().take_while(|n| n.start == start).count();
+            let next = notes.get(idx + group_len).map(|n| n.start);
+
+            if start > now {
+                msgs.push(Box::new(Message::Note(
+                    Pitch::Rest,
+                    to_len(beats(start - now)),
+                    MAX_VELOCITY,
+                )));
+                now = start;
+            }
+
+            let mut chord = Vec::new();
+            let mut end = start;
+            for n in notes[idx..idx + group_len].iter() {
+                let n_end = match next {
+                    Some(next) => n.end.min(next),
+                    None => n.end,
+                };
+                end = end.max(n_end);
+                if let Some(pitch) = midi_to_pitch(n.key) {
+                    let len = to_len(beats(n_end - start));
+                    chord.push(Message::Note(pitch, len, n.vel as Velocity));
+                }
+            }
+            // unmappable keys are left as gaps to be filled by the next rest
+            match chord.len() {
+                0 => (),
+                1 => {
+                    msgs.push(Box::new(chord.remove(0)));
+                    now = end;
+                }
+                _ => {
+                    msgs.push(Box::new(Message::Chord(chord)));
+                    now = end;
+                }
+            }
+            idx += group_len;
+        }
+        Ok(Pattern::new(msgs))
+    }
+
+    // writes a single-track file. `loop` becomes a marker and ends the track
+    pub fn to_midi(&self, path: &str, bpm: f64) -> Result<(), MidiFileError> {
+        let measure = Measure { beat: 4, note: 4 };
+        let to_ticks =
+            |len: &Pos| (Pattern::to_beats(len, &measure) * TICKS_PER_BEAT as f64).round() as u64;
+
+        // (tick, note on or not, key, velocity); `last` is the step ties extend
+        let mut notes: Vec<(u64, bool, u8, u8)> = Vec::new();
+        let mut now: u64 = 0;
+        let mut last: Option<(Vec<usize>, u64)> = None;
+        let mut looped = false;
+        for m in self.0.lock().unwrap().iter() {
+            match &**m {
+                Message::Note(pitch, len, vel) => {
+                    let len = to_ticks(len);
+                    let mut offs = Vec::new();
+                    if let Some(key) = pitch_to_midi(pitch) {
+                        notes.push((now, true, key, (*vel).min(MAX_VELOCITY) as u8));
+                        offs.push(notes.len());
+                        notes.push((now + len, false, key, 0));
+                    }
+                    now += len;
+                    last = Some((offs, len));
+                }
+                Message::Chord(chord) => {
+                    let mut offs = Vec::new();
+                    let mut chord_len = 0;
+                    for n in chord.iter() {
+                        if let Message::Note(pitch, len, vel) = n {
+                            let len = to_ticks(len);
+                            if let Some(key) = pitch_to_midi(pitch) {
+                                notes.push((now, true, key, (*vel).min(MAX_VELOCITY) as u8));
+                                offs.push(notes.len());
+                                notes.push((now + len, false, key, 0));
+                            }
+                            chord_len = chord_len.max(len);
+                        }
+                    }
+                    now += chord_len;
+                    last = Some((offs, chord_len));
+                }
+                Message::Tie => {
+                    if let Some((offs, len)) = &last {
+                        for idx in offs.iter() {
+                            notes[*idx].0 += len;
+                        }
+                        now += len;
+                    }
+                }
+                Message::Loop => {
+                    looped = true;
+                    break;
+                }
+            }
+        }
+        // note-offs go first so that repeated keys are not cut by their own previous note
+        notes.sort_by_key(|(tick, on, _, _)| (*tick, *on));
+
+        let tempo = (60000000.0 / bpm).round() as u32;
+        let mut track = vec![TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::new(tempo))),
+        }];
+        let mut prev: u64 = 0;
+        for (tick, on, key, vel) in notes.iter() {
+            let message = if *on {
+                MidiMessage::NoteOn {
+                    key: u7::new(*key),
+                    vel: u7::new(*vel),
+                }
+            } else {
+                MidiMessage::NoteOff {
+                    key: u7::new(*key),
+                    vel: u7::new(0),
+                }
+            };
+            track.push(TrackEvent {
+                delta: u28::new((tick - prev) as u32),
+                kind: TrackEventKind::Midi {
+                    channel: u4::new(0),
+                    message,
+                },
+            });
+            prev = *tick;
+        }
+        if looped {
+            track.push(TrackEvent {
+                delta: u28::new((now.max(prev) - prev) as u32),
+                kind: TrackEventKind::Meta(MetaMessage::Marker(b"loop")),
+            });
+            prev = now.max(prev);
+        }
+        track.push(TrackEvent {
+            delta: u28::new((now.max(prev) - prev) as u32),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        });
+
+        let mut smf = Smf::new(Header::new(
+            Format::SingleTrack,
+            Timing::Metrical(u15::new(TICKS_PER_BEAT)),
+        ));
+        smf.tracks.push(track);
+        match smf.save(path) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(MidiFileError::CannotWriteFile(
+                path.to_string(),
+                err.to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // c4 and e4 for a beat each, a beat of silence, then g4 for two beats at 480 ticks per beat
+    const THREE_NOTES: [u8; 61] = [
+        b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xe0, //
+        b'M', b'T', b'r', b'k', 0, 0, 0, 39, //
+        0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, //
+        0x00, 0x90, 60, 100, //
+        0x83, 0x60, 0x80, 60, 0, //
+        0x00, 0x90, 64, 80, //
+        0x83, 0x60, 0x80, 64, 0, //
+        0x83, 0x60, 0x90, 67, 100, //
+        0x87, 0x40, 0x80, 67, 0, //
+        0x00, 0xff, 0x2f, 0x00,
+    ];
+
+    // (note number and octave or None for rests, length in beats, velocity)
+    type Step = (Option<(u32, u32)>, f64, Velocity);
+
+    fn notes(pat: &Pattern) -> Vec<Step> {
+        let measure = Measure { beat: 4, note: 4 };
+        pat.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| match &**m {
+                Message::Note(Pitch::Pitch(n, o), len, vel) => {
+                    (Some((*n, *o)), Pattern::to_beats(len, &measure), *vel)
+                }
+                Message::Note(Pitch::Rest, len, vel) => {
+                    (None, Pattern::to_beats(len, &measure), *vel)
+                }
+                m => panic!("unexpected {:?}", m),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_notes_from_embedded_bytes() {
+        let pat = Pattern::from_midi_bytes(&THREE_NOTES, 0).unwrap();
+        assert_eq!(
+            notes(&pat),
+            vec![
+                (Some((3, 4)), 1.0, 100),
+                (Some((7, 4)), 1.0, 80),
+                (None, 1.0, MAX_VELOCITY),
+                (Some((10, 4)), 2.0, 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_files_and_tracks_are_errors() {
+        assert!(matches!(
+            Pattern::from_midi_bytes(&THREE_NOTES, 1),
+            Err(MidiFileError::NoSuchTrack(1))
+        ));
+        assert!(matches!(
+            Pattern::from_midi_bytes(b"not a MIDI file", 0),
+            Err(MidiFileError::Malformed(_))
+        ));
+        assert!(matches!(
+            Pattern::from_midi("no/such/file.mid", 0),
+            Err(MidiFileError::CannotReadFile(_, _))
+        ));
+    }
+
+    #[test]
+    fn keys_outside_patterns_are_not_mapped() {
+        assert!(midi_to_pitch(11).is_none());
+        assert!(matches!(midi_to_pitch(12), Some(Pitch::Pitch(3, 0))));
+        assert!(matches!(midi_to_pitch(107), Some(Pitch::Pitch(2, 8))));
+        assert!(midi_to_pitch(108).is_none());
+    }
+
+    #[test]
+    fn written_patterns_read_back_the_same() {
+        let notes_str = "c4:4 e4:4:80 a3:3 c5:5";
+        let msgs = Pattern::parse_str(format!("{} loop", notes_str)).unwrap();
+        let pat = Pattern::new(msgs);
+        let path =
+            std::env::temp_dir().join(format!("tapirus-{}-round-trip.mid", std::process::id()));
+        let path = path.to_str().unwrap();
+        pat.to_midi(path, 90.0).unwrap();
+
+        let read = Pattern::from_midi(path, 0).unwrap();
+        fs::remove_file(path).unwrap();
+        // `loop` is written as a marker, which is not read back
+        let expected = Pattern::new(Pattern::parse_str(notes_str.to_string()).unwrap());
+        assert_eq!(notes(&read), notes(&expected));
+    }
+}
@@ -1,11 +1,14 @@
 use crate::musical_time::time::Pos;
 
 pub type Freq = f64;
+pub type Velocity = u32;
+
+pub const MAX_VELOCITY: Velocity = 127;
 
 #[derive(Debug)]
 pub enum Event {
-    On(Pos, Freq),
-    Kick(Pos),
+    On(Pos, Freq, Velocity),
+    Kick(Pos, Velocity),
     Off(Pos),
     Loop(Pos),
 }
@@ -13,8 +16,8 @@ pub enum Event {
 impl Clone for Event {
     fn clone(&self) -> Self {
         match self {
-            Event::On(pos, freq) => Event::On(pos.clone(), *freq),
-            Event::Kick(pos) => Event::Kick(pos.clone()),
+            Event::On(pos, freq, vel) => Event::On(pos.clone(), *freq, *vel),
+            Event::Kick(pos, vel) => Event::Kick(pos.clone(), *vel),
             Event::Off(pos) => Event::Off(pos.clone()),
             Event::Loop(pos) => Event::Loop(pos.clone()),
         }
@@ -31,8 +34,18 @@ pub enum Pitch {
     Rest,
 }
 
+// reference pitch of A4; non-equal temperaments tune the scale root from it in equal temperament
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tuning {
+    EqualTemperament(Freq),
+    JustIntonation(Freq),
+    Pythagorean(Freq),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
-    Note(Pitch, Pos),
+    Note(Pitch, Pos, Velocity),
+    Chord(Vec<Message>),
+    Tie,
     Loop,
 }
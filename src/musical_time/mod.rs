@@ -1,3 +1,4 @@
 pub mod event;
+pub mod scale;
 pub mod time;
 pub mod utils;
@@ -0,0 +1,33 @@
+pub enum Scale {
+    Chromatic,
+    Major,
+    Minor,
+}
+
+impl Scale {
+    pub fn parse(s: &str) -> Option<Scale> {
+        match s {
+            "chromatic" => Some(Scale::Chromatic),
+            "major" => Some(Scale::Major),
+            "minor" => Some(Scale::Minor),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Scale::Chromatic => "chromatic",
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+        }
+    }
+
+    // semitones from the root within an octave
+    pub fn degrees(&self) -> &[i32] {
+        match self {
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+        }
+    }
+}
@@ -32,6 +32,13 @@ pub struct Transport {
     pub bpm: f64,
     pub measure: Measure,
     pub pos: Pos,
+    // when `loop_end` is set, `pos` jumps back to `loop_start` on reaching it.
+    // `tick` keeps counting so units never see the same tick twice
+    pub loop_start: Pos,
+    pub loop_end: Option<Pos>,
+    // time signature changes sorted by bar, each one taking effect at the start of its bar.
+    // `measure` follows the one active at `pos`
+    pub signatures: Vec<(Pos, Measure)>,
 }
 
 pub trait PosOps<T> {
@@ -51,7 +58,7 @@ impl PosOps<Pos> for Pos {
         let beat_diff = self.beat + other.beat + pos_diff.trunc() as u64;
 
         let new_pos = pos_diff.fract();
-        let new_beat = beat_diff % measure.note;
+        let new_beat = beat_diff % measure.beat;
         let new_bar = self.bar + other.bar + (beat_diff / measure.beat);
 
         Pos {
@@ -152,7 +159,157 @@ impl Transport {
                 beat: 0,
                 pos: 0.0,
             },
+            loop_start: Pos {
+                bar: 0,
+                beat: 0,
+                pos: 0.0,
+            },
+            loop_end: None,
+            signatures: Vec::new(),
+        }
+    }
+
+    // a copy of this transport moved to `tick`, with `pos` computed as `inc()` would do
+    pub fn at(&self, tick: u64) -> Transport {
+        let beats = tick as f64 * self.bpm / 60.0 / self.sample_rate as f64;
+        let pos = self.wrap(beats);
+        Transport {
+            sample_rate: self.sample_rate,
+            tick,
+            bpm: self.bpm,
+            measure: self.measure_at(pos.bar),
+            pos,
+            loop_start: self.loop_start.clone(),
+            loop_end: self.loop_end.clone(),
+            signatures: self.signatures.clone(),
+        }
+    }
+
+    pub fn set_loop(&mut self, start: Pos, end: Pos) {
+        self.loop_start = start;
+        self.loop_end = Some(end);
+    }
+
+    pub fn clear_loop(&mut self) {
+        self.loop_end = None;
+    }
+
+    // switches to `measure` from the bar of `pos` on. the signature before the first change
+    // is the current `measure`, which should be set before adding changes
+    pub fn set_signature(&mut self, pos: Pos, measure: Measure) {
+        if self.signatures.is_empty() {
+            let origin = Pos {
+                bar: 0,
+                beat: 0,
+                pos: 0.0,
+            };
+            self.signatures.push((origin, self.measure.clone()));
         }
+        let pos = Pos {
+            bar: pos.bar,
+            beat: 0,
+            pos: 0.0,
+        };
+        self.signatures.retain(|(p, _)| p.bar != pos.bar);
+        let idx = self
+            .signatures
+            .iter()
+            .take_while(|(p, _)| p.bar < pos.bar)
+            .count();
+        self.signatures.insert(idx, (pos, measure));
+        self.measure = self.measure_at(self.pos.bar);
+    }
+
+    pub fn clear_signatures(&mut self) {
+        if let Some((_, measure)) = self.signatures.first() {
+            self.measure = measure.clone();
+        }
+        self.signatures.clear();
+    }
+
+    // the time signature active at `bar`
+    pub fn measure_at(&self, bar: u64) -> Measure {
+        match self.signatures.iter().rev().find(|(p, _)| p.bar <= bar) {
+            Some((_, measure)) => measure.clone(),
+            None => self.measure.clone(),
+        }
+    }
+
+    pub fn sec_to_samples(&self, sec: f64) -> u64 {
+        (self.sample_rate as f64 * sec) as u64
+    }
+
+    // e.g. a quarter note (one beat) is 22050 samples at 120 bpm and 44100 Hz
+    pub fn note_len_to_samples(&self, len: &Pos) -> u64 {
+        self.sec_to_samples(self.to_beats(len) * 60.0 / self.bpm)
+    }
+
+    // bars before `pos` are counted with the signature each one was in
+    fn to_beats(&self, pos: &Pos) -> f64 {
+        let mut beats = 0;
+        let mut bar = 0;
+        let mut measure = self.measure_at(0);
+        for (p, m) in self.signatures.iter().skip(1) {
+            if p.bar >= pos.bar {
+                break;
+            }
+            beats += (p.bar - bar) * measure.beat;
+            bar = p.bar;
+            measure = m.clone();
+        }
+        beats += (pos.bar - bar) * measure.beat + pos.beat;
+        beats as f64 + pos.pos
+    }
+
+    fn beats_to_pos(&self, beats: f64) -> Pos {
+        let mut beats = beats;
+        let mut bar = 0;
+        let mut measure = self.measure_at(0);
+        for (p, m) in self.signatures.iter().skip(1) {
+            let span = ((p.bar - bar) * measure.beat) as f64;
+            if beats < span {
+                break;
+            }
+            beats -= span;
+            bar = p.bar;
+            measure = m.clone();
+        }
+        let origin = Pos {
+            bar,
+            beat: 0,
+            pos: 0.0,
+        };
+        origin.add(beats, &measure)
+    }
+
+    // folds a position in beats into the loop region, keeping what passed over `loop_end`
+    fn wrap(&self, beats: f64) -> Pos {
+        if let Some(end) = &self.loop_end {
+            let start = self.to_beats(&self.loop_start);
+            let end = self.to_beats(end);
+            if end > start && beats >= end {
+                let beats = start + (beats - end) % (end - start);
+                return self.beats_to_pos(beats);
+            }
+        }
+        self.beats_to_pos(beats)
+    }
+
+    pub fn rewind(&mut self) {
+        self.tick = 0;
+        self.pos = Pos {
+            bar: 0,
+            beat: 0,
+            pos: 0.0,
+        };
+        self.measure = self.measure_at(0);
+    }
+}
+
+// 44100 Hz, 120 bpm and 4/4 from the beginning
+impl Default for Transport {
+    fn default() -> Transport {
+        Transport::new(44100)
     }
 }
 
@@ -161,7 +318,129 @@ impl Clock for Transport {
         self.tick += 1;
 
         // update pos
+        let bar = self.pos.bar;
         let beat_diff = self.bpm / 60.0 / self.sample_rate as f64;
         self.pos = self.pos.add(beat_diff, &self.measure);
+        if let Some(end) = &self.loop_end {
+            if self.pos >= *end {
+                self.pos = self.wrap(self.to_beats(&self.pos));
+            }
+        }
+        if self.pos.bar != bar && !self.signatures.is_empty() {
+            self.measure = self.measure_at(self.pos.bar);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beats(pos: &Pos) -> f64 {
+        (pos.bar * 4 + pos.beat) as f64 + pos.pos
+    }
+
+    #[test]
+    fn one_bar_loop_returns_to_bar_zero() {
+        let mut transport = Transport::default();
+        let bar = Pos {
+            bar: 1,
+            beat: 0,
+            pos: 0.0,
+        };
+        transport.set_loop(transport.loop_start.clone(), bar);
+
+        let step = transport.bpm / 60.0 / transport.sample_rate as f64;
+        let mut wrapped = 0;
+        let mut prev = beats(&transport.pos);
+        // a bar is 88200 samples at 120 bpm in 4/4
+        for _ in 0..88200 * 3 {
+            transport.inc();
+            assert_eq!(transport.pos.bar, 0);
+            let now = beats(&transport.pos);
+            // moves by one step everywhere, also across the loop point
+            let moved = if now < prev {
+                now + 4.0 - prev
+            } else {
+                now - prev
+            };
+            assert!((moved - step).abs() < 1e-9);
+            if now < prev {
+                wrapped += 1;
+            }
+            prev = now;
+        }
+        assert_eq!(wrapped, 3);
+        assert_eq!(transport.tick, 88200 * 3);
+    }
+
+    #[test]
+    fn quarter_note_is_22050_samples_at_120_bpm() {
+        let mut transport = Transport::default();
+        let quarter = Pos {
+            bar: 0,
+            beat: 1,
+            pos: 0.0,
+        };
+        assert_eq!(transport.note_len_to_samples(&quarter), 22050);
+
+        let bar = Pos {
+            bar: 1,
+            beat: 0,
+            pos: 0.0,
+        };
+        assert_eq!(transport.note_len_to_samples(&bar), 88200);
+        transport.measure = Measure { beat: 3, note: 4 };
+        assert_eq!(transport.note_len_to_samples(&bar), 66150);
+
+        transport.bpm = 60.0;
+        assert_eq!(transport.note_len_to_samples(&quarter), 44100);
+    }
+
+    #[test]
+    fn default_transport_starts_at_zero_in_4_4() {
+        let mut transport = Transport::default();
+        assert_eq!(transport.sample_rate, 44100);
+        assert_eq!(transport.bpm, 120.0);
+        assert_eq!((transport.measure.beat, transport.measure.note), (4, 4));
+        assert_eq!(transport.tick, 0);
+        assert_eq!(beats(&transport.pos), 0.0);
+        assert!(transport.loop_end.is_none());
+
+        // a beat is half a second
+        for _ in 0..22050 * 5 {
+            transport.inc();
+        }
+        assert_eq!(transport.tick, 22050 * 5);
+        // the second beat of the second bar, give or take rounding errors
+        assert!((beats(&transport.pos) - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn signature_change_shortens_bars_from_its_bar() {
+        let mut transport = Transport::default();
+        let bar2 = Pos {
+            bar: 2,
+            beat: 0,
+            pos: 0.0,
+        };
+        transport.set_signature(bar2, Measure { beat: 3, note: 4 });
+
+        // ticks where each bar starts, a beat being 22050 samples
+        let mut starts = vec![0];
+        let mut bar = 0;
+        for tick in 1..=22050 * 15 {
+            transport.inc();
+            if transport.pos.bar != bar {
+                bar = transport.pos.bar;
+                starts.push(tick);
+            }
+        }
+        let beats: Vec<f64> = starts
+            .windows(2)
+            .map(|w| ((w[1] - w[0]) as f64 / 22050.0).round())
+            .collect();
+        assert_eq!(beats, vec![4.0, 4.0, 3.0, 3.0]);
+        assert_eq!(transport.measure.beat, 3);
     }
 }
@@ -1,4 +1,4 @@
-use super::event::{Freq, Pitch};
+use super::event::{Freq, NoteNum, Octave, Pitch, Tuning};
 use super::time::{Measure, Pos};
 
 pub fn to_note(name: &str) -> Option<Pitch> {
@@ -115,9 +115,97 @@ pub fn to_str(pitch: &Pitch) -> String {
     s
 }
 
+// note numbers count semitones from A and octaves change at A; A4 is (0, 5)
+pub fn note_to_freq(note: NoteNum, octave: Octave) -> Freq {
+    440.0 * 2.0f64.powf(note as f64 / 12.0 + (octave as f64) - 5.0)
+}
+
+// returns the nearest note and its deviation in cents
+pub fn freq_to_note(freq: Freq) -> (NoteNum, Octave, f64) {
+    let semitones = 12.0 * (freq / 440.0).log2() + 60.0;
+    let nearest = semitones.round().max(0.0);
+    let cents = (semitones - nearest) * 100.0;
+    let nearest = nearest as u32;
+    (nearest % 12, nearest / 12, cents)
+}
+
+// MIDI keys to pitches. A0 (key 21) is Pitch(0, 1), and only keys from c0 (12) to
+// b7 (107) are mapped as notes outside of them cannot be written in patterns
+pub fn midi_to_pitch(key: u8) -> Option<Pitch> {
+    if !(12..=107).contains(&key) {
+        None
+    } else {
+        let n = key as u32 - 9;
+        Some(Pitch::Pitch(n % 12, n / 12))
+    }
+}
+
+// the inverse of `midi_to_pitch`; kicks are sent as the bass drum of General MIDI
+pub fn pitch_to_midi(pitch: &Pitch) -> Option<u8> {
+    match pitch {
+        Pitch::Pitch(n, o) => {
+            let key = o * 12 + n + 9;
+            if key <= 127 {
+                Some(key as u8)
+            } else {
+                None
+            }
+        }
+        Pitch::Kick => Some(36),
+        Pitch::Rest => None,
+    }
+}
+
 pub fn to_freq(pitch: &Pitch) -> Freq {
     if let Pitch::Pitch(n, o) = pitch {
-        440.0 * 2.0f64.powf(*n as f64 / 12.0 + (*o as f64) - 5.0)
+        note_to_freq(*n, *o)
+    } else {
+        440.0
+    }
+}
+
+const JUST_RATIOS: [f64; 12] = [
+    1.0,
+    16.0 / 15.0,
+    9.0 / 8.0,
+    6.0 / 5.0,
+    5.0 / 4.0,
+    4.0 / 3.0,
+    45.0 / 32.0,
+    3.0 / 2.0,
+    8.0 / 5.0,
+    5.0 / 3.0,
+    9.0 / 5.0,
+    15.0 / 8.0,
+];
+
+const PYTHAGOREAN_RATIOS: [f64; 12] = [
+    1.0,
+    256.0 / 243.0,
+    9.0 / 8.0,
+    32.0 / 27.0,
+    81.0 / 64.0,
+    4.0 / 3.0,
+    729.0 / 512.0,
+    3.0 / 2.0,
+    128.0 / 81.0,
+    27.0 / 16.0,
+    16.0 / 9.0,
+    243.0 / 128.0,
+];
+
+// `root` is the note number of the scale root; equal temperament ignores it
+pub fn to_tuned_freq(pitch: &Pitch, tuning: &Tuning, root: NoteNum) -> Freq {
+    if let Pitch::Pitch(n, o) = pitch {
+        let (reference, ratios) = match tuning {
+            Tuning::EqualTemperament(r) => return r / 440.0 * note_to_freq(*n, *o),
+            Tuning::JustIntonation(r) => (r, &JUST_RATIOS),
+            Tuning::Pythagorean(r) => (r, &PYTHAGOREAN_RATIOS),
+        };
+        let interval = (12 * o + n) as i64 - root as i64;
+        let octave = interval.div_euclid(12) as i32;
+        let degree = interval.rem_euclid(12) as usize;
+        reference / 440.0 * note_to_freq(root, 0) * 2.0f64.powi(octave) * ratios[degree]
     } else {
         440.0
     }
@@ -146,3 +234,59 @@ pub fn to_len(p: &Pos, measure: &Measure) -> String {
     let len = (beat_pos / 0.125).log(4.0) * 2.0;
     len.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a4_is_440hz() {
+        assert_eq!(note_to_freq(0, 5), 440.0);
+        let (note, octave, cents) = freq_to_note(440.0);
+        assert_eq!((note, octave), (0, 5));
+        assert!(cents.abs() < 1e-9);
+    }
+
+    #[test]
+    fn middle_c() {
+        // c4 comes three semitones after a3
+        assert!((note_to_freq(3, 4) - 261.6256).abs() < 1e-3);
+        let (note, octave, _) = freq_to_note(261.6256);
+        assert_eq!((note, octave), (3, 4));
+    }
+
+    #[test]
+    fn detuned_freq_has_cents_deviation() {
+        let (note, octave, cents) = freq_to_note(445.0);
+        assert_eq!((note, octave), (0, 5));
+        assert!((cents - 19.56).abs() < 0.01);
+        let (note, octave, cents) = freq_to_note(435.0);
+        assert_eq!((note, octave), (0, 5));
+        assert!((cents + 19.78).abs() < 0.01);
+    }
+
+    fn cents(from: Freq, to: Freq) -> f64 {
+        1200.0 * (to / from).log2()
+    }
+
+    #[test]
+    fn just_major_third_is_narrower_than_equal() {
+        // c4 as the root and e4 above it
+        let (root, third) = (Pitch::Pitch(3, 4), Pitch::Pitch(7, 4));
+        let equal = Tuning::EqualTemperament(440.0);
+        let just = Tuning::JustIntonation(440.0);
+
+        let equal_third = cents(
+            to_tuned_freq(&root, &equal, 3),
+            to_tuned_freq(&third, &equal, 3),
+        );
+        let just_third = cents(
+            to_tuned_freq(&root, &just, 3),
+            to_tuned_freq(&third, &just, 3),
+        );
+        assert!((equal_third - 400.0).abs() < 1e-6);
+        assert!((just_third - 386.31).abs() < 0.01);
+        // the root stays where equal temperament puts it
+        assert!((to_tuned_freq(&root, &just, 3) - note_to_freq(3, 4)).abs() < 1e-9);
+    }
+}
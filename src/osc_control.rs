@@ -0,0 +1,214 @@
+use std::io;
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+
+use crate::tapirlisp::dump::shared_units;
+use crate::tapirlisp::types::{Env, Value};
+use crate::ugens::core::{Aug, Operate};
+
+#[derive(Debug, Clone)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct OscMessage {
+    pub addr: String,
+    pub args: Vec<OscArg>,
+}
+
+// OSC strings are null terminated and padded to 4 bytes
+fn read_str(buf: &[u8], pos: usize) -> Option<(String, usize)> {
+    let len = buf[pos..].iter().position(|b| *b == 0)?;
+    let s = String::from_utf8(buf[pos..pos + len].to_vec()).ok()?;
+    Some((s, pos + (len / 4 + 1) * 4))
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Option<u32> {
+    if pos + 4 > buf.len() {
+        return None;
+    }
+    let b = &buf[pos..pos + 4];
+    Some(u32::from(b[0]) << 24 | u32::from(b[1]) << 16 | u32::from(b[2]) << 8 | u32::from(b[3]))
+}
+
+pub fn decode(packet: &[u8]) -> Option<OscMessage> {
+    let (addr, pos) = read_str(packet, 0)?;
+    if !addr.starts_with('/') || pos > packet.len() {
+        return None;
+    }
+    let mut args = Vec::new();
+    if pos == packet.len() {
+        return Some(OscMessage { addr, args });
+    }
+
+    let (tags, mut pos) = read_str(packet, pos)?;
+    if !tags.starts_with(',') {
+        return None;
+    }
+    for tag in tags.chars().skip(1) {
+        match tag {
+            'i' => {
+                args.push(OscArg::Int(read_u32(packet, pos)? as i32));
+                pos += 4;
+            }
+            'f' => {
+                args.push(OscArg::Float(f32::from_bits(read_u32(packet, pos)?)));
+                pos += 4;
+            }
+            's' => {
+                if pos >= packet.len() {
+                    return None;
+                }
+                let (s, next) = read_str(packet, pos)?;
+                args.push(OscArg::Str(s));
+                pos = next;
+            }
+            _ => return None,
+        }
+    }
+    Some(OscMessage { addr, args })
+}
+
+// resolves `shared-N` to the Nth shared unit of `root` as numbered by `dump`, and other
+// names to units bound in `env`
+fn find_unit(name: &str, root: &Aug, env: &Env) -> Option<Aug> {
+    if let Some(idx) = name.strip_prefix("shared-") {
+        let idx = idx.parse::<usize>().ok()?;
+        return shared_units(root.clone()).get(idx).cloned();
+    }
+    match env.binding.get(name) {
+        Some(v) => match &**v {
+            Value::Unit(aug) => Some(aug.clone()),
+            _ => None,
+        },
+        None => None,
+    }
+}
+
+// sets `/shared-N/param` or `/name/param`; numbers go to `set` and strings to `set_str`
+pub fn dispatch(msg: &OscMessage, root: &Aug, env: &Env) -> bool {
+    let path: Vec<&str> = msg.addr.split('/').skip(1).collect();
+    if path.len() != 2 || path[0].is_empty() || path[1].is_empty() {
+        eprintln!("osc_control: malformed address {:?}", msg.addr);
+        return false;
+    }
+    let (name, pname) = (path[0], path[1]);
+
+    let mut aug = match find_unit(name, root, env) {
+        Some(aug) => aug,
+        None => {
+            eprintln!("osc_control: unknown unit {:?}", name);
+            return false;
+        }
+    };
+
+    let result = match msg.args.first() {
+        Some(OscArg::Int(v)) => aug.set(pname, Aug::val(*v as f64)),
+        Some(OscArg::Float(v)) => aug.set(pname, Aug::val(*v as f64)),
+        Some(OscArg::Str(s)) => aug.set_str(pname, s.to_string()),
+        None => {
+            eprintln!("osc_control: no value for {:?}", msg.addr);
+            return false;
+        }
+    };
+    match result {
+        Ok(_) => true,
+        Err(err) => {
+            eprintln!("osc_control: cannot set {:?}: {:?}", msg.addr, err);
+            false
+        }
+    }
+}
+
+pub fn listen(addr: &str, root: Aug, env: Arc<Mutex<Env>>) -> io::Result<()> {
+    let socket = UdpSocket::bind(addr)?;
+    let mut buf = [0; 1536];
+
+    loop {
+        let (size, _src) = socket.recv_from(&mut buf)?;
+        match decode(&buf[..size]) {
+            Some(msg) => {
+                dispatch(&msg, &root, &env.lock().unwrap());
+            }
+            None => eprintln!("osc_control: malformed packet"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tapirlisp::eval_all;
+    use crate::tapirlisp::sexp::read;
+
+    // a null terminated string padded to 4 bytes as OSC does
+    fn osc_str(s: &str) -> Vec<u8> {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.resize((s.len() / 4 + 1) * 4, 0);
+        bytes
+    }
+
+    fn patch(src: &str, env: &mut Env) -> Aug {
+        match eval_all(read(src.to_string()).unwrap(), env) {
+            Ok(Value::Unit(aug)) => aug,
+            _ => panic!("not a unit"),
+        }
+    }
+
+    #[test]
+    fn message_sets_shared_unit_param() {
+        let mut env = Env::default();
+        let root = patch("(def osc (sine 0 440)) (pan osc osc)", &mut env);
+
+        let mut packet = osc_str("/shared-0/freq");
+        packet.extend(osc_str(",f"));
+        packet.extend(&220.0f32.to_bits().to_be_bytes());
+        let msg = decode(&packet).unwrap();
+        assert!(dispatch(&msg, &root, &env));
+
+        let osc = shared_units(root.clone())[0].clone();
+        assert_eq!(osc.get("freq").unwrap().to_val(), Some(220.0));
+    }
+
+    #[test]
+    fn message_sets_unit_by_name() {
+        let mut env = Env::default();
+        let root = patch("(def osc (sine 0 440)) (pan 0 osc)", &mut env);
+
+        let msg = OscMessage {
+            addr: "/osc/freq".to_string(),
+            args: vec![OscArg::Int(330)],
+        };
+        assert!(dispatch(&msg, &root, &env));
+        let osc = match &**env.binding.get("osc").unwrap() {
+            Value::Unit(aug) => aug.clone(),
+            _ => panic!("not a unit"),
+        };
+        assert_eq!(osc.get("freq").unwrap().to_val(), Some(330.0));
+    }
+
+    #[test]
+    fn bad_messages_are_ignored() {
+        let mut env = Env::default();
+        let root = patch("(def osc (sine 0 440)) (pan osc osc)", &mut env);
+
+        for addr in [
+            "/shared-0",
+            "/shared-9/freq",
+            "/nothing/freq",
+            "/shared-0/nothing",
+        ]
+        .iter()
+        {
+            let msg = OscMessage {
+                addr: addr.to_string(),
+                args: vec![OscArg::Float(1.0)],
+            };
+            assert!(!dispatch(&msg, &root, &env));
+        }
+        assert!(decode(b"not osc\0").is_none());
+    }
+}
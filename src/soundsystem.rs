@@ -1,47 +1,325 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::musical_time::time::{Clock, Transport};
 use crate::ugens::core::{Aug, Proc};
+use crate::ugens::util::rewind_ugs;
 
-use crate::audiodevice::AudioDevice;
+use crate::audiodevice::AudioOutput;
+
+pub type AutomationHook = Box<dyn FnMut(&Transport) + Send>;
 
 pub struct SoundSystem {
     transport: Arc<Mutex<Transport>>,
     root_ug: Aug,
     lock: Arc<Mutex<bool>>,
+    running: Arc<AtomicBool>,
+    limiter: AtomicBool,
+    glitches: AtomicUsize,
+    meters: Arc<Mutex<(Meter, Meter)>>,
+    automation: Option<AutomationHook>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PeakRms {
+    pub peak: f64,
+    pub rms: f64,
+}
+
+// keeps the peak since created and the RMS over the last `len` samples
+pub struct Meter {
+    peak: f64,
+    window: VecDeque<f64>,
+    len: usize,
+    sum_sq: f64,
+}
+
+// length of the RMS window in seconds
+const METER_WINDOW: f64 = 0.3;
+
+// the limiter only shapes peaks above this so normal levels pass as they are
+const LIMITER_THRESHOLD: f64 = 0.9;
+
+// passes signals under the threshold as is and saturates above it smoothly toward 1.0
+fn soft_clip(v: f64) -> f64 {
+    let a = v.abs();
+    if a <= LIMITER_THRESHOLD {
+        v
+    } else {
+        let knee = 1.0 - LIMITER_THRESHOLD;
+        v.signum() * (LIMITER_THRESHOLD + knee * ((a - LIMITER_THRESHOLD) / knee).tanh())
+    }
+}
+
+// non-finite samples (e.g. from unstable filters) are muted and counted as glitches
+fn sanitize(v: f64, glitches: &AtomicUsize) -> f32 {
+    if v.is_finite() {
+        v.clamp(-1.0, 1.0) as f32
+    } else {
+        glitches.fetch_add(1, Ordering::SeqCst);
+        0.0
+    }
+}
+
+impl Meter {
+    pub fn new(len: usize) -> Meter {
+        Meter {
+            peak: 0.0,
+            window: VecDeque::with_capacity(len),
+            len: len.max(1),
+            sum_sq: 0.0,
+        }
+    }
+
+    fn push(&mut self, v: f64) {
+        self.peak = self.peak.max(v.abs());
+        self.window.push_back(v * v);
+        self.sum_sq += v * v;
+        if self.window.len() > self.len {
+            self.sum_sq -= self.window.pop_front().unwrap();
+        }
+    }
+
+    pub fn read(&self) -> PeakRms {
+        let rms = if self.window.is_empty() {
+            0.0
+        } else {
+            // running sums can go slightly below zero by rounding errors
+            (self.sum_sq.max(0.0) / self.window.len() as f64).sqrt()
+        };
+        PeakRms {
+            peak: self.peak,
+            rms,
+        }
+    }
 }
 
 impl SoundSystem {
     pub fn new(transport: Arc<Mutex<Transport>>, ug: Aug, lock: Arc<Mutex<bool>>) -> SoundSystem {
+        let len = (transport.lock().unwrap().sample_rate as f64 * METER_WINDOW) as usize;
         SoundSystem {
             transport: transport,
             root_ug: ug,
             lock: lock,
+            running: Arc::new(AtomicBool::new(true)),
+            limiter: AtomicBool::new(false),
+            glitches: AtomicUsize::new(0),
+            meters: Arc::new(Mutex::new((Meter::new(len), Meter::new(len)))),
+            automation: None,
         }
     }
 
-    pub fn run(&mut self, ad: &AudioDevice) {
-        ad.run(|mut buffer| {
+    pub fn running(&self) -> Arc<AtomicBool> {
+        self.running.clone()
+    }
+
+    pub fn start(&self) {
+        self.running.store(true, Ordering::SeqCst);
+    }
+
+    // makes `run` return after the buffer being rendered
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    pub fn set_limiter(&self, enabled: bool) {
+        self.limiter.store(enabled, Ordering::SeqCst);
+    }
+
+    // number of non-finite samples replaced with silence so far
+    pub fn glitches(&self) -> usize {
+        self.glitches.load(Ordering::SeqCst)
+    }
+
+    // levels of the left and right channels as written to the device
+    pub fn meters(&self) -> (PeakRms, PeakRms) {
+        let meters = self.meters.lock().unwrap();
+        (meters.0.read(), meters.1.read())
+    }
+
+    // for reading levels from other threads while running
+    pub fn meter_handle(&self) -> Arc<Mutex<(Meter, Meter)>> {
+        self.meters.clone()
+    }
+
+    // called before every sample with the transport of it, e.g. to sweep parameters
+    // with `Operate::set_str`. the unit graph is locked meanwhile
+    pub fn set_automation(&mut self, hook: AutomationHook) {
+        self.automation = Some(hook);
+    }
+
+    pub fn rewind(&self) {
+        let _lock = self.lock.lock().unwrap();
+        self.transport.lock().unwrap().rewind();
+        rewind_ugs(self.root_ug.clone());
+    }
+
+    pub fn run<D: AudioOutput>(&mut self, ad: &D) {
+        ad.run(|buffer| {
+            if !self.running.load(Ordering::SeqCst) {
+                return false;
+            }
+
+            let limiter = self.limiter.load(Ordering::SeqCst);
+            let mut meters = self.meters.lock().unwrap();
             let mut iter = buffer.iter_mut();
             loop {
                 let (mut l, mut r) = (0.0, 0.0);
                 if let Ok(_) = self.lock.lock() {
                     let mut transport = self.transport.lock().unwrap();
+                    if let Some(hook) = self.automation.as_mut() {
+                        hook(&transport);
+                    }
                     let s = self.root_ug.0.lock().unwrap().proc(&transport);
                     l = s.0;
                     r = s.1;
                     transport.inc();
                 }
+                if limiter {
+                    l = soft_clip(l);
+                    r = soft_clip(r);
+                }
 
                 match iter.next() {
-                    Some(lref) => *lref = l as f32,
+                    Some(lref) => {
+                        *lref = sanitize(l, &self.glitches);
+                        meters.0.push(*lref as f64);
+                    }
                     None => break,
                 }
                 match iter.next() {
-                    Some(rref) => *rref = r as f32,
+                    Some(rref) => {
+                        *rref = sanitize(r, &self.glitches);
+                        meters.1.push(*rref as f64);
+                    }
                     None => break,
                 }
             }
+            true
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audiodevice::NullDevice;
+    use crate::ugens::core::Operate;
+    use crate::ugens::misc::Gain;
+    use crate::ugens::osc::Sine;
+
+    fn system(ug: Aug) -> SoundSystem {
+        let transport = Arc::new(Mutex::new(Transport::default()));
+        SoundSystem::new(transport, ug, Arc::new(Mutex::new(true)))
+    }
+
+    #[test]
+    fn null_device_captures_a_constant() {
+        let device = NullDevice::new(44100, 64, 4);
+        system(Aug::val(0.25)).run(&device);
+        let output = device.output();
+        assert_eq!(output.len(), 64 * 2 * 4);
+        // units have nothing computed for tick 0 so the first frame is silent
+        assert_eq!(&output[..2], &[0.0, 0.0]);
+        assert!(output[2..].iter().all(|v| *v == 0.25));
+    }
+
+    #[test]
+    fn rewind_starts_over_from_the_first_sample() {
+        let mut system = system(Sine::new(Aug::val(0.0), Aug::val(440.0)));
+        let fresh = NullDevice::new(44100, 64, 1);
+        system.run(&fresh);
+        system.run(&NullDevice::new(44100, 64, 10));
+
+        system.rewind();
+        let rewound = NullDevice::new(44100, 64, 1);
+        system.run(&rewound);
+        assert_eq!(rewound.output(), fresh.output());
+    }
+
+    #[test]
+    fn limiter_keeps_loud_signals_in_bounds() {
+        for v in [1.5, 4.0, -3.0].iter() {
+            let limited = soft_clip(*v);
+            assert!(limited.abs() <= 1.0);
+            assert!(limited.abs() > LIMITER_THRESHOLD);
+        }
+        // shaped by the limiter rather than cut at 1.0 on writing
+        let mut system = system(Aug::val(1.2));
+        system.set_limiter(true);
+        let device = NullDevice::new(44100, 64, 2);
+        system.run(&device);
+        let limited = soft_clip(1.2) as f32;
+        assert!(limited < 1.0);
+        assert!(device.output()[2..].iter().all(|v| *v == limited));
+    }
+
+    #[test]
+    fn limiter_passes_quiet_signals() {
+        for v in [0.0, 0.5, -0.8, LIMITER_THRESHOLD].iter() {
+            assert_eq!(soft_clip(*v), *v);
+        }
+    }
+
+    #[test]
+    fn non_finite_samples_are_muted_and_counted() {
+        let mut muted = system(Aug::val(f64::NAN));
+        let device = NullDevice::new(44100, 64, 1);
+        muted.run(&device);
+        assert!(device.output().iter().all(|v| *v == 0.0));
+        // both channels of every frame but the first one, which is silent anyway
+        assert_eq!(muted.glitches(), 63 * 2);
+
+        let device = NullDevice::new(44100, 64, 1);
+        system(Aug::val(f64::INFINITY)).run(&device);
+        assert!(device.output().iter().all(|v| *v == 0.0));
+        // out of range but finite ones are clamped
+        let device = NullDevice::new(44100, 64, 1);
+        system(Aug::val(-3.0)).run(&device);
+        assert!(device.output()[2..].iter().all(|v| *v == -1.0));
+    }
+
+    #[test]
+    fn meters_follow_peak_and_rms_of_a_sine() {
+        // a period is 100 samples
+        let mut metered = system(Sine::new(Aug::val(0.0), Aug::val(441.0)));
+        assert_eq!(metered.meters().0.peak, 0.0);
+        assert_eq!(metered.meters().0.rms, 0.0);
+
+        // more than the RMS window of 0.3 seconds
+        metered.run(&NullDevice::new(44100, 100, 200));
+        let (l, r) = metered.meters();
+        for m in [l, r].iter() {
+            assert!((m.peak - 1.0).abs() < 1e-3);
+            assert!((m.rms - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        }
+
+        // the window forgets louder samples, unlike the peak
+        let mut meter = Meter::new(4);
+        meter.push(-1.0);
+        for _ in 0..4 {
+            meter.push(0.5);
+        }
+        assert_eq!(meter.read().peak, 1.0);
+        assert!((meter.read().rms - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn automation_hook_ramps_a_gain_each_sample() {
+        let gain = Gain::new(Aug::val(0.0), Aug::val(1.0));
+        let mut automated = system(gain.clone());
+        automated.set_automation(Box::new(move |transport: &Transport| {
+            let v = transport.tick as f64 / 1000.0;
+            let _ = gain.0.lock().unwrap().set_str("gain", v.to_string());
+        }));
+        let device = NullDevice::new(44100, 256, 1);
+        automated.run(&device);
+
+        let output = device.output();
+        for (tick, frame) in output.chunks(2).enumerate().skip(1) {
+            let expected = (tick as f64 / 1000.0) as f32;
+            assert_eq!(frame, &[expected, expected]);
+        }
+    }
+}
@@ -1,12 +1,21 @@
 use std::cmp::Ordering;
 use std::sync::Arc;
 
-use crate::ugens::core::{Aug, Dump, Slot, UgNode, Value};
+use crate::musical_time::event::{Pitch, Tuning};
+use crate::musical_time::utils::to_str;
+use crate::ugens::core::{Aug, Dump, Slot, Table, UgNode, Value};
 use crate::ugens::util::collect_shared_ugs;
 
+use super::sexp::{print, read, to_vec, Cons};
 use super::types::Env;
 
-fn dump_table(name: &String, vec: &Vec<f64>) -> String {
+// tables shorter than this are kept readable by `dump_compact`
+const COMPACT_TABLE_LEN: usize = 32;
+
+fn dump_table(name: &String, vec: &Vec<f64>, compact: bool) -> String {
+    if compact && vec.len() >= COMPACT_TABLE_LEN {
+        return format!("({} {})", name, Table::to_base64(vec));
+    }
     let mut s = String::new();
     s.push_str("(");
     s.push_str(&name[..]);
@@ -37,11 +46,17 @@ fn dump_list(name: &String, vec: &Vec<String>) -> String {
 }
 
 pub fn dump_value(v: &Value, shared: &Vec<Aug>) -> String {
+    write_value(v, shared, false)
+}
+
+fn write_value(v: &Value, shared: &Vec<Aug>, compact: bool) -> String {
     match v {
         Value::Number(n) => n.to_string(),
-        Value::Table(vals) => dump_table(&"table".to_string(), vals),
+        Value::Bool(b) => b.to_string(),
+        Value::Table(vals) => dump_table(&"table".to_string(), vals, compact),
         Value::Pattern(pat) => dump_list(&"pat".to_string(), pat),
-        Value::Ug(ug) => dump_unit(&ug.dump(shared), shared),
+        Value::Symbol(s) => s.to_string(),
+        Value::Ug(ug) => dump_aug(ug, shared, compact),
         Value::Shared(n, _aug) => format!("shared-{}", n),
     }
 }
@@ -51,13 +66,14 @@ fn dump_ug(
     slots: &Vec<Slot>,
     values: &Vec<Box<Value>>,
     shared: &Vec<Aug>,
+    compact: bool,
 ) -> String {
     let mut s = String::new();
     s.push_str("(");
     s.push_str(&name[..]);
     s.push_str(" ");
     for (i, u) in slots.iter().enumerate() {
-        let dump = dump_value(&u.value, shared);
+        let dump = write_value(&u.value, shared, compact);
         s.push_str(&dump[..]);
         if dump.len() != 0 && i != slots.len() - 1 || values.len() > 0 {
             s.push_str(" ");
@@ -65,7 +81,7 @@ fn dump_ug(
     }
     if values.len() > 0 {
         for (i, v) in values.iter().enumerate() {
-            s.push_str(&dump_value(&v, shared)[..]);
+            s.push_str(&write_value(&v, shared, compact)[..]);
             if i != values.len() - 1 {
                 s.push_str(" ");
             }
@@ -97,8 +113,10 @@ fn is_include(a: &Aug, b: &Aug) -> Ordering {
                 for v in values {
                     let _ = order.then(match *v {
                         Value::Number(_) => Ordering::Less,
+                        Value::Bool(_) => Ordering::Less,
                         Value::Table(_) => Ordering::Less,
                         Value::Pattern(_) => Ordering::Less,
+                        Value::Symbol(_) => Ordering::Less,
                         Value::Ug(aug) => is_include(a, &aug),
                         Value::Shared(_, aug) => is_include(a, &aug),
                     });
@@ -110,16 +128,45 @@ fn is_include(a: &Aug, b: &Aug) -> Ordering {
 }
 
 pub fn dump_unit(dump: &UgNode, shared: &Vec<Aug>) -> String {
+    write_unit(dump, shared, false)
+}
+
+fn write_unit(dump: &UgNode, shared: &Vec<Aug>, compact: bool) -> String {
     match dump {
-        UgNode::Val(v) => dump_value(v, shared),
-        UgNode::Ug(name, slots) => dump_ug(&name, slots, &Vec::new(), shared),
-        UgNode::UgRest(name, slots, _, values) => dump_ug(&name, slots, values, shared),
+        UgNode::Val(v) => write_value(v, shared, compact),
+        UgNode::Ug(name, slots) => dump_ug(&name, slots, &Vec::new(), shared, compact),
+        UgNode::UgRest(name, slots, _, values) => dump_ug(&name, slots, values, shared, compact),
+    }
+}
+
+// keeps the bypass state by wrapping the unit with `bypass`
+fn dump_aug(ug: &Aug, shared: &Vec<Aug>, compact: bool) -> String {
+    let dumped = write_unit(&ug.dump(shared), shared, compact);
+    if ug.is_bypassed() {
+        format!("(bypass {})", dumped)
+    } else {
+        dumped
     }
 }
 
 pub fn dump(ug: Aug, env: &Env) -> String {
-    let mut shared_units = collect_shared_ugs(ug.clone());
+    write(ug, env, false)
+}
+
+// same as `dump` but long tables are written in base64, which `table` reads back exactly
+pub fn dump_compact(ug: Aug, env: &Env) -> String {
+    write(ug, env, true)
+}
+
+// units referred more than once, in the order dumped as `shared-0`, `shared-1`...
+pub fn shared_units(ug: Aug) -> Vec<Aug> {
+    let mut shared_units = collect_shared_ugs(ug);
     shared_units.sort_by(is_include);
+    shared_units
+}
+
+fn write(ug: Aug, env: &Env, compact: bool) -> String {
+    let shared_units = shared_units(ug.clone());
 
     let mut tlisp_str = String::new();
     tlisp_str.push_str(";; environment\n");
@@ -130,15 +177,149 @@ pub fn dump(ug: Aug, env: &Env) -> String {
         env.transport.measure.beat, env.transport.measure.note
     );
     tlisp_str.push_str(&mes_str.to_string());
+    let (name, reference) = match env.tuning {
+        Tuning::EqualTemperament(r) => ("equal", r),
+        Tuning::JustIntonation(r) => ("just", r),
+        Tuning::Pythagorean(r) => ("pythagorean", r),
+    };
+    if name != "equal" || reference != 440.0 {
+        let root = to_str(&Pitch::Pitch(env.tuning_root, 5));
+        let root = root.trim_end_matches(|c: char| c.is_ascii_digit());
+        tlisp_str.push_str(&format!("(tuning {} {} {})\n", name, root, reference));
+    }
 
     tlisp_str.push_str("\n;; shared units\n");
     for (idx, su) in shared_units.iter().enumerate() {
-        let dumped = dump_unit(&su.0.lock().unwrap().dump(&shared_units), &shared_units);
+        let dumped = dump_aug(su, &shared_units, compact);
         tlisp_str.push_str(&format!("(def {} {})\n", format!("shared-{}", idx), dumped));
     }
 
     tlisp_str.push_str("\n;; unit graph\n");
-    let dumped = dump_unit(&ug.dump(&shared_units), &shared_units);
+    let dumped = dump_aug(&ug, &shared_units, compact);
     tlisp_str.push_str(&format!("{}\n", dumped));
     format!("{}", tlisp_str)
 }
+
+// lists containing other lists are broken into one element per line
+fn print_indented(exp: &Cons, indent: usize, depth: usize) -> String {
+    let elems = match exp {
+        Cons::Cons(_, _) => to_vec(exp),
+        _ => return print(exp),
+    };
+    if elems.iter().all(|e| !matches!(**e, Cons::Cons(_, _))) {
+        return print(exp);
+    }
+
+    let mut s = String::new();
+    s.push('(');
+    s.push_str(&print(&elems[0]));
+    for e in elems[1..].iter() {
+        s.push('\n');
+        s.push_str(&" ".repeat(indent * (depth + 1)));
+        s.push_str(&print_indented(e, indent, depth + 1));
+    }
+    s.push(')');
+    s
+}
+
+// same as `dump` but nested units are indented by `indent` spaces per level
+pub fn dump_pretty(ug: Aug, env: &Env, indent: usize) -> String {
+    let mut tlisp_str = String::new();
+    for line in dump(ug, env).lines() {
+        match read(line.to_string()) {
+            Ok(ref exps) if !exps.is_empty() && !line.starts_with(';') => {
+                for exp in exps.iter() {
+                    tlisp_str.push_str(&print_indented(exp, indent, 0));
+                    tlisp_str.push('\n');
+                }
+            }
+            _ => {
+                tlisp_str.push_str(line);
+                tlisp_str.push('\n');
+            }
+        }
+    }
+    tlisp_str
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tapirlisp::eval::eval_all;
+    use crate::tapirlisp::types::Value;
+    use crate::ugens::core::Operate;
+    use crate::ugens::core::TABLE_BASE64_PREFIX;
+
+    fn eval_unit(src: &str, env: &mut Env) -> Aug {
+        match eval_all(read(src.to_string()).unwrap(), env) {
+            Ok(Value::Unit(ug)) => ug,
+            _ => panic!("not a unit: {}", src),
+        }
+    }
+
+    #[test]
+    fn pretty_dump_reads_back_to_the_same_graph() {
+        let mut env = Env::default();
+        let src = "(def osc (sine 0 440)) (delay 0.3 0.5 0.5 (lpf 800 1 (pan osc osc)))";
+        let ug = eval_unit(src, &mut env);
+        let pretty = dump_pretty(ug.clone(), &env, 2);
+        assert!(pretty.contains("\n  (lpf"));
+        assert!(pretty.contains("\n    (pan"));
+
+        let mut reread_env = Env::default();
+        let reread = eval_unit(&pretty, &mut reread_env);
+        assert_eq!(dump(reread, &reread_env), dump(ug, &env));
+    }
+
+    #[test]
+    fn compact_tables_read_back_exactly() {
+        let data: Vec<f64> = (0..256).map(|n| (n as f64 / 3.0).sin() / 7.0).collect();
+        let encoded = Table::to_base64(&data);
+        assert!(encoded.starts_with(TABLE_BASE64_PREFIX));
+        assert_eq!(Table::parse_str(encoded), Some(data.clone()));
+
+        let mut env = Env::default();
+        let numbers: Vec<String> = data.iter().map(|v| v.to_string()).collect();
+        let src = format!("(wavetable (table {}) 0)", numbers.join(" "));
+        let unit = eval_unit(&src, &mut env);
+        let compact = dump_compact(unit.clone(), &env);
+        assert!(compact.contains(TABLE_BASE64_PREFIX));
+        assert!(compact.len() < dump(unit.clone(), &env).len());
+        let read_back = eval_unit(&compact, &mut env);
+        assert_eq!(dump(read_back, &env), dump(unit, &env));
+
+        // short tables stay readable
+        let short = eval_unit("(wavetable (table 0 0.5 1) 0)", &mut env);
+        assert_eq!(dump_compact(short.clone(), &env), dump(short, &env));
+    }
+
+    #[test]
+    fn boolean_slots_read_back_as_booleans() {
+        assert_eq!(
+            read("true false".to_string()).unwrap(),
+            vec![Box::new(Cons::Bool(true)), Box::new(Cons::Bool(false))]
+        );
+
+        let mut env = Env::default();
+        let unit = eval_unit("(oneshot (sine 0 440) (adsr 0 0.1 0 0) true)", &mut env);
+        let dumped = dump(unit.clone(), &env);
+        assert!(dumped.contains("(adsr 0 0.1 0 0) true)"));
+        let read_back = eval_unit(&dumped, &mut env);
+        assert_eq!(read_back.0.lock().unwrap().get_str("loop").unwrap(), "true");
+        assert_eq!(dump(read_back.clone(), &env), dumped);
+
+        assert!(read_back
+            .0
+            .lock()
+            .unwrap()
+            .set_str("loop", "false".to_string())
+            .is_ok());
+        assert!(dump(read_back.clone(), &env).contains("(adsr 0 0.1 0 0) false)"));
+        assert!(read_back
+            .0
+            .lock()
+            .unwrap()
+            .set_str("loop", "1".to_string())
+            .is_err());
+    }
+}
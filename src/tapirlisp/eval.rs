@@ -1,39 +1,100 @@
 use std::collections::VecDeque;
 
-use crate::musical_time::event::Message;
+use crate::musical_time::event::{Message, Pitch, Tuning, Velocity, MAX_VELOCITY};
+use crate::musical_time::scale::Scale;
 use crate::musical_time::utils::{to_note, to_pos};
 
-use crate::ugens::core::{Aug, Pattern, Table, UGen, UG};
-use crate::ugens::fx::{Delay, LPFilter};
-use crate::ugens::misc::{Add, Clip, Gain, Multiply, Offset, Out, Pan};
-use crate::ugens::osc::{OneshotOsc, Phase, Pulse, Rand, Saw, Sine, Tri, WaveTable};
-use crate::ugens::seq::{AdsrEg, Seq, Trigger};
+use crate::ugens::core::Value as UgValue;
+use crate::ugens::core::{Aug, Dump, Operate, Pattern, Slot, Table, UGen, UgNode, Walk, UG};
+use crate::ugens::fx::{
+    Allpass, Chorus, Comb, CombMode, Compressor, DCBlock, Delay, Flanger, Freeze, FreqShift,
+    HighShelf, LPFilter, LowShelf, MultiTapDelay, PingPong, PitchShift, WaveShaper, Widener,
+};
+use crate::ugens::misc::{
+    db_to_gain, Add, Arp, ArpMode, AvgMix, Clip, ClipMode, Dup, EdgeTrigger, EnvFollow, ExpScale,
+    Gain, Gate, Glide, Metronome, MidSide, Multiply, Offset, Out, Pan, Quantize, RingMod,
+    SampleHold, Slew, StepSeq, Stereo, Subtract, Swap, ToMono, XFade,
+};
+use crate::ugens::osc::{
+    BlPulse, Granular, KickDrum, Lfo, LfoShape, MorphTable, OneshotOsc, Phase, Pulse, Rand, Saw,
+    Sine, Sync, Tri, WaveTable, Waveform, WAVETABLE_DEFAULT_LEN,
+};
+use crate::ugens::seq::{AdsrEg, EnvTable, Seq, Trigger};
+use crate::ugens::util::detect_cycle;
 
+use super::dump::dump_unit;
 use super::sexp::{print, to_vec, Cons};
 use super::types::{Env, EvalError, Value};
 
-pub static TYPE_NAMES: [&str; 22] = [
+pub static TYPE_NAMES: [&str; 69] = [
     "pan",
+    "stereo",
+    "swap",
+    "mono",
+    "dup",
+    "ms",
     "clip",
     "offset",
     "gain",
     "+",
+    "avg",
     "*",
+    "-",
+    "xfade",
+    "ring",
+    "glide",
+    "follow",
+    "slew",
+    "quantize",
+    "expscale",
+    "metro",
+    "edge",
+    "sh",
+    "stepseq",
+    "arp",
+    "gate",
     "oneshot",
     "rand",
     "sine",
     "tri",
     "saw",
     "pulse",
+    "blpulse",
+    "lfo",
+    "osc",
+    "sync",
     "table",
     "phase",
     "wavetable",
+    "wavetable-from",
+    "morph",
+    "granular",
+    "kick",
     "pat",
+    "euclid",
+    "transpose",
+    "toscale",
     "trig",
     "adsr",
+    "envtable",
     "seq",
     "lpf",
+    "lowshelf",
+    "highshelf",
     "delay",
+    "pingpong",
+    "multitap",
+    "freeze",
+    "width",
+    "comp",
+    "chorus",
+    "fshift",
+    "pitchshift",
+    "allpass",
+    "comb",
+    "flanger",
+    "waveshaper",
+    "dcblock",
     "out",
 ];
 
@@ -58,23 +119,95 @@ fn make_pan(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     }
 }
 
-fn make_clip(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 3 {
+fn make_stereo(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(min)) => match eval(&args[1], env) {
-                Ok(Value::Unit(max)) => match eval(&args[2], env) {
-                    Ok(Value::Unit(src)) => Ok(Clip::new(min, max, src)),
-                    Ok(_v) => Err(EvalError::NotAug),
-                    Err(err) => Err(err),
-                },
-                Ok(_) => Err(EvalError::NotAug),
+            Ok(Value::Unit(left)) => match eval(&args[1], env) {
+                Ok(Value::Unit(right)) => Ok(Stereo::new(left, right)),
+                Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
-            Ok(_) => Err(EvalError::NotAug),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("stereo"), args))
+    }
+}
+
+fn make_swap(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => Ok(Swap::new(src)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("swap"), args))
+    }
+}
+
+fn make_mono(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => Ok(ToMono::new(src)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("mono"), args))
+    }
+}
+
+fn make_dup(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => Ok(Dup::new(src)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("dup"), args))
+    }
+}
+
+fn make_ms(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => Ok(MidSide::new(src)),
+            Ok(_v) => Err(EvalError::NotAug),
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("clip"), args))
+        Err(EvalError::FnWrongParams(String::from("ms"), args))
+    }
+}
+
+fn make_clip(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    // the mode can be omitted as `(clip min max src)` for hard clipping
+    let (mode, src) = match args.len() {
+        3 => (ClipMode::Hard, 2),
+        4 => match &*args[2] {
+            Cons::Symbol(name) => match ClipMode::parse(name) {
+                Some(mode) => (mode, 3),
+                None => return Err(EvalError::FnWrongParams(String::from("clip"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        },
+        _ => return Err(EvalError::FnWrongParams(String::from("clip"), args)),
+    };
+    match eval(&args[0], env) {
+        Ok(Value::Unit(min)) => match eval(&args[1], env) {
+            Ok(Value::Unit(max)) => match eval(&args[src], env) {
+                Ok(Value::Unit(src)) => Ok(Clip::new(min, max, mode, src)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        },
+        Ok(_) => Err(EvalError::NotAug),
+        Err(err) => Err(err),
     }
 }
 
@@ -115,11 +248,33 @@ fn make_add(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     for s in args.iter() {
         match eval(s, env) {
             Ok(Value::Unit(unit)) => v.push(unit),
+            Ok(Value::List(units)) => v.extend(units),
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        }
+    }
+    match constants(&v) {
+        Some(vals) if !vals.is_empty() => Ok(Aug::val(vals.iter().sum())),
+        _ => Ok(Add::new(v)),
+    }
+}
+
+fn make_avg(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    let mut v: Vec<Aug> = Vec::new();
+    for s in args.iter() {
+        match eval(s, env) {
+            Ok(Value::Unit(unit)) => v.push(unit),
+            Ok(Value::List(units)) => v.extend(units),
             Ok(_v) => return Err(EvalError::NotAug),
             Err(err) => return Err(err),
         }
     }
-    Ok(Add::new(v))
+    match constants(&v) {
+        Some(vals) if !vals.is_empty() => {
+            Ok(Aug::val(vals.iter().sum::<f64>() / vals.len() as f64))
+        }
+        _ => Ok(AvgMix::new(v)),
+    }
 }
 
 fn make_multiply(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
@@ -127,20 +282,88 @@ fn make_multiply(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError>
     for s in args.iter() {
         match eval(s, env) {
             Ok(Value::Unit(unit)) => v.push(unit),
+            Ok(Value::List(units)) => v.extend(units),
             Ok(_v) => return Err(EvalError::NotAug),
             Err(err) => return Err(err),
         }
     }
-    Ok(Multiply::new(v))
+    match constants(&v) {
+        Some(vals) if !vals.is_empty() => Ok(Aug::val(vals.iter().product())),
+        _ => Ok(Multiply::new(v)),
+    }
 }
 
-// oscillators
+fn make_subtract(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    let mut v: Vec<Aug> = Vec::new();
+    for s in args.iter() {
+        match eval(s, env) {
+            Ok(Value::Unit(unit)) => v.push(unit),
+            Ok(Value::List(units)) => v.extend(units),
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        }
+    }
+    if v.is_empty() {
+        return Err(EvalError::FnWrongParams(String::from("-"), args));
+    }
 
-fn make_oneshot(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 2 {
+    match constants(&v) {
+        Some(vals) if vals.len() == 1 => Ok(Aug::val(-vals[0])),
+        Some(vals) => Ok(Aug::val(vals[0] - vals[1..].iter().sum::<f64>())),
+        None if v.len() == 1 => Ok(Subtract::new(Aug::val(0.0), v.remove(0))),
+        None if v.len() == 2 => Ok(Subtract::new(v.remove(0), v.remove(0))),
+        None => {
+            let a = v.remove(0);
+            match constants(&v) {
+                Some(vals) => Ok(Subtract::new(a, Aug::val(vals.iter().sum()))),
+                None => Ok(Subtract::new(a, Add::new(v))),
+            }
+        }
+    }
+}
+
+fn make_divide(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    let mut v: Vec<Aug> = Vec::new();
+    for s in args.iter() {
+        match eval(s, env) {
+            Ok(Value::Unit(unit)) => v.push(unit),
+            Ok(Value::List(units)) => v.extend(units),
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        }
+    }
+    if v.len() < 2 {
+        return Err(EvalError::FnWrongParams(String::from("/"), args));
+    }
+
+    // only constant divisors are supported, so signals are scaled by the reciprocal
+    let divisor = match constants(&v[1..]) {
+        Some(vals) => vals.iter().product::<f64>(),
+        None => return Err(EvalError::FnWrongParams(String::from("/"), args)),
+    };
+    if divisor == 0.0 {
+        return Err(EvalError::FnWrongParams(String::from("/"), args));
+    }
+    match v[0].to_val() {
+        Some(n) => Ok(Aug::val(n / divisor)),
+        None => Ok(Multiply::new(vec![v.remove(0), Aug::val(1.0 / divisor)])),
+    }
+}
+
+// gives the values when every unit is a constant, so that arithmetics can be folded
+fn constants(units: &[Aug]) -> Option<Vec<f64>> {
+    units.iter().map(|u| u.to_val()).collect()
+}
+
+fn make_xfade(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(osc)) => match eval(&args[1], env) {
-                Ok(Value::Unit(eg)) => Ok(OneshotOsc::new(osc.clone(), eg.clone())),
+            Ok(Value::Unit(a)) => match eval(&args[1], env) {
+                Ok(Value::Unit(b)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(x)) => Ok(XFade::new(a, b, x)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
@@ -148,27 +371,31 @@ fn make_oneshot(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("oneshot"), args))
+        Err(EvalError::FnWrongParams(String::from("xfade"), args))
     }
 }
 
-fn make_rand(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 1 {
+fn make_ring(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(unit)) => Ok(Rand::new(unit.clone())),
+            Ok(Value::Unit(a)) => match eval(&args[1], env) {
+                Ok(Value::Unit(b)) => Ok(RingMod::new(a, b)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
             Ok(_v) => Err(EvalError::NotAug),
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("rand"), args))
+        Err(EvalError::FnWrongParams(String::from("ring"), args))
     }
 }
 
-fn make_sine(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_glide(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 2 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
-                Ok(Value::Unit(freq)) => Ok(Sine::new(init_ph, freq)),
+            Ok(Value::Unit(time)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(Glide::new(time, src)),
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
@@ -176,15 +403,19 @@ fn make_sine(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("sine"), args))
+        Err(EvalError::FnWrongParams(String::from("glide"), args))
     }
 }
 
-fn make_tri(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 2 {
+fn make_follow(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
-                Ok(Value::Unit(freq)) => Ok(Tri::new(init_ph, freq)),
+            Ok(Value::Unit(attack)) => match eval(&args[1], env) {
+                Ok(Value::Unit(release)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(EnvFollow::new(attack, release, src)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
@@ -192,15 +423,19 @@ fn make_tri(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("tri"), args))
+        Err(EvalError::FnWrongParams(String::from("follow"), args))
     }
 }
 
-fn make_saw(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 2 {
+fn make_slew(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
-                Ok(Value::Unit(freq)) => Ok(Saw::new(init_ph, freq)),
+            Ok(Value::Unit(rise)) => match eval(&args[1], env) {
+                Ok(Value::Unit(fall)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(Slew::new(rise, fall, src)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
@@ -208,16 +443,35 @@ fn make_saw(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("saw"), args))
+        Err(EvalError::FnWrongParams(String::from("slew"), args))
     }
 }
 
-fn make_pulse(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_quantize(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        let scale = match &*args[0] {
+            Cons::Symbol(name) => match Scale::parse(name) {
+                Some(scale) => scale,
+                None => return Err(EvalError::FnWrongParams(String::from("quantize"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
+        match (eval(&args[1], env), eval(&args[2], env)) {
+            (Ok(Value::Unit(root)), Ok(Value::Unit(src))) => Ok(Quantize::new(scale, root, src)),
+            (Err(err), _) | (_, Err(err)) => Err(err),
+            _ => Err(EvalError::NotAug),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("quantize"), args))
+    }
+}
+
+fn make_expscale(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 3 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
-                Ok(Value::Unit(freq)) => match eval(&args[2], env) {
-                    Ok(Value::Unit(duty)) => Ok(Pulse::new(init_ph, freq, duty)),
+            Ok(Value::Unit(min)) => match eval(&args[1], env) {
+                Ok(Value::Unit(max)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(ExpScale::new(min, max, src)),
                     Ok(_v) => Err(EvalError::NotAug),
                     Err(err) => Err(err),
                 },
@@ -228,56 +482,51 @@ fn make_pulse(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("pulse"), args))
+        Err(EvalError::FnWrongParams(String::from("expscale"), args))
     }
 }
 
-// wavetable oscillator
-
-fn make_table(args: Vec<Box<Cons>>, _env: &mut Env) -> Result<Aug, EvalError> {
-    let mut table = Vec::new();
-    if args.len() > 0 {
-        for s in args.iter() {
-            match **s {
-                Cons::Number(n) => table.push(n),
-                _ => return Err(EvalError::NotANumber(print(s))),
-            }
+fn make_metro(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(freq)) => match eval(&args[1], env) {
+                Ok(Value::Unit(accent)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(decay)) => Ok(Metronome::new(freq, accent, decay)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
         }
-        Ok(Aug::new(UGen::new(UG::Tab(Table::new(table)))))
     } else {
-        Err(EvalError::FnWrongParams("table".to_string(), args))
+        Err(EvalError::FnWrongParams(String::from("metro"), args))
     }
 }
 
-fn make_phase(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 1 {
+fn make_edge(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(osc)) => Ok(Phase::new(osc)),
+            Ok(Value::Unit(threshold)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(EdgeTrigger::new(threshold, src)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
             Ok(_v) => Err(EvalError::NotAug),
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("phase"), args))
+        Err(EvalError::FnWrongParams(String::from("edge"), args))
     }
 }
 
-fn make_wavetable(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_sh(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 2 {
-        match eval(&args[1], env) {
-            Ok(Value::Unit(ph)) => match eval(&args[0], env) {
-                Ok(Value::Unit(table)) => {
-                    let mut node_type = 0;
-                    match &table.0.lock().unwrap().ug {
-                        UG::Osc(_) => node_type = 1,
-                        UG::Tab(_) => node_type = 2,
-                        _ => (),
-                    };
-                    match node_type {
-                        1 => Ok(WaveTable::from_osc(table.clone(), ph, &env.transport)),
-                        2 => Ok(WaveTable::from_table(table.clone(), ph)),
-                        _ => return Err(EvalError::NotAug),
-                    }
-                }
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => match eval(&args[1], env) {
+                Ok(Value::Unit(trigger)) => Ok(SampleHold::new(src, trigger)),
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
             },
@@ -285,121 +534,967 @@ fn make_wavetable(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError>
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("wavetable"), args))
+        Err(EvalError::FnWrongParams(String::from("sh"), args))
     }
 }
 
-// sequencer
-
-pub fn make_msg(e: &Cons, _env: &mut Env) -> Result<Vec<Box<Message>>, EvalError> {
-    let mut ev = Vec::new();
-    match e {
-        Cons::Cons(name, cdr) => {
-            if let Cons::Symbol(pitch) = &**name {
-                if let Cons::Cons(len, _) = &**cdr {
-                    let len = match &**len {
-                        Cons::Number(l) => to_pos(*l as u32),
-                        _ => to_pos(4),
-                    };
-                    if let Some(note) = to_note(pitch) {
-                        ev.push(Box::new(Message::Note(note, len)));
-                    } else {
-                        return Err(EvalError::EvWrongParams(print(e)));
-                    }
-                } else {
-                    // without length
-                }
-            } else {
-                return Err(EvalError::EvWrongParams(print(e)));
+fn make_stepseq(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() >= 2 {
+        let trigger = match eval(&args[0], env) {
+            Ok(Value::Unit(trigger)) => trigger,
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        };
+        let mut values = Vec::new();
+        for exp in &args[1..] {
+            match eval(exp, env) {
+                Ok(Value::Unit(u)) => match u.to_val() {
+                    Some(v) => values.push(v),
+                    None => return Err(EvalError::FnWrongParams(String::from("stepseq"), args)),
+                },
+                Ok(_v) => return Err(EvalError::NotAug),
+                Err(err) => return Err(err),
             }
         }
-        Cons::Symbol(name) => match &name[..] {
-            "loop" => ev.push(Box::new(Message::Loop)),
-            name if name.contains(':') => {
-                if let Ok(msg) = Pattern::parse_str_1(name.clone()) {
-                    ev.push(Box::new(msg));
-                } else {
-                    return Err(EvalError::EvMalformedEvent(name.to_string()));
-                }
-            }
-            name => return Err(EvalError::EvUnknown(name.to_string())),
-        },
-        sexp => return Err(EvalError::EvMalformedEvent(print(sexp))),
+        Ok(StepSeq::new(trigger, values))
+    } else {
+        Err(EvalError::FnWrongParams(String::from("stepseq"), args))
     }
-    Ok(ev)
 }
 
-fn eval_msgs(events: Vec<Box<Cons>>, env: &mut Env) -> Result<Vec<Box<Message>>, EvalError> {
-    let mut ev: Vec<Box<Message>> = Vec::new();
-    for e in events.iter() {
-        match &mut make_msg(e, env) {
-            Ok(vec) => ev.append(vec),
-            Err(err) => return Err(err.clone()),
+fn make_arp(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() >= 3 {
+        let mode = match &*args[0] {
+            Cons::Symbol(name) => match ArpMode::parse(name) {
+                Some(mode) => mode,
+                None => return Err(EvalError::FnWrongParams(String::from("arp"), args)),
+            },
+            _ => return Err(EvalError::FnWrongParams(String::from("arp"), args)),
+        };
+        let rate = match eval(&args[1], env) {
+            Ok(Value::Unit(rate)) => rate,
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        };
+        // notes are given as note names or taken from patterns
+        let mut notes = Vec::new();
+        for exp in &args[2..] {
+            if let Cons::Symbol(name) = &**exp {
+                if let Some(pitch @ Pitch::Pitch(_, _)) = to_note(name) {
+                    notes.push(pitch);
+                    continue;
+                }
+            }
+            match eval(exp, env) {
+                Ok(Value::Unit(u)) => match &u.0.lock().unwrap().ug {
+                    UG::Pat(pat) => notes.extend(pat.pitches()),
+                    _ => return Err(EvalError::NotAPattern),
+                },
+                Ok(_v) => return Err(EvalError::NotAug),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Arp::new(
+            mode,
+            rate,
+            notes,
+            env.tuning.clone(),
+            env.tuning_root,
+        ))
+    } else {
+        Err(EvalError::FnWrongParams(String::from("arp"), args))
+    }
+}
+
+fn make_gate(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(pattern)) => Ok(Gate::new(pattern)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("gate"), args))
+    }
+}
+
+// oscillators
+
+fn make_oneshot(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 || args.len() == 3 {
+        let looping = match args.get(2).map(|a| &**a) {
+            Some(Cons::Bool(b)) => *b,
+            Some(_) => return Err(EvalError::FnWrongParams(String::from("oneshot"), args)),
+            None => false,
+        };
+        match eval(&args[0], env) {
+            Ok(Value::Unit(osc)) => match eval(&args[1], env) {
+                Ok(Value::Unit(eg)) => Ok(OneshotOsc::new(osc.clone(), eg.clone(), looping)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("oneshot"), args))
+    }
+}
+
+fn make_rand(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 || args.len() == 2 {
+        let seed = match args.get(1).map(|a| &**a) {
+            Some(Cons::Number(n)) if *n >= 0.0 => Some(*n as u64),
+            Some(_) => return Err(EvalError::FnWrongParams(String::from("rand"), args)),
+            None => None,
+        };
+        match eval(&args[0], env) {
+            Ok(Value::Unit(unit)) => Ok(Rand::new(unit.clone(), seed)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("rand"), args))
+    }
+}
+
+fn make_sine(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
+                Ok(Value::Unit(freq)) => Ok(Sine::new(init_ph, freq)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("sine"), args))
+    }
+}
+
+fn make_tri(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
+                Ok(Value::Unit(freq)) => Ok(Tri::new(init_ph, freq)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("tri"), args))
+    }
+}
+
+fn make_saw(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
+                Ok(Value::Unit(freq)) => Ok(Saw::new(init_ph, freq)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("saw"), args))
+    }
+}
+
+fn make_pulse(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
+                Ok(Value::Unit(freq)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(duty)) => Ok(Pulse::new(init_ph, freq, duty)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("pulse"), args))
+    }
+}
+
+fn make_blpulse(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(init_ph)) => match eval(&args[1], env) {
+                Ok(Value::Unit(freq)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(duty)) => Ok(BlPulse::new(init_ph, freq, duty)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("blpulse"), args))
+    }
+}
+
+fn make_lfo(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        let shape = match &*args[1] {
+            Cons::Symbol(name) => match LfoShape::parse(name) {
+                Some(shape) => shape,
+                None => return Err(EvalError::FnWrongParams(String::from("lfo"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
+        match (
+            eval(&args[0], env),
+            eval(&args[2], env),
+            eval(&args[3], env),
+        ) {
+            (Ok(Value::Unit(freq)), Ok(Value::Unit(min)), Ok(Value::Unit(max))) => {
+                Ok(Lfo::new(freq, shape, min, max))
+            }
+            (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => Err(err),
+            _ => Err(EvalError::NotAug),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("lfo"), args))
+    }
+}
+
+fn make_osc(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        let kind = match &*args[0] {
+            Cons::Symbol(name) => match Waveform::parse(name) {
+                Some(kind) => kind,
+                None => return Err(EvalError::FnWrongParams(String::from("osc"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
+        match (eval(&args[1], env), eval(&args[2], env)) {
+            (Ok(Value::Unit(init_ph)), Ok(Value::Unit(freq))) => {
+                Ok(crate::ugens::osc::make_osc(kind, init_ph, freq))
+            }
+            (Err(err), _) | (_, Err(err)) => Err(err),
+            _ => Err(EvalError::NotAug),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("osc"), args))
+    }
+}
+
+fn make_sync(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(master)) => match eval(&args[1], env) {
+                Ok(Value::Unit(slave)) => Ok(Sync::new(master, slave)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("sync"), args))
+    }
+}
+
+// wavetable oscillator
+
+fn make_table(args: Vec<Box<Cons>>, _env: &mut Env) -> Result<Aug, EvalError> {
+    let mut table = Vec::new();
+    if let [arg] = &args[..] {
+        if let Cons::Symbol(data) = &**arg {
+            return match Table::parse_str(data.to_string()) {
+                Some(table) => Ok(Aug::new(UGen::new(UG::Tab(Table::new(table))))),
+                None => Err(EvalError::NotANumber(data.to_string())),
+            };
+        }
+    }
+    if args.len() > 0 {
+        for s in args.iter() {
+            match **s {
+                Cons::Number(n) => table.push(n),
+                _ => return Err(EvalError::NotANumber(print(s))),
+            }
+        }
+        Ok(Aug::new(UGen::new(UG::Tab(Table::new(table)))))
+    } else {
+        Err(EvalError::FnWrongParams("table".to_string(), args))
+    }
+}
+
+fn make_table_file(args: Vec<Box<Cons>>, _env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match &*args[0] {
+            Cons::Symbol(name) => {
+                // the reader has no strings, so quotes around the path are just stripped
+                let path = name.trim_matches('"');
+                match Table::from_file(path) {
+                    Ok(table) => Ok(Aug::new(UGen::new(UG::Tab(Table::new(table))))),
+                    Err(err) => Err(EvalError::CannotReadFile(path.to_string(), err.to_string())),
+                }
+            }
+            c => Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        }
+    } else {
+        Err(EvalError::FnWrongParams("table-file".to_string(), args))
+    }
+}
+
+fn make_phase(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(osc)) => Ok(Phase::new(osc)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("phase"), args))
+    }
+}
+
+fn make_wavetable(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[1], env) {
+            Ok(Value::Unit(ph)) => match eval(&args[0], env) {
+                Ok(Value::Unit(table)) => {
+                    let mut node_type = 0;
+                    match &table.0.lock().unwrap().ug {
+                        UG::Osc(_) => node_type = 1,
+                        UG::Tab(_) => node_type = 2,
+                        _ => (),
+                    };
+                    match node_type {
+                        1 => Ok(WaveTable::from_osc(
+                            table.clone(),
+                            ph,
+                            WAVETABLE_DEFAULT_LEN,
+                            &env.transport,
+                        )),
+                        2 => Ok(WaveTable::from_table(table.clone(), ph)),
+                        _ => return Err(EvalError::NotAug),
+                    }
+                }
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("wavetable"), args))
+    }
+}
+
+// `(wavetable-from osc len)` renders one cycle of `osc` into a table of `len` points
+fn make_wavetable_from(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        let len = match &*args[1] {
+            Cons::Number(len) if *len >= 2.0 => *len as usize,
+            _ => {
+                return Err(EvalError::FnWrongParams(
+                    String::from("wavetable-from"),
+                    args,
+                ))
+            }
+        };
+        match eval(&args[0], env) {
+            Ok(Value::Unit(osc)) => {
+                let is_osc = matches!(osc.0.lock().unwrap().ug, UG::Osc(_));
+                if is_osc {
+                    let table = osc.to_table(len, &env.transport);
+                    Ok(Aug::new(UGen::new(UG::Tab(table))))
+                } else {
+                    Err(EvalError::NotAug)
+                }
+            }
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(
+            String::from("wavetable-from"),
+            args,
+        ))
+    }
+}
+
+fn make_morph(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() >= 3 {
+        match (eval(&args[0], env), eval(&args[1], env)) {
+            (Ok(Value::Unit(pos)), Ok(Value::Unit(ph))) => {
+                let mut tables = Vec::new();
+                for s in args[2..].iter() {
+                    match eval(s, env) {
+                        Ok(Value::Unit(table)) => match &table.0.lock().unwrap().ug {
+                            UG::Tab(_) => tables.push(table.clone()),
+                            _ => return Err(EvalError::NotAug),
+                        },
+                        Ok(_v) => return Err(EvalError::NotAug),
+                        Err(err) => return Err(err),
+                    }
+                }
+                Ok(MorphTable::new(tables, pos, ph))
+            }
+            (Err(err), _) | (_, Err(err)) => Err(err),
+            _ => Err(EvalError::NotAug),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("morph"), args))
+    }
+}
+
+fn make_granular(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 5 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(buffer)) => match eval(&args[1], env) {
+                Ok(Value::Unit(position)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(grainsize)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(density)) => match eval(&args[4], env) {
+                            Ok(Value::Unit(spread)) => {
+                                Ok(Granular::new(buffer, position, grainsize, density, spread))
+                            }
+                            Ok(_v) => Err(EvalError::NotAug),
+                            Err(err) => Err(err),
+                        },
+                        Ok(_v) => Err(EvalError::NotAug),
+                        Err(err) => Err(err),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("granular"), args))
+    }
+}
+
+fn make_kick(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(trigger)) => match eval(&args[1], env) {
+                Ok(Value::Unit(pitch)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(decay)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(click)) => Ok(KickDrum::new(trigger, pitch, decay, click)),
+                        Ok(_v) => Err(EvalError::NotAug),
+                        Err(err) => Err(err),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("kick"), args))
+    }
+}
+
+// sequencer
+
+pub fn make_msg(e: &Cons, _env: &mut Env) -> Result<Vec<Box<Message>>, EvalError> {
+    let mut ev = Vec::new();
+    match e {
+        Cons::Cons(name, cdr) => {
+            if let Cons::Symbol(pitch) = &**name {
+                if let Cons::Cons(len, rest) = &**cdr {
+                    let len = match &**len {
+                        Cons::Number(l) => to_pos(*l as u32),
+                        _ => to_pos(4),
+                    };
+                    let vel = match &**rest {
+                        Cons::Cons(vel, _) => match &**vel {
+                            Cons::Number(v) if *v >= 0.0 && *v <= MAX_VELOCITY as f64 => {
+                                *v as Velocity
+                            }
+                            _ => return Err(EvalError::EvWrongParams(print(e))),
+                        },
+                        _ => MAX_VELOCITY,
+                    };
+                    if let Some(note) = to_note(pitch) {
+                        ev.push(Box::new(Message::Note(note, len, vel)));
+                    } else {
+                        return Err(EvalError::EvWrongParams(print(e)));
+                    }
+                } else {
+                    // without length
+                }
+            } else {
+                return Err(EvalError::EvWrongParams(print(e)));
+            }
+        }
+        Cons::Symbol(name) => match &name[..] {
+            "loop" => ev.push(Box::new(Message::Loop)),
+            "~" => ev.push(Box::new(Message::Tie)),
+            name if name.contains(':') || name.starts_with('[') => {
+                if let Ok(msg) = Pattern::parse_str_1(name.clone()) {
+                    ev.push(Box::new(msg));
+                } else {
+                    return Err(EvalError::EvMalformedEvent(name.to_string()));
+                }
+            }
+            name => return Err(EvalError::EvUnknown(name.to_string())),
+        },
+        sexp => return Err(EvalError::EvMalformedEvent(print(sexp))),
+    }
+    Ok(ev)
+}
+
+fn eval_msgs(events: Vec<Box<Cons>>, env: &mut Env) -> Result<Vec<Box<Message>>, EvalError> {
+    let mut ev: Vec<Box<Message>> = Vec::new();
+    let mut chord: Option<(usize, String)> = None;
+    // errors are reported with the position of the event, counted from 1
+    for (idx, e) in events.iter().enumerate() {
+        let pos = idx + 1;
+        // a chord like `[c4:4 e4:4]` is read as separated symbols so join them here
+        let (pos, e) = match (&**e, chord.take()) {
+            (Cons::Symbol(name), Some((start, mut c))) => {
+                c.push(' ');
+                c.push_str(name);
+                if !name.ends_with(']') {
+                    chord = Some((start, c));
+                    continue;
+                }
+                (start, Cons::Symbol(c))
+            }
+            (Cons::Symbol(name), None) if name.starts_with('[') && !name.ends_with(']') => {
+                chord = Some((pos, name.to_string()));
+                continue;
+            }
+            (_, Some((start, c))) => {
+                let err = EvalError::EvMalformedEvent(c);
+                return Err(EvalError::EvPosition(start, Box::new(err)));
+            }
+            (e, None) => (pos, e.clone()),
+        };
+        match &mut make_msg(&e, env) {
+            Ok(vec) => ev.append(vec),
+            Err(err) => return Err(EvalError::EvPosition(pos, Box::new(err.clone()))),
+        }
+    }
+    match chord {
+        Some((start, c)) => {
+            let err = EvalError::EvMalformedEvent(c);
+            Err(EvalError::EvPosition(start, Box::new(err)))
+        }
+        None => Ok(ev),
+    }
+}
+
+fn make_pat(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    match eval_msgs(args, env) {
+        Ok(msgs) => Ok(Aug::new(UGen::new(UG::Pat(Pattern::new(msgs))))),
+        Err(err) => Err(err),
+    }
+}
+
+fn make_euclid(args: Vec<Box<Cons>>, _env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        match (&*args[0], &*args[1], &*args[2], &*args[3]) {
+            (Cons::Number(pulses), Cons::Number(steps), Cons::Symbol(pitch), Cons::Number(len)) => {
+                if to_note(pitch).is_none() {
+                    return Err(EvalError::EvUnknown(pitch.to_string()));
+                }
+                let msgs = Pattern::euclidean(*pulses as u32, *steps as u32, pitch, *len as u32);
+                Ok(Aug::new(UGen::new(UG::Pat(Pattern::new(msgs)))))
+            }
+            _ => Err(EvalError::FnWrongParams(String::from("euclid"), args)),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("euclid"), args))
+    }
+}
+
+// evaluates `sexp` to a pattern and makes a new one from it with `f`
+fn map_pattern(
+    sexp: &Cons,
+    env: &mut Env,
+    f: &dyn Fn(&Pattern) -> Pattern,
+) -> Result<Aug, EvalError> {
+    match eval(sexp, env)? {
+        Value::Unit(u) => match &u.0.lock().unwrap().ug {
+            UG::Pat(pat) => Ok(Aug::new(UGen::new(UG::Pat(f(pat))))),
+            _ => Err(EvalError::NotAPattern),
+        },
+        _ => Err(EvalError::NotAug),
+    }
+}
+
+fn make_transpose(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match &*args[0] {
+            Cons::Number(semitones) => {
+                let semitones = *semitones as i32;
+                map_pattern(&args[1], env, &|pat| pat.transpose(semitones))
+            }
+            _ => Err(EvalError::FnWrongParams(String::from("transpose"), args)),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("transpose"), args))
+    }
+}
+
+// `(toscale minor c pat)` snaps notes of `pat` into C minor
+fn make_toscale(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        let scale = match &*args[0] {
+            Cons::Symbol(name) => match Scale::parse(name) {
+                Some(scale) => scale,
+                None => return Err(EvalError::FnWrongParams(String::from("toscale"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
+        let root = match &*args[1] {
+            Cons::Symbol(name) => match to_note(name) {
+                Some(Pitch::Pitch(n, _)) => n,
+                _ => return Err(EvalError::EvUnknown(name.to_string())),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
+        map_pattern(&args[2], env, &|pat| pat.quantize_scale(&scale, root))
+    } else {
+        Err(EvalError::FnWrongParams(String::from("toscale"), args))
+    }
+}
+
+fn make_trig(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() > 0 {
+        // TODO: implement `(trig $eg $egs1 $egs2 ...)`
+        let mut egs = Vec::<Aug>::new();
+        for exp in &args[1..] {
+            match eval(exp, env) {
+                Ok(Value::Unit(eg)) => egs.push(eg),
+                Ok(_v) => return Err(EvalError::NotAug),
+                _err => return Err(EvalError::FnWrongParams(String::from("trig"), args)),
+            }
+        }
+        match eval(&args[0], env) {
+            Ok(Value::Unit(eg)) => Ok(Trigger::new(eg, egs)),
+            Ok(_v) => Err(EvalError::NotAug),
+            _err => Err(EvalError::FnWrongParams(String::from("trig"), args)),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("trig"), args))
+    }
+}
+
+fn make_adsr_eg(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(a)) => match eval(&args[1], env) {
+                Ok(Value::Unit(d)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(s)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(r)) => Ok(AdsrEg::new(a.clone(), d, s, r)),
+                        Ok(_v) => Err(EvalError::NotAug),
+                        _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("asdr"), args))
+    }
+}
+
+// the gate can be left out when `seq` or `trig` starts the envelope
+fn make_envtable(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 || args.len() == 3 {
+        let gate = match args.get(2) {
+            Some(gate) => match eval(gate, env)? {
+                Value::Unit(gate) => gate,
+                _ => return Err(EvalError::NotAug),
+            },
+            None => Aug::val(0.0),
+        };
+        match eval(&args[0], env) {
+            Ok(Value::Unit(table)) => match eval(&args[1], env) {
+                Ok(Value::Unit(duration)) => Ok(EnvTable::new(table, duration, gate)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("envtable"), args))
+    }
+}
+
+fn has_chords(pat: &Aug) -> bool {
+    match &pat.0.lock().unwrap().ug {
+        UG::Pat(pat) => pat.has_chords(),
+        _ => false,
+    }
+}
+
+fn make_seq(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        match eval(&args[1], env) {
+            Ok(Value::Unit(osc)) => match eval(&args[2], env) {
+                Ok(Value::Unit(osc_mod)) => match eval(&args[3], env) {
+                    Ok(Value::Unit(eg)) => match eval(&args[0], env) {
+                        Ok(Value::Unit(pat)) if has_chords(&pat) => Err(EvalError::ChordInSeq),
+                        Ok(Value::Unit(pat)) => Ok(Seq::new(
+                            pat,
+                            osc,
+                            osc_mod,
+                            eg,
+                            env.tuning.clone(),
+                            env.tuning_root,
+                            &env.transport,
+                        )),
+                        _ => Err(EvalError::NotAPattern),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("seq"), args))
+    }
+}
+
+// effects
+
+fn make_lpf(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(freq)) => match eval(&args[1], env) {
+                Ok(Value::Unit(q)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(LPFilter::new(freq, q, src)),
+                    _ => Err(EvalError::NotAPattern),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("lpf"), args))
+    }
+}
+
+fn make_lowshelf(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(freq)) => match eval(&args[1], env) {
+                Ok(Value::Unit(gain)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(LowShelf::new(freq, gain, src)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("lowshelf"), args))
+    }
+}
+
+fn make_highshelf(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(freq)) => match eval(&args[1], env) {
+                Ok(Value::Unit(gain)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(HighShelf::new(freq, gain, src)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("highshelf"), args))
+    }
+}
+
+fn make_delay(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 4 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(transport)) => match eval(&args[1], env) {
+                Ok(Value::Unit(feedback)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(mix)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(src)) => Ok(Delay::new(transport, feedback, mix, src, env)),
+                        Ok(_v) => Err(EvalError::NotAug),
+                        Err(_err) => Err(EvalError::NotAug),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("delay"), args))
+    }
+}
+
+fn make_pingpong(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 5 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(time_l)) => match eval(&args[1], env) {
+                Ok(Value::Unit(time_r)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(feedback)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(mix)) => match eval(&args[4], env) {
+                            Ok(Value::Unit(src)) => {
+                                Ok(PingPong::new(time_l, time_r, feedback, mix, src, env))
+                            }
+                            Ok(_v) => Err(EvalError::NotAug),
+                            Err(err) => Err(err),
+                        },
+                        Ok(_v) => Err(EvalError::NotAug),
+                        Err(err) => Err(err),
+                    },
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("pingpong"), args))
+    }
+}
+
+fn make_multitap(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if !args.is_empty() && args.len() % 2 == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => {
+                let mut taps: Vec<(Aug, Aug)> = Vec::new();
+                for pair in args[1..].chunks(2) {
+                    match (eval(&pair[0], env), eval(&pair[1], env)) {
+                        (Ok(Value::Unit(time)), Ok(Value::Unit(gain))) => taps.push((time, gain)),
+                        (Err(err), _) | (_, Err(err)) => return Err(err),
+                        _ => return Err(EvalError::NotAug),
+                    }
+                }
+                Ok(MultiTapDelay::new(src, taps, env))
+            }
+            Ok(_) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("multitap"), args))
+    }
+}
+
+fn make_freeze(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 3 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(record)) => match eval(&args[1], env) {
+                Ok(Value::Unit(length)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(Freeze::new(record, length, src, env)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
         }
-    }
-    Ok(ev)
-}
-
-fn make_pat(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    match eval_msgs(args, env) {
-        Ok(msgs) => Ok(Aug::new(UGen::new(UG::Pat(Pattern::new(msgs))))),
-        Err(err) => Err(err),
+    } else {
+        Err(EvalError::FnWrongParams(String::from("freeze"), args))
     }
 }
 
-fn make_trig(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() > 0 {
-        // TODO: implement `(trig $eg $egs1 $egs2 ...)`
-        let mut egs = Vec::<Aug>::new();
-        for exp in &args[1..] {
-            match eval(exp, env) {
-                Ok(Value::Unit(eg)) => egs.push(eg),
-                Ok(_v) => return Err(EvalError::NotAug),
-                _err => return Err(EvalError::FnWrongParams(String::from("trig"), args)),
-            }
-        }
+fn make_width(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(eg)) => Ok(Trigger::new(eg, egs)),
+            Ok(Value::Unit(amount)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(Widener::new(amount, src, env)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
             Ok(_v) => Err(EvalError::NotAug),
-            _err => Err(EvalError::FnWrongParams(String::from("trig"), args)),
+            Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("trig"), args))
+        Err(EvalError::FnWrongParams(String::from("width"), args))
     }
 }
 
-fn make_adsr_eg(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
-    if args.len() == 4 {
+fn make_comp(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 5 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(a)) => match eval(&args[1], env) {
-                Ok(Value::Unit(d)) => match eval(&args[2], env) {
-                    Ok(Value::Unit(s)) => match eval(&args[3], env) {
-                        Ok(Value::Unit(r)) => Ok(AdsrEg::new(a.clone(), d, s, r)),
+            Ok(Value::Unit(threshold)) => match eval(&args[1], env) {
+                Ok(Value::Unit(ratio)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(attack)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(release)) => match eval(&args[4], env) {
+                            Ok(Value::Unit(src)) => {
+                                Ok(Compressor::new(threshold, ratio, attack, release, src))
+                            }
+                            Ok(_v) => Err(EvalError::NotAug),
+                            Err(err) => Err(err),
+                        },
                         Ok(_v) => Err(EvalError::NotAug),
-                        _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+                        Err(err) => Err(err),
                     },
                     Ok(_v) => Err(EvalError::NotAug),
-                    _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+                    Err(err) => Err(err),
                 },
                 Ok(_v) => Err(EvalError::NotAug),
-                _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+                Err(err) => Err(err),
             },
             Ok(_v) => Err(EvalError::NotAug),
-            _err => Err(EvalError::FnWrongParams(String::from("adsr"), args)),
+            Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("asdr"), args))
+        Err(EvalError::FnWrongParams(String::from("comp"), args))
     }
 }
 
-fn make_seq(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_chorus(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 4 {
-        match eval(&args[1], env) {
-            Ok(Value::Unit(osc)) => match eval(&args[2], env) {
-                Ok(Value::Unit(osc_mod)) => match eval(&args[3], env) {
-                    Ok(Value::Unit(eg)) => match eval(&args[0], env) {
-                        Ok(Value::Unit(pat)) => Ok(Seq::new(pat, osc, osc_mod, eg, &env.transport)),
-                        _ => Err(EvalError::NotAPattern),
+        match eval(&args[0], env) {
+            Ok(Value::Unit(rate)) => match eval(&args[1], env) {
+                Ok(Value::Unit(depth)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(mix)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(src)) => Ok(Chorus::new(rate, depth, mix, src, env)),
+                        Ok(_v) => Err(EvalError::NotAug),
+                        Err(err) => Err(err),
                     },
                     Ok(_v) => Err(EvalError::NotAug),
                     Err(err) => Err(err),
@@ -411,19 +1506,50 @@ fn make_seq(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("seq"), args))
+        Err(EvalError::FnWrongParams(String::from("chorus"), args))
     }
 }
 
-// effects
+fn make_fshift(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(shift)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(FreqShift::new(shift, src)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("fshift"), args))
+    }
+}
 
-fn make_lpf(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_pitchshift(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(semitones)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(PitchShift::new(semitones, src, env)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("pitchshift"), args))
+    }
+}
+
+fn make_allpass(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 3 {
         match eval(&args[0], env) {
-            Ok(Value::Unit(freq)) => match eval(&args[1], env) {
-                Ok(Value::Unit(q)) => match eval(&args[2], env) {
-                    Ok(Value::Unit(src)) => Ok(LPFilter::new(freq, q, src)),
-                    _ => Err(EvalError::NotAPattern),
+            Ok(Value::Unit(delay)) => match eval(&args[1], env) {
+                Ok(Value::Unit(gain)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(src)) => Ok(Allpass::new(delay, gain, src, env)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
                 },
                 Ok(_v) => Err(EvalError::NotAug),
                 Err(err) => Err(err),
@@ -432,19 +1558,52 @@ fn make_lpf(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("lpf"), args))
+        Err(EvalError::FnWrongParams(String::from("allpass"), args))
     }
 }
 
-fn make_delay(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+fn make_comb(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
     if args.len() == 4 {
+        let mode = match &*args[2] {
+            Cons::Symbol(name) => match CombMode::parse(name) {
+                Some(mode) => mode,
+                None => return Err(EvalError::FnWrongParams(String::from("comb"), args)),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        };
         match eval(&args[0], env) {
-            Ok(Value::Unit(transport)) => match eval(&args[1], env) {
-                Ok(Value::Unit(feedback)) => match eval(&args[2], env) {
-                    Ok(Value::Unit(mix)) => match eval(&args[3], env) {
-                        Ok(Value::Unit(src)) => Ok(Delay::new(transport, feedback, mix, src, env)),
+            Ok(Value::Unit(delay)) => match eval(&args[1], env) {
+                Ok(Value::Unit(gain)) => match eval(&args[3], env) {
+                    Ok(Value::Unit(src)) => Ok(Comb::new(delay, gain, mode, src, env)),
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                },
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("comb"), args))
+    }
+}
+
+fn make_flanger(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 5 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(rate)) => match eval(&args[1], env) {
+                Ok(Value::Unit(depth)) => match eval(&args[2], env) {
+                    Ok(Value::Unit(feedback)) => match eval(&args[3], env) {
+                        Ok(Value::Unit(mix)) => match eval(&args[4], env) {
+                            Ok(Value::Unit(src)) => {
+                                Ok(Flanger::new(rate, depth, feedback, mix, src, env))
+                            }
+                            Ok(_v) => Err(EvalError::NotAug),
+                            Err(err) => Err(err),
+                        },
                         Ok(_v) => Err(EvalError::NotAug),
-                        Err(_err) => Err(EvalError::NotAug),
+                        Err(err) => Err(err),
                     },
                     Ok(_v) => Err(EvalError::NotAug),
                     Err(err) => Err(err),
@@ -456,7 +1615,35 @@ fn make_delay(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
             Err(err) => Err(err),
         }
     } else {
-        Err(EvalError::FnWrongParams(String::from("delay"), args))
+        Err(EvalError::FnWrongParams(String::from("flanger"), args))
+    }
+}
+
+fn make_waveshaper(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 2 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(table)) => match eval(&args[1], env) {
+                Ok(Value::Unit(src)) => Ok(WaveShaper::new(table, src)),
+                Ok(_v) => Err(EvalError::NotAug),
+                Err(err) => Err(err),
+            },
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("waveshaper"), args))
+    }
+}
+
+fn make_dcblock(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
+    if args.len() == 1 {
+        match eval(&args[0], env) {
+            Ok(Value::Unit(src)) => Ok(DCBlock::new(src)),
+            Ok(_v) => Err(EvalError::NotAug),
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(EvalError::FnWrongParams(String::from("dcblock"), args))
     }
 }
 
@@ -470,6 +1657,7 @@ fn make_out(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug, EvalError> {
                 for s in args[1..].iter() {
                     match eval(s, env) {
                         Ok(Value::Unit(unit)) => v.push(unit),
+                        Ok(Value::List(units)) => v.extend(units),
                         Ok(_v) => return Err(EvalError::NotAug),
                         Err(err) => return Err(err),
                     }
@@ -488,11 +1676,32 @@ pub fn make_unit(name: &str, args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug,
     match &name[..] {
         // core
         "pan" => make_pan(args, env),
+        "stereo" => make_stereo(args, env),
+        "swap" => make_swap(args, env),
+        "mono" => make_mono(args, env),
+        "dup" => make_dup(args, env),
+        "ms" => make_ms(args, env),
         "clip" => make_clip(args, env),
         "offset" => make_offset(args, env),
         "gain" => make_gain(args, env),
         "+" => make_add(args, env),
+        "avg" => make_avg(args, env),
         "*" => make_multiply(args, env),
+        "-" => make_subtract(args, env),
+        "/" => make_divide(args, env),
+        "xfade" => make_xfade(args, env),
+        "ring" => make_ring(args, env),
+        "glide" => make_glide(args, env),
+        "follow" => make_follow(args, env),
+        "slew" => make_slew(args, env),
+        "quantize" => make_quantize(args, env),
+        "expscale" => make_expscale(args, env),
+        "metro" => make_metro(args, env),
+        "edge" => make_edge(args, env),
+        "sh" => make_sh(args, env),
+        "stepseq" => make_stepseq(args, env),
+        "arp" => make_arp(args, env),
+        "gate" => make_gate(args, env),
         // oscillator
         "oneshot" => make_oneshot(args, env),
         "rand" => make_rand(args, env),
@@ -500,28 +1709,185 @@ pub fn make_unit(name: &str, args: Vec<Box<Cons>>, env: &mut Env) -> Result<Aug,
         "tri" => make_tri(args, env),
         "saw" => make_saw(args, env),
         "pulse" => make_pulse(args, env),
+        "blpulse" => make_blpulse(args, env),
+        "lfo" => make_lfo(args, env),
+        "osc" => make_osc(args, env),
+        "sync" => make_sync(args, env),
         "table" => make_table(args, env),
+        "table-file" => make_table_file(args, env),
         "phase" => make_phase(args, env),
         "wavetable" => make_wavetable(args, env),
+        "wavetable-from" => make_wavetable_from(args, env),
+        "morph" => make_morph(args, env),
+        "granular" => make_granular(args, env),
+        "kick" => make_kick(args, env),
         // // sequencer
         "pat" => make_pat(args, env),
+        "euclid" => make_euclid(args, env),
+        "transpose" => make_transpose(args, env),
+        "toscale" => make_toscale(args, env),
         "trig" => make_trig(args, env),
         "adsr" => make_adsr_eg(args, env),
+        "envtable" => make_envtable(args, env),
         "seq" => make_seq(args, env),
         // // fx
         "lpf" => make_lpf(args, env),
+        "lowshelf" => make_lowshelf(args, env),
+        "highshelf" => make_highshelf(args, env),
         "delay" => make_delay(args, env),
+        "pingpong" => make_pingpong(args, env),
+        "multitap" => make_multitap(args, env),
+        "freeze" => make_freeze(args, env),
+        "width" => make_width(args, env),
+        "comp" => make_comp(args, env),
+        "chorus" => make_chorus(args, env),
+        "fshift" => make_fshift(args, env),
+        "pitchshift" => make_pitchshift(args, env),
+        "allpass" => make_allpass(args, env),
+        "comb" => make_comb(args, env),
+        "flanger" => make_flanger(args, env),
+        "waveshaper" => make_waveshaper(args, env),
+        "dcblock" => make_dcblock(args, env),
         // // for convinience
         "out" => make_out(args, env),
         _ => Err(EvalError::FnUnknown(String::from(name))),
     }
 }
 
+fn same_slots(a: &[Slot], b: &[Slot]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.name == b.name)
+}
+
+// constant values (e.g. `stepseq` steps or `arp` notes) are not units, so changing them replaces the unit
+fn same_numbers(a: &[Box<UgValue>], b: &[Box<UgValue>]) -> bool {
+    a.iter().zip(b.iter()).all(|(a, b)| match (&**a, &**b) {
+        (UgValue::Number(a), UgValue::Number(b)) => a == b,
+        (UgValue::Symbol(a), UgValue::Symbol(b)) => a == b,
+        _ => true,
+    })
+}
+
+fn replace_unit(old: &Aug, new: &Aug) {
+    let mut new = new.0.lock().unwrap();
+    let mut old = old.0.lock().unwrap();
+    old.ug = std::mem::replace(&mut new.ug, UG::Val(0.0));
+    old.enabled = new.enabled;
+}
+
+fn update_slot(owner: &Aug, old: &Slot, new: &Slot, named: &[Aug]) {
+    if old.ug == new.ug {
+        return;
+    }
+    match &new.value {
+        UgValue::Symbol(sym) => {
+            let _ = owner.0.lock().unwrap().set_str(&new.name, sym.to_string());
+        }
+        UgValue::Bool(b) => {
+            let _ = owner.0.lock().unwrap().set_str(&new.name, b.to_string());
+        }
+        // named units are rewired instead of being rewritten for everyone referring them
+        _ if named.contains(&old.ug) || named.contains(&new.ug) => {
+            let _ = owner.0.lock().unwrap().set(&new.name, new.ug.clone());
+        }
+        _ => update_unit(&old.ug, &new.ug, named),
+    }
+}
+
+// rewrites `old` into `new` while keeping units of the same type and their states.
+// only parameters that differ are set, and other units are replaced inside the same `Aug`
+fn update_unit(old: &Aug, new: &Aug, named: &[Aug]) {
+    if old == new {
+        return;
+    }
+    let bypass_changed = old.is_bypassed() != new.is_bypassed();
+    match (old.dump(&vec![]), new.dump(&vec![])) {
+        (UgNode::Ug(on, os), UgNode::Ug(nn, ns)) if on == nn && same_slots(&os, &ns) => {
+            for (o, n) in os.iter().zip(ns.iter()) {
+                update_slot(old, o, n, named);
+            }
+        }
+        (UgNode::UgRest(on, os, _, ov), UgNode::UgRest(nn, ns, _, nv))
+            if on == nn
+                && same_slots(&os, &ns)
+                && ov.len() == nv.len()
+                && same_numbers(&ov, &nv) =>
+        {
+            for (o, n) in os.iter().zip(ns.iter()) {
+                update_slot(old, o, n, named);
+            }
+            for (o, n) in ov.iter().zip(nv.iter()) {
+                if let (UgValue::Ug(o), UgValue::Ug(n)) = (&**o, &**n) {
+                    update_unit(o, n, named);
+                }
+            }
+        }
+        (on @ UgNode::Val(_), nn @ UgNode::Val(_)) => {
+            if dump_unit(&on, &vec![]) != dump_unit(&nn, &vec![]) {
+                replace_unit(old, new);
+            }
+        }
+        _ => replace_unit(old, new),
+    }
+    if bypass_changed {
+        old.set_bypass(new.is_bypassed());
+    }
+}
+
+fn refers(ug: &Aug, target: &Aug) -> bool {
+    let mut found = false;
+    let mut searched_units: Vec<Aug> = Vec::new();
+    ug.walk(&mut |u: &Aug| {
+        if *u == *target {
+            found = true;
+        }
+        if found || searched_units.contains(u) {
+            false
+        } else {
+            searched_units.push(u.clone());
+            true
+        }
+    });
+    found
+}
+
+// redefining a unit updates the bound one in place so that units referring it keep working
+// and unchanged parts keep their states (e.g. delay buffers)
+fn redefine(name: &str, sexp: &Cons, env: &mut Env) -> Result<Value, EvalError> {
+    let old = match env.binding.get(name).map(|v| &**v) {
+        Some(Value::Unit(old)) => old.clone(),
+        _ => return Err(EvalError::AlreadyBound(name.to_string())),
+    };
+    let new = match eval(sexp, env) {
+        Ok(Value::Unit(new)) => new,
+        Ok(_v) => {
+            let reason = "the new value is not a unit".to_string();
+            return Err(EvalError::CannotRedefine(name.to_string(), reason));
+        }
+        Err(err) => return Err(err),
+    };
+    if refers(&new, &old) {
+        let reason = "the new unit refers to the one it replaces".to_string();
+        return Err(EvalError::CannotRedefine(name.to_string(), reason));
+    }
+
+    let named: Vec<Aug> = env
+        .binding
+        .iter()
+        .filter(|(n, _)| n.as_str() != name)
+        .filter_map(|(_, v)| match &**v {
+            Value::Unit(u) => Some(u.clone()),
+            _ => None,
+        })
+        .collect();
+    update_unit(&old, &new, &named);
+    Ok(Value::Nil)
+}
+
 fn eval_def(name: &Cons, sexp: &Cons, env: &mut Env) -> Result<Value, EvalError> {
     match name {
         Cons::Symbol(name) => {
             if env.binding.contains_key(name) {
-                Err(EvalError::AlreadyBound(name.to_string()))
+                redefine(name, sexp, env)
             } else {
                 match eval(sexp, env) {
                     Ok(v) => {
@@ -536,6 +1902,125 @@ fn eval_def(name: &Cons, sexp: &Cons, env: &mut Env) -> Result<Value, EvalError>
     }
 }
 
+// appends a placeholder for `src`, which is replaced by the previous stage
+fn with_placeholder(list: &Cons) -> Cons {
+    match list {
+        Cons::Cons(elem, rest) => Cons::Cons(elem.clone(), Box::new(with_placeholder(rest))),
+        _ => Cons::Cons(Box::new(Cons::Number(0.0)), Box::new(Cons::Nil)),
+    }
+}
+
+fn eval_chain(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Value, EvalError> {
+    if args.is_empty() {
+        return Err(EvalError::FnWrongParams("chain".to_string(), args));
+    }
+
+    let mut prev = match eval(&args[0], env) {
+        Ok(Value::Unit(u)) => u,
+        Ok(_v) => return Err(EvalError::NotAug),
+        Err(err) => return Err(err),
+    };
+    for stage in args.iter().skip(1) {
+        let sexp = match &**stage {
+            Cons::Cons(_, _) => with_placeholder(stage),
+            sexp => sexp.clone(),
+        };
+        let unit = match eval(&sexp, env) {
+            Ok(Value::Unit(u)) => u,
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        };
+        if !unit.params().iter().any(|p| p == "src") {
+            return Err(EvalError::NoSrcSlot(print(stage)));
+        }
+        if unit.0.lock().unwrap().set("src", prev).is_err() {
+            return Err(EvalError::NoSrcSlot(print(stage)));
+        }
+        prev = unit;
+    }
+    Ok(Value::Unit(prev))
+}
+
+fn eval_map(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Value, EvalError> {
+    if args.len() != 3 {
+        return Err(EvalError::FnWrongParams("map".to_string(), args));
+    }
+
+    let var = match to_vec(&args[0]).first().map(|v| &**v) {
+        Some(Cons::Symbol(name)) => name.to_string(),
+        Some(c) => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        None => return Err(EvalError::FnWrongParams("map".to_string(), args)),
+    };
+    let mut values = Vec::new();
+    for sexp in to_vec(&args[1]).iter() {
+        match eval(sexp, env) {
+            Ok(Value::Unit(u)) => values.push(u),
+            Ok(_v) => return Err(EvalError::NotAug),
+            Err(err) => return Err(err),
+        }
+    }
+
+    // the variable is bound only while evaluating the template
+    let shadowed = env.binding.remove(&var);
+    let mut units = Vec::new();
+    let mut result = Ok(());
+    for value in values.into_iter() {
+        env.binding
+            .insert(var.clone(), Box::new(Value::Unit(value)));
+        match eval(&args[2], env) {
+            Ok(Value::Unit(u)) => units.push(u),
+            Ok(_v) => result = Err(EvalError::NotAug),
+            Err(err) => result = Err(err),
+        }
+        if result.is_err() {
+            break;
+        }
+    }
+    env.binding.remove(&var);
+    if let Some(v) = shadowed {
+        env.binding.insert(var, v);
+    }
+
+    match result {
+        Ok(()) => Ok(Value::List(units)),
+        Err(err) => Err(err),
+    }
+}
+
+fn eval_tuning(args: Vec<Box<Cons>>, env: &mut Env) -> Result<Value, EvalError> {
+    if args.is_empty() || args.len() > 3 {
+        return Err(EvalError::FnWrongParams("tuning".to_string(), args));
+    }
+
+    let reference = match args.get(2) {
+        Some(arg) => match **arg {
+            Cons::Number(n) => n,
+            _ => return Err(EvalError::NotANumber(print(arg))),
+        },
+        None => 440.0,
+    };
+    let tuning = match &*args[0] {
+        Cons::Symbol(name) if &name[..] == "equal" => Tuning::EqualTemperament(reference),
+        Cons::Symbol(name) if &name[..] == "just" => Tuning::JustIntonation(reference),
+        Cons::Symbol(name) if &name[..] == "pythagorean" => Tuning::Pythagorean(reference),
+        _ => return Err(EvalError::FnWrongParams("tuning".to_string(), args)),
+    };
+    let root = match args.get(1) {
+        Some(arg) => match &**arg {
+            Cons::Symbol(name) => match to_note(name) {
+                Some(Pitch::Pitch(n, _)) => n % 12,
+                _ => return Err(EvalError::EvUnknown(name.to_string())),
+            },
+            c => return Err(EvalError::NotASymbol(Box::new(c.clone()))),
+        },
+        None => 3,
+    };
+
+    env.tuning = tuning;
+    env.tuning_root = root;
+    Ok(Value::Nil)
+}
+
 fn eval_call(name: &Cons, args: &Cons, env: &mut Env) -> Result<Value, EvalError> {
     match name {
         Cons::Symbol(name) if &name[..] == "def" => {
@@ -581,6 +2066,35 @@ fn eval_call(name: &Cons, args: &Cons, env: &mut Env) -> Result<Value, EvalError
                 Err(EvalError::FnWrongParams("measure".to_string(), vec))
             }
         }
+        Cons::Symbol(name) if &name[..] == "tuning" => eval_tuning(to_vec(args), env),
+        Cons::Symbol(name) if &name[..] == "map" => eval_map(to_vec(args), env),
+        Cons::Symbol(name) if &name[..] == "chain" => eval_chain(to_vec(args), env),
+        Cons::Symbol(name) if &name[..] == "bypass" => {
+            let vec = to_vec(args);
+            if vec.len() == 1 {
+                match eval(&vec[0], env) {
+                    Ok(Value::Unit(u)) => {
+                        u.set_bypass(true);
+                        Ok(Value::Unit(u))
+                    }
+                    Ok(_v) => Err(EvalError::NotAug),
+                    Err(err) => Err(err),
+                }
+            } else {
+                Err(EvalError::FnWrongParams("bypass".to_string(), vec))
+            }
+        }
+        Cons::Symbol(name) if &name[..] == "db" => {
+            let vec = to_vec(args);
+            if vec.len() == 1 {
+                match *vec[0] {
+                    Cons::Number(n) => Ok(Value::Unit(Aug::val(db_to_gain(n)))),
+                    _ => Err(EvalError::NotANumber(print(&vec[0]))),
+                }
+            } else {
+                Err(EvalError::FnWrongParams("db".to_string(), vec))
+            }
+        }
         Cons::Symbol(name) => match make_unit(&name, to_vec(&args), env) {
             Ok(u) => Ok(Value::Unit(u)),
             Err(err) => Err(err),
@@ -597,6 +2111,8 @@ pub fn eval(sexp: &Cons, env: &mut Env) -> Result<Value, EvalError> {
             None => Err(EvalError::UnboundVariable(name.to_string())),
         },
         Cons::Number(num) => Ok(Value::Unit(Aug::val(*num))),
+        // booleans in signal slots are 1 and 0
+        Cons::Bool(b) => Ok(Value::Unit(Aug::val(if *b { 1.0 } else { 0.0 }))),
         Cons::Nil => Ok(Value::Nil),
     }
 }
@@ -609,8 +2125,203 @@ pub fn eval_all(sexp_vec: Vec<Box<Cons>>, env: &mut Env) -> Result<Value, EvalEr
             Err(err) => return Err(err),
         }
     }
-    match q.len() {
-        0 => Ok(Value::Nil),
-        _ => Ok(q.pop_back().unwrap()),
+    let result = match q.pop_back() {
+        Some(v) => v,
+        None => return Ok(Value::Nil),
+    };
+
+    // a loop in the graph would lock the same unit twice while processing
+    let roots = match &result {
+        Value::Unit(u) => vec![u.clone()],
+        Value::List(units) => units.clone(),
+        Value::Nil => Vec::new(),
+    };
+    for root in roots.iter() {
+        if let Some(ids) = detect_cycle(root) {
+            return Err(EvalError::CyclicGraph(ids));
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::time::Transport;
+    use crate::tapirlisp::dump::dump;
+    use crate::tapirlisp::sexp::read;
+    use crate::ugens::util::collect_shared_ugs;
+
+    fn eval_str(src: &str, env: &mut Env) -> Result<Value, EvalError> {
+        eval_all(read(src.to_string()).unwrap(), env)
+    }
+
+    fn dump_str(src: &str) -> String {
+        let mut env = Env::default();
+        match eval_str(src, &mut env) {
+            Ok(Value::Unit(ug)) => dump(ug, &env),
+            _ => panic!("not a unit: {}", src),
+        }
+    }
+
+    #[test]
+    fn chain_builds_the_nested_graph() {
+        assert_eq!(
+            dump_str("(chain (sine 0 440) (lpf 800 1) (delay 0.3 0.5 0.5))"),
+            dump_str("(delay 0.3 0.5 0.5 (lpf 800 1 (sine 0 440)))")
+        );
+    }
+
+    #[test]
+    fn chain_refuses_stages_without_src() {
+        let mut env = Env::default();
+        let result = eval_str("(chain (sine 0 440) (rand 10))", &mut env);
+        assert!(matches!(result, Err(EvalError::NoSrcSlot(_))));
+        let result = eval_str("(chain (sine 0 440) 0.5)", &mut env);
+        assert!(matches!(result, Err(EvalError::NoSrcSlot(_))));
+        // forms which cannot take one more argument are refused by their constructor
+        let result = eval_str("(chain (sine 0 440) (sine 0 220))", &mut env);
+        assert!(matches!(result, Err(EvalError::FnWrongParams(_, _))));
+    }
+
+    #[test]
+    fn map_builds_one_unit_per_value() {
+        let mut env = Env::default();
+        let sines = match eval_str("(map (freq) (440 550 660) (sine 0 freq))", &mut env) {
+            Ok(Value::List(units)) => units,
+            _ => panic!("not a list"),
+        };
+        let freqs: Vec<Option<f64>> = sines
+            .iter()
+            .map(|s| s.get("freq").unwrap().to_val())
+            .collect();
+        assert_eq!(freqs, vec![Some(440.0), Some(550.0), Some(660.0)]);
+        assert!(sines[0] != sines[1] && sines[1] != sines[2]);
+        // the variable does not leak out
+        assert!(!env.binding.contains_key("freq"));
+    }
+
+    #[test]
+    fn every_arithmetic_takes_mapped_lists() {
+        let mapped = |op: &str| format!("({} (map (x) (12 2 3) x))", op);
+        assert_eq!(eval_unit(&mapped("+")).to_val(), Some(17.0));
+        assert_eq!(eval_unit(&mapped("*")).to_val(), Some(72.0));
+        assert_eq!(eval_unit(&mapped("-")).to_val(), Some(7.0));
+        assert_eq!(eval_unit(&mapped("/")).to_val(), Some(2.0));
+        assert_eq!(eval_unit("(- 1 (map (x) (2 3) x))").to_val(), Some(-4.0));
+    }
+
+    fn eval_unit(src: &str) -> Aug {
+        match eval_str(src, &mut Env::default()) {
+            Ok(Value::Unit(ug)) => ug,
+            _ => panic!("not a unit: {}", src),
+        }
+    }
+
+    #[test]
+    fn arithmetic_folds_constants() {
+        assert_eq!(eval_unit("(* 440 2)").to_val(), Some(880.0));
+        assert_eq!(eval_unit("(+ 1 2 3)").to_val(), Some(6.0));
+        assert_eq!(eval_unit("(- 1 2 3)").to_val(), Some(-4.0));
+        assert_eq!(eval_unit("(/ 1 4)").to_val(), Some(0.25));
+    }
+
+    #[test]
+    fn arithmetic_on_units_makes_units() {
+        let mut scaled = eval_unit("(* (sine 0 100) 100)");
+        assert_eq!(scaled.to_val(), None);
+        let out = scaled.proc_n(&mut Transport::default(), 441);
+        let dry = eval_unit("(sine 0 100)").proc_n(&mut Transport::default(), 441);
+        for (s, d) in out.iter().zip(dry.iter()) {
+            assert!((s.0 - d.0 * 100.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn pat_reads_note_symbols() {
+        let pat = eval_unit("(pat c4:4 loop)");
+        let msgs = match &pat.0.lock().unwrap().ug {
+            UG::Pat(pat) => pat.0.lock().unwrap().clone(),
+            _ => panic!("not a pattern"),
+        };
+        assert_eq!(msgs.len(), 2);
+        assert!(matches!(*msgs[0], Message::Note(Pitch::Pitch(3, 4), _, _)));
+        assert!(matches!(*msgs[1], Message::Loop));
+    }
+
+    #[test]
+    fn pat_reports_the_position_of_bad_notes() {
+        let result = eval_str("(pat c4:4 e4:4 x9:4)", &mut Env::default());
+        assert!(matches!(result, Err(EvalError::EvPosition(3, _))));
+    }
+
+    #[test]
+    fn redefining_a_shared_unit_updates_every_user() {
+        let mut env = Env::default();
+        eval_str("(def osc (sine 0 440))", &mut env).unwrap();
+        eval_str("(def mix (+ (gain 0.5 osc) (gain 0.25 osc)))", &mut env).unwrap();
+        let bound = |env: &Env, name: &str| match env.binding.get(name).map(|v| &**v) {
+            Some(Value::Unit(ug)) => ug.clone(),
+            _ => panic!("{} is not a unit", name),
+        };
+        let osc = bound(&env, "osc");
+
+        eval_str("(def osc (sine 0 220))", &mut env).unwrap();
+        assert!(bound(&env, "osc") == osc);
+        assert_eq!(osc.0.lock().unwrap().get_str("freq").unwrap(), "220");
+        // both gains still play the one oscillator
+        let mix = bound(&env, "mix");
+        let shared = collect_shared_ugs(mix.clone());
+        assert!(shared.len() == 1 && shared[0] == osc);
+        let mut mix = mix;
+        let mut expected = eval_unit("(sine 0 220)");
+        let out = mix.proc_n(&mut Transport::default(), 100);
+        let expected = expected.proc_n(&mut Transport::default(), 100);
+        for (o, e) in out.iter().zip(expected.iter()) {
+            assert!((o.0 - e.0 * 0.75).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn redefining_reports_the_binding_it_cannot_replace() {
+        let mut env = Env::default();
+        eval_str("(def osc (sine 0 440))", &mut env).unwrap();
+        for src in ["(def osc (gain 0.5 osc))", "(def osc (map (x) (1 2) x))"].iter() {
+            match eval_str(src, &mut env) {
+                Err(EvalError::CannotRedefine(name, _)) => assert_eq!(name, "osc"),
+                other => panic!("unexpected {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn redefining_a_parameter_keeps_delay_buffers() {
+        let mut env = Env::default();
+        eval_str("(def d (delay 0.1 0.5 1.0 (sine 0 440)))", &mut env).unwrap();
+        let bound = |env: &Env| match env.binding.get("d").map(|v| &**v) {
+            Some(Value::Unit(ug)) => ug.clone(),
+            _ => panic!("d is not a unit"),
+        };
+        let mut delay = bound(&env);
+        let mut transport = Transport::default();
+        let mut dry = eval_unit("(sine 0 440)");
+        let dry = dry.proc_n(&mut Transport::default(), 2205 + 4410);
+        // the first echo comes out after 4410 samples
+        delay.proc_n(&mut transport, 2205);
+
+        eval_str("(def d (delay 0.1 0.8 1.0 (sine 0 440)))", &mut env).unwrap();
+        assert!(bound(&env) == delay);
+        assert_eq!(delay.0.lock().unwrap().get_str("feedback").unwrap(), "0.8");
+
+        // echoes of what was rendered before redefining, now scaled by the new feedback
+        let after = delay.proc_n(&mut transport, 4410);
+        let echo: Vec<f64> = after
+            .iter()
+            .zip(dry[2205..].iter())
+            .map(|(s, d)| s.0 - d.0)
+            .collect();
+        assert!(echo[..2205].iter().all(|v| v.abs() < 1e-9));
+        let peak = echo[2205..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        assert!((peak - 0.8).abs() < 0.01);
     }
 }
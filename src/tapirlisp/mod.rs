@@ -3,5 +3,5 @@ pub mod eval;
 pub mod sexp;
 pub mod types;
 
-pub use dump::dump;
+pub use dump::{dump, dump_compact, dump_pretty};
 pub use eval::{eval, eval_all, TYPE_NAMES};
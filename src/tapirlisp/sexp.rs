@@ -8,6 +8,7 @@ pub enum Cons {
     Cons(Box<Cons>, Box<Cons>),
     Symbol(String),
     Number(f64),
+    Bool(bool),
     Nil,
 }
 
@@ -48,8 +49,9 @@ fn skip_whitespaces(chars: &mut Peekable<Chars>) {
                 if !comment && !c.is_whitespace() {
                     break;
                 } else if comment && *c == '\n' {
+                    // blank lines or more comments may follow
+                    comment = false;
                     chars.next();
-                    break;
                 } else {
                     chars.next();
                 }
@@ -74,7 +76,11 @@ fn read_symbol(chars: &mut Peekable<Chars>) -> Result<Cons, ReadError> {
             _ => break,
         }
     }
-    Ok(Cons::Symbol(name))
+    match &name[..] {
+        "true" => Ok(Cons::Bool(true)),
+        "false" => Ok(Cons::Bool(false)),
+        _ => Ok(Cons::Symbol(name)),
+    }
 }
 
 fn read_number(chars: &mut Peekable<Chars>) -> Result<Cons, ReadError> {
@@ -129,8 +135,17 @@ fn read_exp(chars: &mut Peekable<Chars>) -> Result<Cons, ReadError> {
         None => Ok(Cons::Nil),
         Some(')') => Err(ReadError::UnexpectedCloseParen),
         Some('(') => read_list(chars),
+        Some('-') => {
+            // `-` not followed by digits is the subtraction symbol
+            let mut ahead = chars.clone();
+            ahead.next();
+            match ahead.peek() {
+                Some(c) if *c == '.' || c.is_ascii_digit() => read_number(chars),
+                _ => read_symbol(chars),
+            }
+        }
         Some(c) => {
-            if *c == '-' || c.is_digit(10) {
+            if c.is_ascii_digit() {
                 read_number(chars)
             } else {
                 read_symbol(chars)
@@ -169,6 +184,10 @@ fn print_list(car: &Cons, cdr: &Cons) -> String {
             s.push_str(" . ");
             s.push_str(n);
         }
+        Cons::Bool(b) => {
+            s.push_str(" . ");
+            s.push_str(&b.to_string());
+        }
     }
     s
 }
@@ -179,6 +198,7 @@ pub fn print(exp: &Cons) -> String {
         Cons::Nil => s.push_str("nil"),
         Cons::Number(n) => s.push_str(n.to_string().as_str()),
         Cons::Symbol(n) => s.push_str(n.to_string().as_str()),
+        Cons::Bool(b) => s.push_str(b.to_string().as_str()),
         Cons::Cons(car, cdr) => {
             s.push('(');
             s.push_str(print_list(car, cdr).as_str());
@@ -200,3 +220,17 @@ pub fn to_vec(list: &Cons) -> Vec<Box<Cons>> {
         _ => panic!("it's not proper list: {:?}", list),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_comment_lines_are_skipped() {
+        let commented = ";; one\n;; two\n\n(sine 0 440) ; three\n; four\n\n(saw 0 1)\n";
+        assert_eq!(
+            read(commented.to_string()).unwrap(),
+            read("(sine 0 440) (saw 0 1)".to_string()).unwrap()
+        );
+    }
+}
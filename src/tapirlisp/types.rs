@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 
-use crate::musical_time::time::Transport;
+use crate::musical_time::event::{NoteNum, Tuning};
+use crate::musical_time::time::{Measure, Transport};
 use crate::tapirlisp::sexp::Cons;
 use crate::ugens::core::Aug;
 
@@ -11,6 +12,7 @@ pub type Name = String;
 #[derive(Clone)]
 pub enum Value {
     Unit(Aug),
+    List(Vec<Aug>),
     Nil,
 }
 
@@ -18,6 +20,8 @@ pub enum Value {
 pub struct Env {
     pub transport: Transport,
     pub binding: HashMap<Name, Box<Value>>,
+    pub tuning: Tuning,
+    pub tuning_root: NoteNum,
 }
 
 impl Env {
@@ -25,8 +29,32 @@ impl Env {
         Env {
             transport: transport,
             binding: HashMap::new(),
+            tuning: Tuning::EqualTemperament(440.0),
+            tuning_root: 3,
         }
     }
+
+    pub fn new(sample_rate: u32, bpm: f64, measure: Measure) -> Env {
+        let mut transport = Transport::new(sample_rate);
+        transport.bpm = bpm;
+        transport.measure = measure;
+        Env::init(transport)
+    }
+
+    pub fn with_sample_rate(sample_rate: u32) -> Env {
+        Env::init(Transport::new(sample_rate))
+    }
+
+    // units sizing their buffers in seconds read the rate from here
+    pub fn sample_rate(&self) -> u32 {
+        self.transport.sample_rate
+    }
+}
+
+impl Default for Env {
+    fn default() -> Env {
+        Env::init(Transport::default())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,12 +65,18 @@ pub enum EvalError {
     EvWrongParams(String),
     EvUnknown(String),
     EvMalformedEvent(String),
+    EvPosition(usize, Box<EvalError>),
     UnboundVariable(String),
     AlreadyBound(String),
     NotANumber(String),
     NotASymbol(Box<Cons>),
     NotAug,
     NotAPattern,
+    ChordInSeq,
+    NoSrcSlot(String),
+    CannotReadFile(String, String),
+    CyclicGraph(Vec<usize>),
+    CannotRedefine(String, String),
 }
 
 impl fmt::Display for EvalError {
@@ -60,12 +94,24 @@ impl fmt::Display for EvalError {
                 write!(f, "{:?} is unknown or not implemented event.", name)
             }
             EvalError::EvMalformedEvent(s) => write!(f, "{:?} is not an event.", s),
+            EvalError::EvPosition(pos, err) => write!(f, "{} (event #{} in 'pat')", err, pos),
             EvalError::UnboundVariable(name) => write!(f, "Unbound variable: '{:?}'", name),
             EvalError::AlreadyBound(name) => write!(f, "'{:?}' is already bound", name),
             EvalError::NotANumber(s) => write!(f, "{:?} is not a number", s),
             EvalError::NotASymbol(cons) => write!(f, "{:?} is not a symbol.", cons),
             EvalError::NotAug => write!(f, "((serialized unit here)) is not an unit"),
             EvalError::NotAPattern => write!(f, "it's not a pattern"),
+            EvalError::ChordInSeq => {
+                write!(f, "'seq' plays one note at a time and cannot play chords")
+            }
+            EvalError::NoSrcSlot(stage) => write!(f, "{} has no 'src' to chain", stage),
+            EvalError::CyclicGraph(ids) => write!(f, "units {:?} are connected in a loop", ids),
+            EvalError::CannotReadFile(path, reason) => {
+                write!(f, "Cannot read {:?}: {}", path, reason)
+            }
+            EvalError::CannotRedefine(name, reason) => {
+                write!(f, "Cannot redefine {:?}: {}", name, reason)
+            }
         }
     }
 }
@@ -79,12 +125,18 @@ impl Error for EvalError {
             EvalError::EvWrongParams(_) => None,
             EvalError::EvUnknown(_) => None,
             EvalError::EvMalformedEvent(_) => None,
+            EvalError::EvPosition(_, err) => Some(&**err),
             EvalError::UnboundVariable(_) => None,
             EvalError::AlreadyBound(_) => None,
             EvalError::NotANumber(_) => None,
             EvalError::NotASymbol(_) => None,
             EvalError::NotAug => None,
             EvalError::NotAPattern => None,
+            EvalError::ChordInSeq => None,
+            EvalError::NoSrcSlot(_) => None,
+            EvalError::CannotReadFile(_, _) => None,
+            EvalError::CyclicGraph(_) => None,
+            EvalError::CannotRedefine(_, _) => None,
         }
     }
 }
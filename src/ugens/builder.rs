@@ -0,0 +1,108 @@
+use crate::tapirlisp::types::Env;
+
+use super::core::Aug;
+use super::fx::{Delay, LPFilter};
+use super::misc::Multiply;
+use super::osc::{make_osc, Waveform};
+use super::seq::AdsrEg;
+
+// chains units from Rust, passing each one as the `src` of the next, e.g.
+// `PatchBuilder::new(&env).osc(Waveform::Saw, 440).lpf(1000, 1).build()`
+pub struct PatchBuilder<'a> {
+    env: &'a Env,
+    unit: Aug,
+    eg: Option<Aug>,
+}
+
+impl<'a> PatchBuilder<'a> {
+    pub fn new(env: &'a Env) -> PatchBuilder<'a> {
+        PatchBuilder {
+            env,
+            unit: Aug::val(0.0),
+            eg: None,
+        }
+    }
+
+    // starts over from an oscillator, dropping units chained so far
+    pub fn osc(mut self, waveform: Waveform, freq: impl Into<Aug>) -> Self {
+        self.unit = make_osc(waveform, Aug::val(0.0), freq.into());
+        self
+    }
+
+    // multiplies by an ADSR envelope, which can be triggered through `eg()`
+    pub fn env(
+        mut self,
+        a: impl Into<Aug>,
+        d: impl Into<Aug>,
+        s: impl Into<Aug>,
+        r: impl Into<Aug>,
+    ) -> Self {
+        let eg = AdsrEg::new(a.into(), d.into(), s.into(), r.into());
+        self.unit = Multiply::new(vec![self.unit, eg.clone()]);
+        self.eg = Some(eg);
+        self
+    }
+
+    pub fn lpf(mut self, cutoff: impl Into<Aug>, q: impl Into<Aug>) -> Self {
+        self.unit = LPFilter::new(cutoff.into(), q.into(), self.unit);
+        self
+    }
+
+    pub fn delay(
+        mut self,
+        time: impl Into<Aug>,
+        feedback: impl Into<Aug>,
+        mix: impl Into<Aug>,
+    ) -> Self {
+        self.unit = Delay::new(
+            time.into(),
+            feedback.into(),
+            mix.into(),
+            self.unit,
+            self.env,
+        );
+        self
+    }
+
+    pub fn eg(&self) -> Option<Aug> {
+        self.eg.clone()
+    }
+
+    pub fn build(self) -> Aug {
+        self.unit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tapirlisp::dump::dump;
+    use crate::ugens::core::Walk;
+    use crate::ugens::osc::Saw;
+
+    #[test]
+    fn built_patch_dumps_like_a_hand_wired_one() {
+        let env = Env::default();
+        let builder = PatchBuilder::new(&env)
+            .osc(Waveform::Saw, 440)
+            .env(0.01, 0.1, 0.5, 0.2)
+            .lpf(1000, 1)
+            .delay(0.25, 0.4, 0.3);
+        let eg = builder.eg().unwrap();
+        let built = builder.build();
+
+        let saw = Saw::new(Aug::val(0.0), Aug::val(440.0));
+        let adsr = AdsrEg::new(Aug::val(0.01), Aug::val(0.1), Aug::val(0.5), Aug::val(0.2));
+        let enveloped = Multiply::new(vec![saw, adsr]);
+        let filtered = LPFilter::new(Aug::val(1000.0), Aug::val(1.0), enveloped);
+        let wired = Delay::new(Aug::val(0.25), Aug::val(0.4), Aug::val(0.3), filtered, &env);
+        // the envelope to trigger is the one in the chain
+        let mut found = false;
+        built.walk(&mut |u: &Aug| {
+            found |= *u == eg;
+            true
+        });
+        assert!(found);
+        assert_eq!(dump(built, &env), dump(wired, &env));
+    }
+}
@@ -1,9 +1,13 @@
 use std::cmp::{Eq, PartialEq};
+use std::fs;
 use std::hash::{Hash, Hasher};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
-use crate::musical_time::event::Message;
-use crate::musical_time::time::{Measure, Transport};
+use crate::musical_time::event::{Message, NoteNum, Octave, Pitch, Velocity, MAX_VELOCITY};
+use crate::musical_time::scale::Scale;
+use crate::musical_time::time::{Clock, Measure, Pos, PosOps, Transport};
 use crate::musical_time::utils::{to_len, to_note, to_pos, to_str};
 
 //// types and traits
@@ -17,8 +21,10 @@ type OpName = String;
 #[derive(Clone)]
 pub enum Value {
     Number(f64),
+    Bool(bool),
     Table(Vec<f64>),
     Pattern(Vec<String>),
+    Symbol(String),
     Ug(Aug),
     Shared(usize, Aug),
 }
@@ -36,6 +42,8 @@ pub enum UgNode {
 }
 
 pub trait Dump: Walk {
+    // the name the unit is dumped with, or "" for plain values, tables and patterns
+    fn name(&self) -> &'static str;
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode;
 }
 
@@ -44,8 +52,43 @@ pub enum OperateError {
     NotUgen,
     CannotParsePattern(String, String),
     CannotParseNumber(String, String),
+    CannotParseSymbol(String, String),
     ParamNotFound(String),
     CannotRepresentAsString(String),
+    TypeMismatch(String, String),
+}
+
+// what a parameter value means, for editors
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamUnit {
+    Hz,
+    Decibel,
+    Seconds,
+    Normalized,
+    Bipolar,
+    Ratio,
+    Radian,
+    Cycle,
+    Semitones,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamInfo {
+    pub min: f64,
+    pub max: f64,
+    pub default: f64,
+    pub unit: ParamUnit,
+}
+
+impl ParamInfo {
+    pub fn new(min: f64, max: f64, default: f64, unit: ParamUnit) -> ParamInfo {
+        ParamInfo {
+            min,
+            max,
+            default,
+            unit,
+        }
+    }
 }
 
 pub trait Operate: Dump {
@@ -54,12 +97,33 @@ pub trait Operate: Dump {
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError>;
     fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError>;
     fn clear(&mut self, pname: &str);
+    fn params(&self) -> Vec<String>;
+    fn param_info(&self, pname: &str) -> Option<ParamInfo>;
+
+    // moves a constant slot to `target` over `samples` samples instead of jumping to it.
+    // the slot holds a ramp meanwhile and becomes the constant again when it arrives
+    fn set_ramp(&mut self, pname: &str, target: f64, samples: u64) -> Result<bool, OperateError> {
+        match self.get(pname)?.to_val() {
+            Some(from) => self.set(pname, Ramp::new(from, target, samples)),
+            None => Err(OperateError::TypeMismatch(
+                format!("{}/{}", self.name(), pname),
+                "number".to_string(),
+            )),
+        }
+    }
 }
 
 pub type Signal = (f64, f64);
 
 pub trait Proc: Operate {
     fn proc(&mut self, transport: &Transport) -> Signal;
+    // clears internal states like delay buffers, phases or envelope stages
+    fn reset(&mut self) {}
+    // a unit which will output only this value from now on, so it can be replaced by it.
+    // asked only of units whose UGen has `settles` set
+    fn settled(&self) -> Option<f64> {
+        None
+    }
 }
 
 pub trait Osc: Proc {
@@ -86,6 +150,16 @@ pub trait Eg: Proc {
 pub struct Table(pub Arc<Mutex<Vec<f64>>>);
 pub struct Pattern(pub Arc<Mutex<Vec<Box<Message>>>>);
 
+// steps of a pattern laid out in beats for one measure, so that units querying notes every
+// sample can keep them instead of walking the pattern again
+pub struct PatternSteps {
+    pub measure: Measure,
+    // (pitch, start, length) in beats
+    steps: Vec<(Pitch, f64, f64)>,
+    len: f64,
+    looped: bool,
+}
+
 pub enum UG {
     Val(f64),
     Proc(Box<dyn Proc + Send>),
@@ -99,6 +173,13 @@ pub struct UGen {
     pub id: usize,
     pub last_tick: u64,
     pub last_sig: Signal,
+    pub enabled: bool,
+    // when set, `peak` keeps the largest absolute output so that `peak_report` can tell which
+    // stage goes over unity. off by default to keep the audio path cheap
+    pub metering: bool,
+    pub peak: f64,
+    // whether `ug` may settle into a constant, so that only ramps are asked on each sample
+    pub settles: bool,
     pub ug: UG,
 }
 
@@ -106,12 +187,77 @@ pub struct Aug(pub Arc<Mutex<UGen>>);
 
 // trait implementations for Table
 
+pub const TABLE_BASE64_PREFIX: &str = "b64:";
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub enum WindowKind {
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl WindowKind {
+    pub fn parse(s: &str) -> Option<WindowKind> {
+        match s {
+            "hann" => Some(WindowKind::Hann),
+            "hamming" => Some(WindowKind::Hamming),
+            "blackman" => Some(WindowKind::Blackman),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            WindowKind::Hann => "hann",
+            WindowKind::Hamming => "hamming",
+            WindowKind::Blackman => "blackman",
+        }
+    }
+
+    // the window over `len` points, symmetric so that both endpoints get the same weight
+    fn weight(&self, n: usize, len: usize) -> f64 {
+        if len < 2 {
+            return 1.0;
+        }
+        let x = 2.0 * std::f64::consts::PI * n as f64 / (len - 1) as f64;
+        match self {
+            WindowKind::Hann => 0.5 - 0.5 * x.cos(),
+            WindowKind::Hamming => 0.54 - 0.46 * x.cos(),
+            WindowKind::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+        }
+    }
+}
+
 impl Table {
     pub fn new(data: Vec<f64>) -> Table {
         Table(Arc::new(Mutex::new(data)))
     }
 
+    // scales values in place so that the peak is 1.0. silent tables are left as they are
+    pub fn normalize(&self) {
+        let mut data = self.0.lock().unwrap();
+        let peak = data.iter().fold(0.0f64, |p, v| p.max(v.abs()));
+        if peak > 0.0 {
+            for v in data.iter_mut() {
+                *v /= peak;
+            }
+        }
+    }
+
+    // multiplies values in place by the window spanning the whole table
+    pub fn apply_window(&self, kind: WindowKind) {
+        let mut data = self.0.lock().unwrap();
+        let len = data.len();
+        for (n, v) in data.iter_mut().enumerate() {
+            *v *= kind.weight(n, len);
+        }
+    }
+
     pub fn parse_str(data: String) -> Option<Vec<f64>> {
+        if let Some(encoded) = data.trim().strip_prefix(TABLE_BASE64_PREFIX) {
+            return Table::from_base64(encoded);
+        }
         let mut table = Vec::new();
         for s in data.trim().split(' ') {
             if let Ok(n) = s.parse::<f64>() {
@@ -122,6 +268,141 @@ impl Table {
         }
         Some(table)
     }
+
+    // encodes the little-endian bytes of the values with base64, which is far shorter than
+    // printing them and restores them exactly. `parse_str` reads it back
+    pub fn to_base64(data: &[f64]) -> String {
+        let bytes: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes().to_vec()).collect();
+        let mut s = String::from(TABLE_BASE64_PREFIX);
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    s.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    s.push('=');
+                }
+            }
+        }
+        s
+    }
+
+    fn from_base64(encoded: &str) -> Option<Vec<f64>> {
+        let mut bytes = Vec::new();
+        let encoded = encoded.trim_end_matches('=').as_bytes();
+        for chunk in encoded.chunks(4) {
+            if chunk.len() == 1 {
+                return None;
+            }
+            let mut n = 0u32;
+            for (i, c) in chunk.iter().enumerate() {
+                let v = BASE64_CHARS.iter().position(|b| b == c)? as u32;
+                n |= v << (18 - 6 * i);
+            }
+            for i in 0..chunk.len() - 1 {
+                bytes.push((n >> (16 - 8 * i)) as u8);
+            }
+        }
+        if bytes.len() % 8 != 0 {
+            return None;
+        }
+        let mut table = Vec::with_capacity(bytes.len() / 8);
+        for b in bytes.chunks(8) {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(b);
+            table.push(f64::from_le_bytes(le));
+        }
+        Some(table)
+    }
+
+    // reads a mono WAV file, or a text file of whitespace separated numbers
+    pub fn from_file(path: &str) -> io::Result<Vec<f64>> {
+        let bytes = fs::read(path)?;
+        if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+            Table::parse_wav(&bytes)
+        } else {
+            let mut table = Vec::new();
+            for s in String::from_utf8_lossy(&bytes).split_whitespace() {
+                match s.parse::<f64>() {
+                    Ok(n) => table.push(n),
+                    Err(_) => {
+                        let msg = format!("{:?} is not a number", s);
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                    }
+                }
+            }
+            Ok(table)
+        }
+    }
+
+    fn parse_wav(bytes: &[u8]) -> io::Result<Vec<f64>> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let u16_at = |i: usize| u16::from(bytes[i]) | u16::from(bytes[i + 1]) << 8;
+        let u32_at = |i: usize| u32::from(u16_at(i)) | u32::from(u16_at(i + 2)) << 16;
+
+        let mut format = None;
+        let mut idx = 12;
+        while idx + 8 <= bytes.len() {
+            let id = &bytes[idx..idx + 4];
+            let size = u32_at(idx + 4) as usize;
+            let body = idx + 8;
+            if body + size > bytes.len() {
+                return Err(invalid("truncated WAV chunk"));
+            }
+
+            if id == b"fmt " && size >= 16 {
+                let mut tag = u16_at(body);
+                // WAVE_FORMAT_EXTENSIBLE keeps the actual format in its sub format GUID
+                if tag == 0xfffe && size >= 26 {
+                    tag = u16_at(body + 24);
+                }
+                let channels = u16_at(body + 2);
+                let bits = u16_at(body + 14);
+                if channels != 1 {
+                    return Err(invalid("only mono WAV files can be tables"));
+                }
+                format = Some((tag, bits));
+            } else if id == b"data" {
+                let data = &bytes[body..body + size];
+                let table = match format {
+                    Some((1, 8)) => data.iter().map(|b| (*b as f64 - 128.0) / 128.0).collect(),
+                    Some((1, 16)) => data
+                        .chunks_exact(2)
+                        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32768.0)
+                        .collect(),
+                    Some((1, 24)) => data
+                        .chunks_exact(3)
+                        .map(|b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f64 / 2147483648.0)
+                        .collect(),
+                    Some((1, 32)) => data
+                        .chunks_exact(4)
+                        .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / 2147483648.0)
+                        .collect(),
+                    Some((3, 32)) => data
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+                        .collect(),
+                    Some((3, 64)) => data
+                        .chunks_exact(8)
+                        .map(|b| {
+                            let mut buf = [0; 8];
+                            buf.copy_from_slice(b);
+                            f64::from_le_bytes(buf)
+                        })
+                        .collect(),
+                    Some(_) => return Err(invalid("unsupported WAV sample format")),
+                    None => return Err(invalid("WAV data appears before its format")),
+                };
+                return Ok(table);
+            }
+            // chunks are padded to even sizes
+            idx = body + size + size % 2;
+        }
+        Err(invalid("WAV file has no data"))
+    }
 }
 
 impl Walk for Table {
@@ -129,6 +410,10 @@ impl Walk for Table {
 }
 
 impl Dump for Table {
+    fn name(&self) -> &'static str {
+        ""
+    }
+
     fn dump(&self, _shared_vec: &Vec<Aug>) -> UgNode {
         let mut vec = Vec::new();
         for v in self.0.lock().unwrap().iter() {
@@ -148,14 +433,36 @@ impl Pattern {
     pub fn parse_str_1(token: &str) -> Result<Message, bool> {
         match token {
             "loop" => Ok(Message::Loop),
+            "~" => Ok(Message::Tie),
+            s if s.starts_with('[') && s.ends_with(']') => {
+                let mut notes = Vec::new();
+                for t in s[1..s.len() - 1].split_whitespace() {
+                    match Pattern::parse_str_1(t) {
+                        Ok(note @ Message::Note(_, _, _)) => notes.push(note),
+                        _ => return Err(false),
+                    }
+                }
+                if notes.is_empty() {
+                    Err(false)
+                } else {
+                    Ok(Message::Chord(notes))
+                }
+            }
             s => {
                 let n: Vec<&str> = s.split(':').collect();
-                if n.len() != 2 {
+                if n.len() != 2 && n.len() != 3 {
                     Err(false)
                 } else {
+                    let vel = match n.get(2) {
+                        Some(v) => match v.parse::<Velocity>() {
+                            Ok(v) if v <= MAX_VELOCITY => v,
+                            _ => return Err(false),
+                        },
+                        None => MAX_VELOCITY,
+                    };
                     if let Some(pitch) = to_note(n[0]) {
                         if let Ok(len) = n[1].parse::<u32>() {
-                            Ok(Message::Note(pitch, to_pos(len)))
+                            Ok(Message::Note(pitch, to_pos(len), vel))
                         } else {
                             Err(false)
                         }
@@ -167,9 +474,33 @@ impl Pattern {
         }
     }
 
+    pub fn tokenize(data: &str) -> Result<Vec<String>, bool> {
+        let mut tokens = Vec::new();
+        let mut chord: Option<String> = None;
+        for t in data.trim().split(' ').filter(|t| !t.is_empty()) {
+            match chord.take() {
+                Some(mut c) => {
+                    c.push(' ');
+                    c.push_str(t);
+                    if t.ends_with(']') {
+                        tokens.push(c);
+                    } else {
+                        chord = Some(c);
+                    }
+                }
+                None if t.starts_with('[') && !t.ends_with(']') => chord = Some(t.to_string()),
+                None => tokens.push(t.to_string()),
+            }
+        }
+        match chord {
+            Some(_) => Err(false),
+            None => Ok(tokens),
+        }
+    }
+
     pub fn parse_str(data: String) -> Result<Vec<Box<Message>>, bool> {
         let mut msgs = Vec::new();
-        for token in data.trim().split(' ') {
+        for token in Pattern::tokenize(&data)?.iter() {
             if let Ok(msg) = Pattern::parse_str_1(token) {
                 msgs.push(Box::new(msg));
             } else {
@@ -178,6 +509,229 @@ impl Pattern {
         }
         Ok(msgs)
     }
+
+    pub fn euclidean(pulses: u32, steps: u32, pitch: &str, len: u32) -> Vec<Box<Message>> {
+        let note = match to_note(pitch) {
+            Some(note) => note,
+            None => Pitch::Rest,
+        };
+        let mut msgs = Vec::new();
+        for hit in Pattern::bjorklund(pulses, steps) {
+            let pitch = if hit { note.clone() } else { Pitch::Rest };
+            msgs.push(Box::new(Message::Note(pitch, to_pos(len), MAX_VELOCITY)));
+        }
+        msgs
+    }
+
+    fn bjorklund(pulses: u32, steps: u32) -> Vec<bool> {
+        if pulses >= steps {
+            return vec![true; steps as usize];
+        }
+        let mut a: Vec<Vec<bool>> = vec![vec![true]; pulses as usize];
+        let mut b: Vec<Vec<bool>> = vec![vec![false]; (steps - pulses) as usize];
+
+        // pair up the groups until at most one remainder group is left
+        while b.len() > 1 && !a.is_empty() {
+            let n = a.len().min(b.len());
+            let rest = if a.len() > n {
+                a.split_off(n)
+            } else {
+                b.split_off(n)
+            };
+            for (x, y) in a.iter_mut().zip(b.iter()) {
+                x.extend(y);
+            }
+            b = rest;
+        }
+        a.into_iter().chain(b).flatten().collect()
+    }
+
+    pub fn to_beats(pos: &Pos, measure: &Measure) -> f64 {
+        (pos.bar * measure.beat + pos.beat) as f64 + pos.pos
+    }
+
+    // a copy with every note shifted by `semitones`
+    pub fn transpose(&self, semitones: i32) -> Pattern {
+        self.map_notes(&|n| n + semitones)
+    }
+
+    // a copy with every note snapped to the nearest degree of `scale` built on `root`.
+    // ties go to the lower degree, as `quantize` does
+    pub fn quantize_scale(&self, scale: &Scale, root: NoteNum) -> Pattern {
+        let root = root as i32;
+        self.map_notes(&|n| {
+            let octave = (n - root).div_euclid(12);
+            let mut nearest = n;
+            let mut distance = i32::MAX;
+            for o in octave - 1..=octave + 1 {
+                for d in scale.degrees().iter() {
+                    let m = root + o * 12 + d;
+                    if (m - n).abs() < distance {
+                        nearest = m;
+                        distance = (m - n).abs();
+                    }
+                }
+            }
+            nearest
+        })
+    }
+
+    pub fn has_chords(&self) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|m| matches!(**m, Message::Chord(_)))
+    }
+
+    // pitched notes in order, including each member of chords
+    pub fn pitches(&self) -> Vec<Pitch> {
+        fn collect(msg: &Message, pitches: &mut Vec<Pitch>) {
+            match msg {
+                Message::Note(pitch @ Pitch::Pitch(_, _), _, _) => pitches.push(pitch.clone()),
+                Message::Chord(notes) => notes.iter().for_each(|n| collect(n, pitches)),
+                _ => (),
+            }
+        }
+
+        let mut pitches = Vec::new();
+        for msg in self.0.lock().unwrap().iter() {
+            collect(msg, &mut pitches);
+        }
+        pitches
+    }
+
+    // rewrites notes counted in semitones from A0 (octave 0 starts at A). results are kept
+    // within c0 to b7, which is what patterns can spell
+    fn map_notes(&self, f: &dyn Fn(i32) -> i32) -> Pattern {
+        let msgs = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| Box::new(Pattern::map_msg(m, f)))
+            .collect();
+        Pattern::new(msgs)
+    }
+
+    fn map_msg(msg: &Message, f: &dyn Fn(i32) -> i32) -> Message {
+        match msg {
+            Message::Note(Pitch::Pitch(n, o), len, vel) => {
+                let note = f((o * 12 + n) as i32).clamp(3, 8 * 12 + 2) as u32;
+                let pitch = Pitch::Pitch(note % 12, (note / 12) as Octave);
+                Message::Note(pitch, len.clone(), *vel)
+            }
+            Message::Chord(notes) => {
+                Message::Chord(notes.iter().map(|n| Pattern::map_msg(n, f)).collect())
+            }
+            msg => msg.clone(),
+        }
+    }
+
+    // chords are represented by their first note and last as long as their longest one
+    pub fn steps(&self, measure: &Measure) -> PatternSteps {
+        let mut steps: Vec<(Pitch, f64, f64)> = Vec::new();
+        let mut pos = 0.0;
+        let mut last_len: Option<f64> = None;
+        let mut looped = false;
+
+        for m in self.0.lock().unwrap().iter() {
+            match &**m {
+                Message::Note(pitch, len, _vel) => {
+                    let len = Pattern::to_beats(len, measure);
+                    steps.push((pitch.clone(), pos, len));
+                    pos += len;
+                    last_len = Some(len);
+                }
+                Message::Chord(notes) => {
+                    let mut pitch: Option<Pitch> = None;
+                    let mut len: f64 = 0.0;
+                    for n in notes.iter() {
+                        if let Message::Note(p, l, _vel) = n {
+                            pitch.get_or_insert_with(|| p.clone());
+                            len = len.max(Pattern::to_beats(l, measure));
+                        }
+                    }
+                    steps.push((pitch.unwrap_or(Pitch::Rest), pos, len));
+                    pos += len;
+                    last_len = Some(len);
+                }
+                Message::Tie => {
+                    if let Some(len) = last_len {
+                        if let Some(step) = steps.last_mut() {
+                            step.2 += len;
+                        }
+                        pos += len;
+                    }
+                }
+                Message::Loop => {
+                    looped = true;
+                    break;
+                }
+            }
+        }
+        PatternSteps {
+            measure: measure.clone(),
+            steps,
+            len: pos,
+            looped,
+        }
+    }
+
+    // the note sounding at `pos` from the start of the pattern and how far into it (0.0 to 1.0)
+    pub fn note_at(&self, pos: &Pos, measure: &Measure) -> Option<(Pitch, f64)> {
+        self.steps(measure).note_at(pos)
+    }
+
+    // the length of one pass of the pattern, up to `loop` if any
+    pub fn total_len(&self, measure: &Measure) -> Pos {
+        let len = self.steps(measure).len;
+        let origin = Pos {
+            bar: 0,
+            beat: 0,
+            pos: 0.0,
+        };
+        origin.add(len, measure)
+    }
+
+    fn dump_msg(msg: &Message, measure: &Measure) -> String {
+        match msg {
+            Message::Note(pitch, len, vel) => {
+                let pitch_s = to_str(pitch);
+                let len_s = to_len(len, measure);
+                if *vel == MAX_VELOCITY {
+                    format!("{}:{}", pitch_s, len_s)
+                } else {
+                    format!("{}:{}:{}", pitch_s, len_s, vel)
+                }
+            }
+            Message::Chord(notes) => {
+                let notes: Vec<String> = notes
+                    .iter()
+                    .map(|n| Pattern::dump_msg(n, measure))
+                    .collect();
+                format!("[{}]", notes.join(" "))
+            }
+            Message::Loop => "loop".to_string(),
+            Message::Tie => "~".to_string(),
+        }
+    }
+}
+
+impl PatternSteps {
+    // same as `Pattern::note_at` for the measure these steps were laid out in
+    pub fn note_at(&self, pos: &Pos) -> Option<(Pitch, f64)> {
+        let mut t = Pattern::to_beats(pos, &self.measure);
+        if self.looped && self.len > 0.0 {
+            t %= self.len;
+        }
+
+        self.steps
+            .iter()
+            .find(|(_, start, l)| *start <= t && t < start + l)
+            .filter(|(pitch, _, _)| !matches!(pitch, Pitch::Rest))
+            .map(|(pitch, start, l)| (pitch.clone(), (t - start) / l))
+    }
 }
 
 impl Walk for Pattern {
@@ -185,19 +739,16 @@ impl Walk for Pattern {
 }
 
 impl Dump for Pattern {
+    fn name(&self) -> &'static str {
+        ""
+    }
+
     fn dump(&self, _shared_vec: &Vec<Aug>) -> UgNode {
         let mut vec = Vec::new();
         let m = Measure { beat: 4, note: 4 };
 
         for ev in self.0.lock().unwrap().iter() {
-            match &**ev {
-                Message::Note(pitch, len) => {
-                    let pitch_s = to_str(&pitch);
-                    let len_s = to_len(&len, &m);
-                    vec.push(format!("{}:{}", pitch_s, len_s));
-                }
-                Message::Loop => vec.push("loop".to_string()),
-            }
+            vec.push(Pattern::dump_msg(ev, &m));
         }
         UgNode::Val(Value::Pattern(vec))
     }
@@ -219,6 +770,15 @@ impl Walk for UG {
 }
 
 impl Dump for UG {
+    fn name(&self) -> &'static str {
+        match self {
+            UG::Proc(u) => u.name(),
+            UG::Osc(u) => u.name(),
+            UG::Eg(u) => u.name(),
+            UG::Val(_) | UG::Tab(_) | UG::Pat(_) => "",
+        }
+    }
+
     fn dump(&self, shared_vec: &Vec<Aug>) -> UgNode {
         match self {
             UG::Val(v) => UgNode::Val(Value::Number(*v)),
@@ -231,20 +791,83 @@ impl Dump for UG {
     }
 }
 
+impl UG {
+    fn kind(&self) -> &'static str {
+        match self {
+            UG::Tab(_) => "table",
+            UG::Pat(_) => "pattern",
+            _ => "signal",
+        }
+    }
+}
+
 impl Operate for UG {
-    fn get(&self, _pname: &str) -> Result<Aug, OperateError> {
-        Err(OperateError::NotUgen)
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match self {
+            UG::Proc(u) => u.get(pname),
+            UG::Osc(u) => u.get(pname),
+            UG::Eg(u) => u.get(pname),
+            _ => Err(OperateError::NotUgen),
+        }
+    }
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self {
+            UG::Proc(u) => u.get_str(pname),
+            UG::Osc(u) => u.get_str(pname),
+            UG::Eg(u) => u.get_str(pname),
+            _ => Err(OperateError::NotUgen),
+        }
     }
-    fn get_str(&self, _pname: &str) -> Result<String, OperateError> {
-        Err(OperateError::NotUgen)
+    // a slot only takes the same kind (signal, table or pattern) as its current value
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        if let Ok(current) = self.get(pname) {
+            let expected = current.0.lock().unwrap().ug.kind();
+            if ug.0.lock().unwrap().ug.kind() != expected {
+                return Err(OperateError::TypeMismatch(
+                    format!("{}/{}", self.name(), pname),
+                    expected.to_string(),
+                ));
+            }
+        }
+        match self {
+            UG::Proc(u) => u.set(pname, ug),
+            UG::Osc(u) => u.set(pname, ug),
+            UG::Eg(u) => u.set(pname, ug),
+            _ => Err(OperateError::NotUgen),
+        }
     }
-    fn set(&mut self, _pname: &str, _ug: Aug) -> Result<bool, OperateError> {
-        Ok(true)
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        match self {
+            UG::Proc(u) => u.set_str(pname, data),
+            UG::Osc(u) => u.set_str(pname, data),
+            UG::Eg(u) => u.set_str(pname, data),
+            _ => Err(OperateError::NotUgen),
+        }
     }
-    fn set_str(&mut self, _pname: &str, _data: String) -> Result<bool, OperateError> {
-        Ok(true)
+    fn clear(&mut self, pname: &str) {
+        match self {
+            UG::Proc(u) => u.clear(pname),
+            UG::Osc(u) => u.clear(pname),
+            UG::Eg(u) => u.clear(pname),
+            _ => (),
+        }
+    }
+    fn params(&self) -> Vec<String> {
+        match self {
+            UG::Proc(u) => u.params(),
+            UG::Osc(u) => u.params(),
+            UG::Eg(u) => u.params(),
+            _ => Vec::new(),
+        }
+    }
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match self {
+            UG::Proc(u) => u.param_info(pname),
+            UG::Osc(u) => u.param_info(pname),
+            UG::Eg(u) => u.param_info(pname),
+            _ => None,
+        }
     }
-    fn clear(&mut self, _pname: &str) {}
 }
 
 impl Proc for UG {
@@ -258,6 +881,15 @@ impl Proc for UG {
             UG::Pat(_) => (0.0, 0.0),
         }
     }
+
+    fn reset(&mut self) {
+        match self {
+            UG::Proc(u) => u.reset(),
+            UG::Osc(u) => u.reset(),
+            UG::Eg(u) => u.reset(),
+            _ => (),
+        }
+    }
 }
 
 impl Osc for UG {
@@ -308,15 +940,33 @@ impl Eg for UG {
 
 // trait implementations for UGen
 
+static NEXT_UGEN_ID: AtomicUsize = AtomicUsize::new(0);
+
 impl UGen {
     pub fn new(ug: UG) -> UGen {
         UGen {
-            id: 0, // FIXME
+            id: NEXT_UGEN_ID.fetch_add(1, Ordering::Relaxed),
             last_tick: 0,
             last_sig: (0.0, 0.0),
+            enabled: true,
+            metering: false,
+            peak: 0.0,
+            settles: false,
             ug: ug,
         }
     }
+
+    // bypassed units pass their `src` through, or are silent without it
+    fn bypass(&mut self, transport: &Transport) -> Signal {
+        match &self.ug {
+            UG::Val(_) | UG::Tab(_) | UG::Pat(_) => self.ug.proc(transport),
+            UG::Osc(_) => (0.0, 0.0),
+            _ => match self.get("src") {
+                Ok(mut src) => src.proc(transport),
+                Err(_) => (0.0, 0.0),
+            },
+        }
+    }
 }
 
 impl Walk for UGen {
@@ -326,6 +976,10 @@ impl Walk for UGen {
 }
 
 impl Dump for UGen {
+    fn name(&self) -> &'static str {
+        self.ug.name()
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         self.ug.dump(shared_ug)
     }
@@ -333,48 +987,31 @@ impl Dump for UGen {
 
 impl Operate for UGen {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
-        match &self.ug {
-            UG::Proc(u) => u.get(pname),
-            UG::Osc(u) => u.get(pname),
-            UG::Eg(u) => u.get(pname),
-            _ => Err(OperateError::NotUgen),
-        }
+        self.ug.get(pname)
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
-        match &self.ug {
-            UG::Proc(u) => u.get_str(pname),
-            UG::Osc(u) => u.get_str(pname),
-            UG::Eg(u) => u.get_str(pname),
-            _ => Err(OperateError::NotUgen),
-        }
+        self.ug.get_str(pname)
     }
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
-        match &mut self.ug {
-            UG::Proc(u) => u.set(pname, ug),
-            UG::Osc(u) => u.set(pname, ug),
-            UG::Eg(u) => u.set(pname, ug),
-            _ => Err(OperateError::NotUgen),
-        }
+        self.ug.set(pname, ug)
     }
 
     fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
-        match &mut self.ug {
-            UG::Proc(u) => u.set_str(pname, data),
-            UG::Osc(u) => u.set_str(pname, data),
-            UG::Eg(u) => u.set_str(pname, data),
-            _ => Err(OperateError::NotUgen),
-        }
+        self.ug.set_str(pname, data)
     }
 
     fn clear(&mut self, pname: &str) {
-        match &mut self.ug {
-            UG::Proc(u) => u.clear(pname),
-            UG::Osc(u) => u.clear(pname),
-            UG::Eg(u) => u.clear(pname),
-            _ => (),
-        }
+        self.ug.clear(pname)
+    }
+
+    fn params(&self) -> Vec<String> {
+        self.ug.params()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        self.ug.param_info(pname)
     }
 }
 
@@ -384,11 +1021,32 @@ impl Proc for UGen {
             self.last_sig
         } else {
             self.last_tick = transport.tick;
-            let sig = self.ug.proc(transport);
+            let sig = if self.enabled {
+                self.ug.proc(transport)
+            } else {
+                self.bypass(transport)
+            };
+            if self.metering {
+                self.peak = self.peak.max(sig.0.abs()).max(sig.1.abs());
+            }
+            if self.settles {
+                if let UG::Proc(u) = &self.ug {
+                    if let Some(v) = u.settled() {
+                        self.ug = UG::Val(v);
+                        self.settles = false;
+                    }
+                }
+            }
             self.last_sig = sig;
             sig
         }
     }
+
+    fn reset(&mut self) {
+        self.last_tick = 0;
+        self.last_sig = (0.0, 0.0);
+        self.ug.reset();
+    }
 }
 
 // trait implementations for Aug
@@ -402,6 +1060,88 @@ impl Aug {
         Aug::new(UGen::new(UG::Val(v)))
     }
 
+    pub fn set_bypass(&self, bypass: bool) {
+        self.0.lock().unwrap().enabled = !bypass;
+    }
+
+    pub fn is_bypassed(&self) -> bool {
+        !self.0.lock().unwrap().enabled
+    }
+
+    // evaluates the graph once at `transport.tick`, ignoring signals memoized by earlier calls.
+    // only units reading the transport, such as gates and sequencers, follow the tick. the
+    // others ignore it: oscillators just advance their phase by one step per call, and
+    // filters, delays and envelopes go on from the samples they have processed before, so
+    // sample them sequentially to get the same output as playback
+    pub fn proc_at(&self, transport: &Transport) -> Signal {
+        let mut visited = vec![self.clone()];
+        self.0.lock().unwrap().last_tick = u64::MAX;
+        self.walk(&mut |u: &Aug| {
+            if visited.contains(u) {
+                false
+            } else {
+                visited.push(u.clone());
+                u.0.lock().unwrap().last_tick = u64::MAX;
+                true
+            }
+        });
+        self.0.lock().unwrap().proc(transport)
+    }
+
+    // renders `n` samples, advancing `transport` before each one so that no sample
+    // is taken at an already processed tick
+    pub fn proc_n(&mut self, transport: &mut Transport, n: usize) -> Vec<Signal> {
+        let mut out = Vec::with_capacity(n);
+        for _ in 0..n {
+            transport.inc();
+            out.push(self.0.lock().unwrap().proc(transport));
+        }
+        out
+    }
+
+    // renders exactly one cycle of an oscillator into `len` points, running it at 1 Hz while
+    // rendering at `len` samples per second
+    pub fn to_table(&self, len: usize, transport: &Transport) -> Table {
+        let mut ug = self.0.lock().unwrap();
+        let (freq, ph) = (ug.ug.get_freq(), ug.ug.get_ph());
+        ug.ug.set_freq(Aug::val(1.0));
+        ug.ug.set_ph(0.0);
+
+        // starts from tick 1 because fresh units regard tick 0 as already processed
+        let mut transport = Transport {
+            sample_rate: len as u32,
+            tick: 1,
+            bpm: transport.bpm,
+            measure: transport.measure.clone(),
+            ..Transport::default()
+        };
+        let mut table = Vec::with_capacity(len);
+        for _ in 0..len {
+            table.push(ug.ug.proc(&transport).0);
+            transport.inc();
+        }
+
+        ug.ug.set_freq(freq);
+        ug.ug.set_ph(ph);
+        Table::new(table)
+    }
+
+    // resets this unit and all units under it
+    pub fn reset(&self) {
+        let mut searched_units: Vec<Aug> = Vec::new();
+
+        self.0.lock().unwrap().reset();
+        self.walk(&mut |u: &Aug| {
+            if searched_units.contains(u) {
+                false
+            } else {
+                searched_units.push(u.clone());
+                u.0.lock().unwrap().reset();
+                true
+            }
+        });
+    }
+
     pub fn to_val(&self) -> Option<f64> {
         match self.0.lock().unwrap().ug {
             UG::Val(v) => Some(v),
@@ -416,6 +1156,19 @@ impl Clone for Aug {
     }
 }
 
+// lets constants be written as literals, e.g. `Sine::new(0.0.into(), 440.into())`
+impl From<f64> for Aug {
+    fn from(v: f64) -> Aug {
+        Aug::val(v)
+    }
+}
+
+impl From<i32> for Aug {
+    fn from(v: i32) -> Aug {
+        Aug::val(v as f64)
+    }
+}
+
 impl PartialEq for Aug {
     fn eq(&self, other: &Self) -> bool {
         Arc::ptr_eq(&self.0, &other.0)
@@ -437,6 +1190,10 @@ impl Walk for Aug {
 }
 
 impl Dump for Aug {
+    fn name(&self) -> &'static str {
+        self.0.lock().unwrap().name()
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         self.0.lock().unwrap().dump(shared_ug)
     }
@@ -458,10 +1215,468 @@ impl Operate for Aug {
     fn clear(&mut self, pname: &str) {
         self.0.lock().unwrap().clear(pname)
     }
+    fn params(&self) -> Vec<String> {
+        self.0.lock().unwrap().params()
+    }
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        self.0.lock().unwrap().param_info(pname)
+    }
 }
 
 impl Proc for Aug {
     fn proc(&mut self, transport: &Transport) -> Signal {
         self.0.lock().unwrap().proc(transport)
     }
+
+    fn reset(&mut self) {
+        Aug::reset(self);
+    }
+}
+
+// goes linearly from `from` to `to` in `len` samples, made by `Operate::set_ramp`.
+// it dumps as `to` since it turns into that value on arrival
+pub struct Ramp {
+    from: f64,
+    to: f64,
+    len: u64,
+    pos: u64,
+}
+
+impl Ramp {
+    pub fn new(from: f64, to: f64, len: u64) -> Aug {
+        let mut ugen = UGen::new(UG::Proc(Box::new(Ramp {
+            from,
+            to,
+            len,
+            pos: 0,
+        })));
+        ugen.settles = true;
+        Aug::new(ugen)
+    }
+}
+
+impl Walk for Ramp {
+    fn walk(&self, _f: &mut dyn FnMut(&Aug) -> bool) {}
+}
+
+impl Dump for Ramp {
+    fn name(&self) -> &'static str {
+        "ramp"
+    }
+
+    fn dump(&self, _shared_ug: &Vec<Aug>) -> UgNode {
+        UgNode::Val(Value::Number(self.to))
+    }
+}
+
+impl Operate for Ramp {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        Err(OperateError::ParamNotFound(format!("ramp/{}", pname)))
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        Err(OperateError::ParamNotFound(format!("ramp/{}", pname)))
+    }
+
+    fn set(&mut self, pname: &str, _ug: Aug) -> Result<bool, OperateError> {
+        Err(OperateError::ParamNotFound(format!("ramp/{}", pname)))
+    }
+
+    fn set_str(&mut self, pname: &str, _data: String) -> Result<bool, OperateError> {
+        Err(OperateError::ParamNotFound(format!("ramp/{}", pname)))
+    }
+
+    fn clear(&mut self, _pname: &str) {}
+
+    fn params(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Ramp {
+    fn proc(&mut self, _transport: &Transport) -> Signal {
+        if self.pos >= self.len {
+            return (self.to, self.to);
+        }
+        let v = self.from + (self.to - self.from) * self.pos as f64 / self.len as f64;
+        self.pos += 1;
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.pos = 0;
+    }
+
+    fn settled(&self) -> Option<f64> {
+        if self.pos >= self.len {
+            Some(self.to)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::scale::Scale;
+    use crate::ugens::fx::LPFilter;
+    use crate::ugens::misc::Gain;
+    use crate::ugens::misc::Gate;
+    use crate::ugens::osc::Sine;
+    use crate::ugens::osc::WaveTable;
+
+    #[test]
+    fn note_velocity_is_parsed_after_length() {
+        match Pattern::parse_str_1("c4:4:100") {
+            Ok(Message::Note(Pitch::Pitch(3, 4), len, 100)) => assert_eq!(len, to_pos(4)),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(Pattern::parse_str_1("c4:4:128").is_err());
+    }
+
+    #[test]
+    fn note_velocity_defaults_to_max() {
+        match Pattern::parse_str_1("c4:4") {
+            Ok(Message::Note(_, _, vel)) => assert_eq!(vel, MAX_VELOCITY),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn chord_notes_share_one_start() {
+        let msgs = Pattern::parse_str("c4:4 [c4:4 e4:4 g4:4]".to_string()).unwrap();
+        assert_eq!(msgs.len(), 2);
+        match &*msgs[1] {
+            Message::Chord(notes) => {
+                assert_eq!(notes.len(), 3);
+                assert!(notes.iter().all(|n| matches!(n, Message::Note(_, _, _))));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let measure = Measure { beat: 4, note: 4 };
+        let pat = Pattern::new(msgs);
+        let steps = pat.steps(&measure).steps;
+        // the whole chord is one step starting after the first note
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].1, Pattern::to_beats(&to_pos(4), &measure));
+        assert_eq!(pat.pitches().len(), 4);
+
+        let chord = &pat.0.lock().unwrap()[1];
+        assert_eq!(Pattern::dump_msg(chord, &measure), "[c4:4 e4:4 g4:4]");
+    }
+
+    fn hits(pulses: u32, steps: u32) -> Vec<usize> {
+        Pattern::euclidean(pulses, steps, "c4", 3)
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| matches!(***m, Message::Note(Pitch::Pitch(_, _), _, _)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn euclidean_spreads_hits_evenly() {
+        // x..x..x.
+        assert_eq!(hits(3, 8), vec![0, 3, 6]);
+        // x.xx.xx.
+        assert_eq!(hits(5, 8), vec![0, 2, 3, 5, 6]);
+        assert_eq!(Pattern::euclidean(5, 8, "c4", 3).len(), 8);
+    }
+
+    #[test]
+    fn bypassed_filter_passes_its_source() {
+        let sine = || Sine::new(Aug::val(0.0), Aug::val(880.0));
+        let mut lpf = LPFilter::new(Aug::val(200.0), Aug::val(1.0), sine());
+        lpf.set_bypass(true);
+        let dry = sine().proc_n(&mut Transport::default(), 441);
+        assert_eq!(lpf.proc_n(&mut Transport::default(), 441), dry);
+
+        // and filters again when enabled
+        lpf.set_bypass(false);
+        let wet = lpf.proc_n(&mut Transport::default(), 441);
+        assert_ne!(wet, dry);
+    }
+
+    #[test]
+    fn bypassed_oscillator_is_silent() {
+        let mut sine = Sine::new(Aug::val(0.0), Aug::val(880.0));
+        sine.set_bypass(true);
+        let out = sine.proc_n(&mut Transport::default(), 441);
+        assert!(out.iter().all(|s| *s == (0.0, 0.0)));
+    }
+
+    fn temp_file(name: &str, bytes: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("tapirus-{}-{}", std::process::id(), name));
+        fs::write(&path, bytes).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn table_reads_number_files() {
+        let path = temp_file("table.txt", b"0 0.5\n1\t-0.5\n");
+        assert_eq!(Table::from_file(&path).unwrap(), vec![0.0, 0.5, 1.0, -0.5]);
+        fs::write(&path, b"0 zero").unwrap();
+        assert!(Table::from_file(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn table_reads_mono_wav_files() {
+        let samples: [i16; 3] = [0, 16384, -32768];
+        let data: Vec<u8> = samples
+            .iter()
+            .flat_map(|s| s.to_le_bytes().to_vec())
+            .collect();
+        let mut wav = Vec::new();
+        wav.extend(b"RIFF");
+        wav.extend(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(&16u32.to_le_bytes());
+        // PCM, mono, 44100 Hz, 88200 bytes/s, 2 bytes/frame, 16 bits
+        for field in [1u16, 1].iter() {
+            wav.extend(&field.to_le_bytes());
+        }
+        wav.extend(&44100u32.to_le_bytes());
+        wav.extend(&88200u32.to_le_bytes());
+        for field in [2u16, 16].iter() {
+            wav.extend(&field.to_le_bytes());
+        }
+        wav.extend(b"data");
+        wav.extend(&(data.len() as u32).to_le_bytes());
+        wav.extend(&data);
+
+        let path = temp_file("table.wav", &wav);
+        assert_eq!(Table::from_file(&path).unwrap(), vec![0.0, 0.5, -1.0]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sine_renders_into_one_cycle_table() {
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let table = sine.to_table(1024, &Transport::default());
+        let data = table.0.lock().unwrap();
+        assert_eq!(data.len(), 1024);
+        for (i, v) in data.iter().enumerate() {
+            let expected = (2.0 * std::f64::consts::PI * i as f64 / 1024.0).sin();
+            assert!((v - expected).abs() < 1e-9);
+        }
+        // the oscillator is left as it was
+        assert_eq!(sine.get("freq").unwrap().to_val(), Some(440.0));
+    }
+
+    #[test]
+    fn proc_at_samples_a_gate_at_any_tick() {
+        let pattern = Pattern::parse_str("c4:3 r:3 loop".to_string()).unwrap();
+        let gate = Gate::new(Aug::new(UGen::new(UG::Pat(Pattern::new(pattern)))));
+        // a beat is 22050 samples, so the gate is open over the first half of every 44100
+        let closed_form = |tick: u64| if tick % 44100 < 22050 { 1.0 } else { 0.0 };
+        let transport = Transport::default();
+        for n in (0..100).rev() {
+            let tick = 1000 + n * 3001;
+            assert_eq!(gate.proc_at(&transport.at(tick)).0, closed_form(tick));
+        }
+        // a tick processed before is evaluated again rather than read from memo
+        assert_eq!(gate.proc_at(&transport.at(1000)).0, 1.0);
+        assert_eq!(gate.proc_at(&transport.at(23050)).0, 0.0);
+    }
+
+    #[test]
+    fn proc_at_advances_a_sine_one_step_per_call() {
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let closed_form = |n: u64| (2.0 * std::f64::consts::PI * 440.0 * n as f64 / 44100.0).sin();
+        let transport = Transport::default();
+        // the sine keeps its own phase, so the tick it is sampled at does not matter
+        for n in 0..100 {
+            let tick = 1000 + (n * 7919) % 5000;
+            assert!((sine.proc_at(&transport.at(tick)).0 - closed_form(n)).abs() < 1e-9);
+        }
+        assert!((sine.proc_at(&transport.at(1000)).0 - closed_form(100)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn note_at_reports_the_active_note_and_progress() {
+        let measure = Measure { beat: 4, note: 4 };
+        let pat = Pattern::new(Pattern::parse_str("c4:4 e4:4 g4:4".to_string()).unwrap());
+        let note = Pattern::to_beats(&to_pos(4), &measure);
+        let origin = Pos {
+            bar: 0,
+            beat: 0,
+            pos: 0.0,
+        };
+        let at = |beats: f64| pat.note_at(&origin.add(beats, &measure), &measure);
+
+        assert_eq!(pat.total_len(&measure), origin.add(note * 3.0, &measure));
+        match at(0.0) {
+            Some((Pitch::Pitch(3, 4), p)) => assert_eq!(p, 0.0),
+            other => panic!("unexpected {:?}", other),
+        }
+        match at(note * 1.5) {
+            Some((Pitch::Pitch(7, 4), p)) => assert!((p - 0.5).abs() < 1e-9),
+            other => panic!("unexpected {:?}", other),
+        }
+        match at(note * 2.75) {
+            Some((Pitch::Pitch(10, 4), p)) => assert!((p - 0.75).abs() < 1e-9),
+            other => panic!("unexpected {:?}", other),
+        }
+        // nothing sounds after the end of patterns without `loop`
+        assert!(at(note * 3.0).is_none());
+    }
+
+    #[test]
+    fn units_are_named_as_they_dump() {
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let gain = Gain::new(Aug::val(0.5), sine.clone());
+        for unit in [sine, gain].iter() {
+            match unit.dump(&vec![]) {
+                UgNode::Ug(name, _) | UgNode::UgRest(name, _, _, _) => {
+                    assert_eq!(unit.name(), name)
+                }
+                UgNode::Val(_) => panic!("dumped as a value"),
+            }
+        }
+        assert_eq!(Aug::val(1.0).name(), "");
+    }
+
+    #[test]
+    fn slots_reject_values_of_another_kind() {
+        let pattern = Aug::new(UGen::new(UG::Pat(Pattern::new(Vec::new()))));
+        let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 1.0]))));
+
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        match sine.0.lock().unwrap().set("freq", pattern) {
+            Err(OperateError::TypeMismatch(param, expected)) => {
+                assert_eq!(param, "sine/freq");
+                assert_eq!(expected, "signal");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(sine.0.lock().unwrap().get_str("freq").unwrap(), "440");
+
+        let wavetable = Aug::new(UGen::new(UG::Osc(Box::new(WaveTable {
+            table: table.clone(),
+            ph: Aug::val(0.0),
+        }))));
+        let mut wavetable = wavetable.0.lock().unwrap();
+        assert!(wavetable.set("table", Aug::val(1.0)).is_err());
+        assert!(wavetable.set("table", table).is_ok());
+        assert!(wavetable.set("ph", Aug::val(0.5)).is_ok());
+    }
+
+    #[test]
+    fn literals_convert_into_constant_units() {
+        assert_eq!(Aug::from(440.0).to_val(), Some(440.0));
+        assert_eq!(Aug::from(3).to_val(), Some(3.0));
+
+        let mut literal = Sine::new(0.into(), 440.0.into());
+        let mut explicit = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        assert_eq!(
+            literal.proc_n(&mut Transport::default(), 100),
+            explicit.proc_n(&mut Transport::default(), 100)
+        );
+    }
+
+    #[test]
+    fn proc_n_renders_a_fresh_sample_each_time() {
+        let mut ramp = Ramp::new(0.0, 1.0, 100);
+        let mut transport = Transport::default();
+        let out = ramp.proc_n(&mut transport, 50);
+        assert_eq!(out.len(), 50);
+        assert_eq!(transport.tick, 50);
+        assert!(out.windows(2).all(|w| w[1].0 > w[0].0));
+
+        // goes on from where the transport is
+        let more = ramp.proc_n(&mut transport, 10);
+        assert!(more[0].0 > out[49].0);
+    }
+
+    fn parsed(s: &str) -> String {
+        format!("{:?}", Pattern::parse_str(s.to_string()).unwrap())
+    }
+
+    fn dumped(pat: &Pattern) -> String {
+        format!("{:?}", *pat.0.lock().unwrap())
+    }
+
+    #[test]
+    fn transpose_and_quantize_keep_rests_and_loops() {
+        let run = Pattern::new(
+            Pattern::parse_str("c4:4 d4:4 e4:4 r:4 f4:4 g4:4 a4:4 b4:4 c5:4 loop".to_string())
+                .unwrap(),
+        );
+        assert_eq!(
+            dumped(&run.transpose(7)),
+            parsed("g4:4 a4:4 b4:4 r:4 c5:4 d5:4 e5:4 f+5:4 g5:4 loop")
+        );
+        assert_eq!(dumped(&run.transpose(7).transpose(-7)), dumped(&run));
+
+        // ties between two degrees go to the lower one. a flat is spelled g+ as to_note
+        // cannot lower a
+        let chromatic = Pattern::new(
+            Pattern::parse_str(
+                "c4:8 c+4:8 d4:8 d+4:8 e4:8 f4:8 f+4:8 g4:8 g+4:8 a4:8 a+4:8 b4:8".to_string(),
+            )
+            .unwrap(),
+        );
+        let root = match to_note("c") {
+            Some(Pitch::Pitch(n, _)) => n,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(
+            dumped(&chromatic.quantize_scale(&Scale::Minor, root)),
+            parsed("c4:8 c4:8 d4:8 e-4:8 e-4:8 f4:8 f4:8 g4:8 g+4:8 g+4:8 b-4:8 b-4:8")
+        );
+    }
+
+    #[test]
+    fn normalize_and_windows_shape_tables() {
+        // one cycle of a sine scaled by 0.5
+        let data: Vec<f64> = (0..64)
+            .map(|n| 0.5 * (2.0 * std::f64::consts::PI * n as f64 / 64.0).sin())
+            .collect();
+        let table = Table::new(data.clone());
+        table.normalize();
+        let normalized = table.0.lock().unwrap().clone();
+        let peak = normalized.iter().fold(0.0f64, |p, v| p.max(v.abs()));
+        assert!((peak - 1.0).abs() < 1e-12);
+        for (n, v) in normalized.iter().enumerate() {
+            assert!((v - data[n] * 2.0).abs() < 1e-12);
+        }
+        let silent = Table::new(vec![0.0; 8]);
+        silent.normalize();
+        assert_eq!(*silent.0.lock().unwrap(), vec![0.0; 8]);
+
+        let window = Table::new(vec![1.0; 65]);
+        window.apply_window(WindowKind::Hann);
+        let hann = window.0.lock().unwrap().clone();
+        assert!(hann[0].abs() < 1e-12);
+        assert!(hann[64].abs() < 1e-12);
+        assert!((hann[32] - 1.0).abs() < 1e-12);
+        assert!((hann[16] - hann[48]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn set_ramp_moves_a_gain_over_samples() {
+        let mut gain = Gain::new(Aug::val(0.0), Aug::val(1.0));
+        assert!(gain.0.lock().unwrap().set_ramp("gain", 1.0, 100).is_ok());
+        let out = gain.proc_n(&mut Transport::default(), 200);
+        assert_eq!(out[0], (0.0, 0.0));
+        assert!((out[50].0 - 0.5).abs() < 1e-9);
+        assert!(out.windows(2).all(|w| w[1].0 >= w[0].0));
+        assert!(out[100..].iter().all(|s| *s == (1.0, 1.0)));
+        // the ramp turns back into a constant on arrival
+        assert_eq!(gain.0.lock().unwrap().get_str("gain").unwrap(), "1");
+
+        let driven = Gain::new(Sine::new(Aug::val(0.0), Aug::val(1.0)), Aug::val(1.0));
+        let refused = driven.0.lock().unwrap().set_ramp("gain", 1.0, 100);
+        match refused {
+            Err(OperateError::TypeMismatch(name, _)) => assert_eq!(name, "gain/gain"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
 }
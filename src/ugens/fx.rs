@@ -4,7 +4,8 @@ use crate::musical_time::time::Transport;
 use crate::tapirlisp::types::Env;
 
 use super::core::{
-    Aug, Dump, Operate, OperateError, Proc, Signal, Slot, UGen, UgNode, Value, Walk, UG,
+    Aug, Dump, Operate, OperateError, ParamInfo, ParamUnit, Proc, Signal, Slot, Table, UGen,
+    UgNode, Value, Walk, UG,
 };
 
 pub struct LPFilter {
@@ -42,6 +43,10 @@ impl Walk for LPFilter {
 }
 
 impl Dump for LPFilter {
+    fn name(&self) -> &'static str {
+        "lpf"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -171,6 +176,18 @@ impl Operate for LPFilter {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["freq", "q", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 1000.0, ParamUnit::Hz)),
+            "q" => Some(ParamInfo::new(0.1, 20.0, 1.0, ParamUnit::Ratio)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for LPFilter {
@@ -185,36 +202,56 @@ impl Proc for LPFilter {
         let (b0, b1, b2) = ((1.0 - cw) / 2.0, 1.0 - cw, (1.0 - cw) / 2.0);
         let (a0, a1, a2) = (1.0 + a, -2.0 * cw, 1.0 - a);
 
-        let filter = |v, in0, in1, out0, out1| {
-            (b0 / a0 * v) + (b1 / a0 * in0) + (b2 / a0 * in1) - (a1 / a0 * out0) - (a2 / a0 * out1)
-        };
+        biquad(
+            (b0, b1, b2),
+            (a0, a1, a2),
+            &mut self.inbuf,
+            &mut self.outbuf,
+            (sl, sr),
+        )
+    }
 
-        let l = filter(
-            sl,
-            self.inbuf[0].0,
-            self.inbuf[1].0,
-            self.outbuf[0].0,
-            self.outbuf[1].0,
-        );
-        let r = filter(
-            sr,
-            self.inbuf[0].1,
-            self.inbuf[1].1,
-            self.outbuf[0].1,
-            self.outbuf[1].1,
-        );
+    fn reset(&mut self) {
+        self.inbuf = [(0.0, 0.0); 2];
+        self.outbuf = [(0.0, 0.0); 2];
+    }
+}
 
-        self.inbuf[1] = self.inbuf[0];
-        self.inbuf[0] = (sl, sr);
-        self.outbuf[1] = self.outbuf[0];
-        self.outbuf[0] = (l, r);
+// values under about -300dB are flushed so silent tails never become denormals
+const DENORMAL_THRESHOLD: f64 = 1e-15;
 
-        (l, r)
-    }
+fn flush_denormal(sig: Signal) -> Signal {
+    let flush = |v: f64| if v.abs() < DENORMAL_THRESHOLD { 0.0 } else { v };
+    (flush(sig.0), flush(sig.1))
+}
+
+// direct form I biquad over two samples of input and output history
+fn biquad(
+    b: (f64, f64, f64),
+    a: (f64, f64, f64),
+    inbuf: &mut [Signal; 2],
+    outbuf: &mut [Signal; 2],
+    sig: Signal,
+) -> Signal {
+    let (b0, b1, b2) = b;
+    let (a0, a1, a2) = a;
+    let filter = |v, in0, in1, out0, out1| {
+        (b0 / a0 * v) + (b1 / a0 * in0) + (b2 / a0 * in1) - (a1 / a0 * out0) - (a2 / a0 * out1)
+    };
+
+    let l = filter(sig.0, inbuf[0].0, inbuf[1].0, outbuf[0].0, outbuf[1].0);
+    let r = filter(sig.1, inbuf[0].1, inbuf[1].1, outbuf[0].1, outbuf[1].1);
+
+    inbuf[1] = inbuf[0];
+    inbuf[0] = sig;
+    outbuf[1] = outbuf[0];
+    outbuf[0] = flush_denormal((l, r));
+
+    outbuf[0]
 }
 
 pub struct Delay {
-    buffer: VecDeque<Box<Signal>>,
+    buffer: VecDeque<Signal>,
     time: Aug,
     feedback: Aug,
     mix: Aug,
@@ -223,10 +260,10 @@ pub struct Delay {
 
 impl Delay {
     pub fn new(time: Aug, feedback: Aug, mix: Aug, src: Aug, env: &Env) -> Aug {
-        let len = (env.transport.sample_rate * 2) as usize;
+        let len = (env.sample_rate() * 2) as usize;
         let mut buffer = VecDeque::with_capacity(len);
         for _n in 0..len {
-            buffer.push_back(Box::new((0.0, 0.0)));
+            buffer.push_back((0.0, 0.0));
         }
         Aug::new(UGen::new(UG::Proc(Box::new(Delay {
             buffer: buffer,
@@ -256,6 +293,10 @@ impl Walk for Delay {
 }
 
 impl Dump for Delay {
+    fn name(&self) -> &'static str {
+        "delay"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -411,28 +452,52 @@ impl Operate for Delay {
             _ => (),
         };
     }
-}
 
-// TODO: factor out; same function is in `sequencer.rs`
-fn sec_to_sample_num(sec: f64, transport: &Transport) -> u64 {
-    (transport.sample_rate as f64 * sec) as u64
+    fn params(&self) -> Vec<String> {
+        ["time", "feedback", "mix", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "time" => Some(ParamInfo::new(0.0, 2.0, 0.25, ParamUnit::Seconds)),
+            "feedback" => Some(ParamInfo::new(
+                0.0,
+                DELAY_MAX_FEEDBACK,
+                0.5,
+                ParamUnit::Normalized,
+            )),
+            "mix" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
 }
 
+// feedback is clamped into +-DELAY_MAX_FEEDBACK so taps always decay. with 1.0 or more
+// every tap would be as loud as the source or louder, and their sum runs away
+const DELAY_MAX_FEEDBACK: f64 = 0.98;
+
 impl Proc for Delay {
     fn proc(&mut self, transport: &Transport) -> Signal {
         self.buffer.pop_back();
         let sig = self.src.proc(transport);
-        self.buffer.push_front(Box::new(sig));
-        let dtime = self.time.proc(transport).0;
-        let dt = sec_to_sample_num(dtime, transport);
-        let fb = self.feedback.proc(transport).0;
+        self.buffer.push_front(flush_denormal(sig));
+        // kept fractional so that modulating `time` glides instead of stepping
+        let dt = transport.sample_rate as f64 * self.time.proc(transport).0;
+        let fb = self
+            .feedback
+            .proc(transport)
+            .0
+            .clamp(-DELAY_MAX_FEEDBACK, DELAY_MAX_FEEDBACK);
         let mix = self.mix.proc(transport).0;
 
         let (mut dl, mut dr) = (0.0, 0.0);
         let mut n = 1;
-        while dt != 0 && n * dt < self.buffer.len() as u64 {
-            let (l, r) = **self.buffer.get((n * dt) as usize).unwrap();
-            let fbr = fb.powi(n as i32);
+        while dt >= 1.0 && n as f64 * dt < self.buffer.len() as f64 {
+            let (l, r) = read_line(&self.buffer, n as f64 * dt);
+            let fbr = fb.powi(n);
             dl += l * fbr;
             dr += r * fbr;
             n += 1;
@@ -440,4 +505,3601 @@ impl Proc for Delay {
 
         (sig.0 + dl * mix, sig.1 + dr * mix)
     }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+    }
+}
+
+// echoes bounce between channels: the source enters the left line, and each line feeds
+// the other one back
+pub struct PingPong {
+    buffer: VecDeque<Signal>,
+    time_l: Aug,
+    time_r: Aug,
+    feedback: Aug,
+    mix: Aug,
+    src: Aug,
+}
+
+impl PingPong {
+    pub fn new(time_l: Aug, time_r: Aug, feedback: Aug, mix: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (env.sample_rate() * 2) as usize;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back((0.0, 0.0));
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(PingPong {
+            buffer,
+            time_l,
+            time_r,
+            feedback,
+            mix,
+            src,
+        }))))
+    }
+}
+
+impl Walk for PingPong {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.time_l) {
+            self.time_l.walk(f);
+        }
+        if f(&self.time_r) {
+            self.time_r.walk(f);
+        }
+        if f(&self.feedback) {
+            self.feedback.walk(f);
+        }
+        if f(&self.mix) {
+            self.mix.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for PingPong {
+    fn name(&self) -> &'static str {
+        "pingpong"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.time_l.clone(),
+            name: "time_l".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.time_l) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.time_l.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.time_r.clone(),
+            name: "time_r".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.time_r) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.time_r.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.feedback.clone(),
+            name: "feedback".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.feedback) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.feedback.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.mix.clone(),
+            name: "mix".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.mix) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.mix.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("pingpong".to_string(), slots)
+    }
+}
+
+impl Operate for PingPong {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "time_l" => Ok(self.time_l.clone()),
+            "time_r" => Ok(self.time_r.clone()),
+            "feedback" => Ok(self.feedback.clone()),
+            "mix" => Ok(self.mix.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("pingpong/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "pingpong/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "time_l" => {
+                self.time_l = ug;
+                Ok(true)
+            }
+            "time_r" => {
+                self.time_r = ug;
+                Ok(true)
+            }
+            "feedback" => {
+                self.feedback = ug;
+                Ok(true)
+            }
+            "mix" => {
+                self.mix = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("pingpong/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "time_l" | "time_r" | "feedback" | "mix" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("pingpong/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("pingpong/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "time_l" | "time_r" | "feedback" | "mix" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["time_l", "time_r", "feedback", "mix", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "time_l" => Some(ParamInfo::new(0.0, 2.0, 0.25, ParamUnit::Seconds)),
+            "time_r" => Some(ParamInfo::new(0.0, 2.0, 0.25, ParamUnit::Seconds)),
+            "feedback" => Some(ParamInfo::new(
+                0.0,
+                DELAY_MAX_FEEDBACK,
+                0.5,
+                ParamUnit::Normalized,
+            )),
+            "mix" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for PingPong {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        let rate = transport.sample_rate as f64;
+        let dt_l = rate * self.time_l.proc(transport).0;
+        let dt_r = rate * self.time_r.proc(transport).0;
+        let fb = self
+            .feedback
+            .proc(transport)
+            .0
+            .clamp(-DELAY_MAX_FEEDBACK, DELAY_MAX_FEEDBACK);
+        let mix = self.mix.proc(transport).0;
+
+        // the front is one sample ago as the current one is not written yet
+        let dl = read_line(&self.buffer, dt_l - 1.0).0;
+        let dr = read_line(&self.buffer, dt_r - 1.0).1;
+        self.buffer.pop_back();
+        let input = (sig.0 + sig.1) / 2.0;
+        self.buffer
+            .push_front(flush_denormal((input + dr * fb, dl * fb)));
+
+        (sig.0 + dl * mix, sig.1 + dr * mix)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+    }
+}
+
+// the right channel is delayed up to this to spread the image (Haas effect)
+const WIDENER_MAX_DELAY: f64 = 0.02;
+
+pub struct Widener {
+    buffer: VecDeque<f64>,
+    amount: Aug,
+    src: Aug,
+}
+
+impl Widener {
+    pub fn new(amount: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (env.sample_rate() as f64 * WIDENER_MAX_DELAY) as usize + 1;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back(0.0);
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(Widener {
+            buffer,
+            amount,
+            src,
+        }))))
+    }
+}
+
+impl Walk for Widener {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.amount) {
+            self.amount.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Widener {
+    fn name(&self) -> &'static str {
+        "width"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.amount.clone(),
+            name: "amount".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.amount) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.amount.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("width".to_string(), slots)
+    }
+}
+
+impl Operate for Widener {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "amount" => Ok(self.amount.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("width/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "width/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "amount" => {
+                self.amount = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("width/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "amount" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("width/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("width/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "amount" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["amount", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "amount" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Widener {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        self.buffer.pop_back();
+        self.buffer.push_front(r);
+
+        let amount = self.amount.proc(transport).0.clamp(0.0, 1.0);
+        let dt = transport.sec_to_samples(amount * WIDENER_MAX_DELAY) as usize;
+        let dt = dt.min(self.buffer.len() - 1);
+
+        (l, self.buffer[dt])
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = 0.0;
+        }
+    }
+}
+
+pub struct Compressor {
+    threshold: Aug,
+    ratio: Aug,
+    attack: Aug,
+    release: Aug,
+    src: Aug,
+    gain: f64,
+}
+
+impl Compressor {
+    pub fn new(threshold: Aug, ratio: Aug, attack: Aug, release: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Compressor {
+            threshold,
+            ratio,
+            attack,
+            release,
+            src,
+            gain: 1.0,
+        }))))
+    }
+}
+
+impl Walk for Compressor {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.threshold) {
+            self.threshold.walk(f);
+        }
+        if f(&self.ratio) {
+            self.ratio.walk(f);
+        }
+        if f(&self.attack) {
+            self.attack.walk(f);
+        }
+        if f(&self.release) {
+            self.release.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Compressor {
+    fn name(&self) -> &'static str {
+        "comp"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.threshold.clone(),
+            name: "threshold".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.threshold) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.threshold.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.ratio.clone(),
+            name: "ratio".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.ratio) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.ratio.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.attack.clone(),
+            name: "attack".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.attack) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.attack.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.release.clone(),
+            name: "release".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.release) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.release.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("comp".to_string(), slots)
+    }
+}
+
+impl Operate for Compressor {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "threshold" => Ok(self.threshold.clone()),
+            "ratio" => Ok(self.ratio.clone()),
+            "attack" => Ok(self.attack.clone()),
+            "release" => Ok(self.release.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("comp/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "comp/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "threshold" => {
+                self.threshold = ug;
+                Ok(true)
+            }
+            "ratio" => {
+                self.ratio = ug;
+                Ok(true)
+            }
+            "attack" => {
+                self.attack = ug;
+                Ok(true)
+            }
+            "release" => {
+                self.release = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("comp/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "threshold" | "ratio" | "attack" | "release" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("comp/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("comp/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "threshold" | "ratio" | "attack" | "release" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["threshold", "ratio", "attack", "release", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "threshold" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            "ratio" => Some(ParamInfo::new(1.0, 20.0, 4.0, ParamUnit::Ratio)),
+            "attack" => Some(ParamInfo::new(0.0, 1.0, 0.01, ParamUnit::Seconds)),
+            "release" => Some(ParamInfo::new(0.0, 2.0, 0.1, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
+}
+
+fn smoothing_coeff(time: f64, transport: &Transport) -> f64 {
+    if time <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (time * transport.sample_rate as f64)).exp()
+    }
+}
+
+impl Proc for Compressor {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let threshold = self.threshold.proc(transport).0;
+        let ratio = self.ratio.proc(transport).0.max(1.0);
+        let attack = self.attack.proc(transport).0;
+        let release = self.release.proc(transport).0;
+
+        let level = l.abs().max(r.abs());
+        let target = if level > threshold && level > 0.0 {
+            (threshold + (level - threshold) / ratio) / level
+        } else {
+            1.0
+        };
+
+        // gain goes down with the attack time and back up with the release time
+        let a = if target < self.gain {
+            smoothing_coeff(attack, transport)
+        } else {
+            smoothing_coeff(release, transport)
+        };
+        self.gain = target + a * (self.gain - target);
+
+        (l * self.gain, r * self.gain)
+    }
+
+    fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+}
+
+// reads the line `delay` samples back, interpolating between neighbours
+fn read_line(buffer: &VecDeque<Signal>, delay: f64) -> Signal {
+    let last = buffer.len() - 1;
+    let delay = delay.max(0.0).min(last as f64);
+    let i = delay.floor() as usize;
+    let j = (i + 1).min(last);
+    let r = delay.fract();
+    let (l1, r1) = buffer[i];
+    let (l2, r2) = buffer[j];
+    (l1 + (l2 - l1) * r, r1 + (r2 - r1) * r)
+}
+
+// the longest delay the LFO sweeps to at full depth
+const CHORUS_MAX_DELAY: f64 = 0.02;
+
+pub struct Chorus {
+    buffer: VecDeque<Signal>,
+    rate: Aug,
+    depth: Aug,
+    mix: Aug,
+    src: Aug,
+    ph: f64,
+}
+
+impl Chorus {
+    pub fn new(rate: Aug, depth: Aug, mix: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (env.sample_rate() as f64 * CHORUS_MAX_DELAY) as usize + 2;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back((0.0, 0.0));
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(Chorus {
+            buffer,
+            rate,
+            depth,
+            mix,
+            src,
+            ph: 0.0,
+        }))))
+    }
+}
+
+impl Walk for Chorus {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.rate) {
+            self.rate.walk(f);
+        }
+        if f(&self.depth) {
+            self.depth.walk(f);
+        }
+        if f(&self.mix) {
+            self.mix.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Chorus {
+    fn name(&self) -> &'static str {
+        "chorus"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.rate.clone(),
+            name: "rate".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.rate) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.rate.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.depth.clone(),
+            name: "depth".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.depth) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.depth.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.mix.clone(),
+            name: "mix".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.mix) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.mix.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("chorus".to_string(), slots)
+    }
+}
+
+impl Operate for Chorus {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "rate" => Ok(self.rate.clone()),
+            "depth" => Ok(self.depth.clone()),
+            "mix" => Ok(self.mix.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("chorus/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "chorus/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "rate" => {
+                self.rate = ug;
+                Ok(true)
+            }
+            "depth" => {
+                self.depth = ug;
+                Ok(true)
+            }
+            "mix" => {
+                self.mix = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("chorus/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "rate" | "depth" | "mix" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("chorus/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("chorus/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "rate" | "depth" | "mix" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["rate", "depth", "mix", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "rate" => Some(ParamInfo::new(0.0, 10.0, 0.5, ParamUnit::Hz)),
+            "depth" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            "mix" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Chorus {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        self.buffer.pop_back();
+        self.buffer.push_front(sig);
+
+        let rate = self.rate.proc(transport).0;
+        let depth = self.depth.proc(transport).0.clamp(0.0, 1.0);
+        let mix = self.mix.proc(transport).0;
+        let sr = transport.sample_rate as f64;
+
+        // right channel LFO runs a quarter cycle ahead
+        let lfo = |ph: f64| 0.5 + 0.5 * (2.0 * std::f64::consts::PI * ph).sin();
+        let max = depth * CHORUS_MAX_DELAY * sr;
+        let wet_l = read_line(&self.buffer, max * lfo(self.ph)).0;
+        let wet_r = read_line(&self.buffer, max * lfo(self.ph + 0.25)).1;
+        self.ph = (self.ph + rate / sr) % 1.0;
+
+        (
+            sig.0 * (1.0 - mix) + wet_l * mix,
+            sig.1 * (1.0 - mix) + wet_r * mix,
+        )
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+        self.ph = 0.0;
+    }
+}
+
+// two all-pass chains whose outputs are about 90 degrees apart over most of the band
+// (coefficients from Olli Niemitalo's Hilbert transformer design)
+const HILBERT_COEFFS: [[f64; 4]; 2] = [
+    [0.6923878, 0.9360654322959, 0.9882295226860, 0.9987488452737],
+    [
+        0.4021921162426,
+        0.8561710882420,
+        0.9722909545651,
+        0.9952884791278,
+    ],
+];
+
+struct AllpassChain {
+    coeffs: [f64; 4],
+    x: [(f64, f64); 4],
+    y: [(f64, f64); 4],
+}
+
+impl AllpassChain {
+    fn new(coeffs: [f64; 4]) -> AllpassChain {
+        AllpassChain {
+            coeffs,
+            x: [(0.0, 0.0); 4],
+            y: [(0.0, 0.0); 4],
+        }
+    }
+
+    fn process(&mut self, v: f64) -> f64 {
+        let mut v = v;
+        for i in 0..4 {
+            let a = self.coeffs[i] * self.coeffs[i];
+            let out = a * (v + self.y[i].1) - self.x[i].1;
+            self.x[i] = (v, self.x[i].0);
+            self.y[i] = (out, self.y[i].0);
+            v = out;
+        }
+        v
+    }
+}
+
+struct Hilbert {
+    re: AllpassChain,
+    im: AllpassChain,
+    re_delayed: f64,
+}
+
+impl Hilbert {
+    fn new() -> Hilbert {
+        Hilbert {
+            re: AllpassChain::new(HILBERT_COEFFS[0]),
+            im: AllpassChain::new(HILBERT_COEFFS[1]),
+            re_delayed: 0.0,
+        }
+    }
+
+    // returns the analytic signal as (real, imaginary)
+    fn process(&mut self, v: f64) -> (f64, f64) {
+        let re = self.re_delayed;
+        self.re_delayed = self.re.process(v);
+        // the second chain leads the first, so it is negated to lag as a Hilbert transform does
+        (re, -self.im.process(v))
+    }
+}
+
+pub struct FreqShift {
+    shift: Aug,
+    src: Aug,
+    ph: f64,
+    hilbert: (Hilbert, Hilbert),
+}
+
+impl FreqShift {
+    pub fn new(shift: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(FreqShift {
+            shift,
+            src,
+            ph: 0.0,
+            hilbert: (Hilbert::new(), Hilbert::new()),
+        }))))
+    }
+}
+
+impl Walk for FreqShift {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.shift) {
+            self.shift.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for FreqShift {
+    fn name(&self) -> &'static str {
+        "fshift"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.shift.clone(),
+            name: "shift".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.shift) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.shift.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("fshift".to_string(), slots)
+    }
+}
+
+impl Operate for FreqShift {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "shift" => Ok(self.shift.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("fshift/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "fshift/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "shift" => {
+                self.shift = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("fshift/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "shift" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("fshift/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("fshift/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "shift" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["shift", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "shift" => Some(ParamInfo::new(-5000.0, 5000.0, 0.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for FreqShift {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let shift = self.shift.proc(transport).0;
+
+        let w = 2.0 * std::f64::consts::PI * self.ph;
+        let (c, s) = (w.cos(), w.sin());
+        self.ph = (self.ph + shift / transport.sample_rate as f64) % 1.0;
+
+        let (lre, lim) = self.hilbert.0.process(l);
+        let (rre, rim) = self.hilbert.1.process(r);
+        (lre * c - lim * s, rre * c - rim * s)
+    }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+        self.hilbert = (Hilbert::new(), Hilbert::new());
+    }
+}
+
+// length of the grains the pitch shifter reads. longer ones smear transients, shorter ones
+// add roughness from the crossfades
+const PITCHSHIFT_WINDOW: f64 = 0.05;
+
+// a jumping pointer may land up to this much further back to be in phase with the other one,
+// enough for periods down to 50 Hz
+const PITCHSHIFT_SEARCH: f64 = 0.02;
+
+// length compared to find where the pointers are in phase, read every few samples
+const PITCHSHIFT_MATCH: f64 = 0.005;
+const PITCHSHIFT_MATCH_STEP: usize = 4;
+
+// offsets tried per sample. a search has until the other pointer jumps, half a grain later
+const PITCHSHIFT_TRIES: usize = 4;
+
+// shifts pitch by `semitones` with two read pointers sweeping through a short delay line at
+// the shifted rate. they run half a window apart and each one fades in and out with a Hann
+// window, so one is always sounding while the other jumps back. out of phase pointers cancel
+// each other while crossfading, so a pointer jumps a bit further to where it matches the other
+pub struct PitchShift {
+    buffer: VecDeque<Signal>,
+    pub semitones: Aug,
+    pub src: Aug,
+    ph: f64,
+    offsets: [f64; 2],
+    search: OffsetSearch,
+}
+
+// where the pointer `tap` lands on its next jump, found a few offsets at a time
+struct OffsetSearch {
+    tap: usize,
+    next: usize,
+    best: usize,
+    diff: f64,
+}
+
+impl OffsetSearch {
+    fn new(tap: usize) -> OffsetSearch {
+        OffsetSearch {
+            tap,
+            next: 0,
+            best: 0,
+            diff: f64::INFINITY,
+        }
+    }
+}
+
+impl PitchShift {
+    pub fn new(semitones: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (env.sample_rate() as f64
+            * (PITCHSHIFT_WINDOW + PITCHSHIFT_SEARCH + PITCHSHIFT_MATCH))
+            as usize
+            + 2;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back((0.0, 0.0));
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(PitchShift {
+            buffer,
+            semitones,
+            src,
+            ph: 0.0,
+            offsets: [0.0, 0.0],
+            search: OffsetSearch::new(0),
+        }))))
+    }
+
+    // tries a few more offsets for the pointer jumping next. it lands at `landing` while the
+    // other one is in the middle of the window
+    fn search_offset(&mut self, window: f64, landing: f64, sample_rate: f64) {
+        let search = &mut self.search;
+        let other = window / 2.0 + self.offsets[1 - search.tap];
+        let len = (sample_rate * PITCHSHIFT_MATCH) as usize;
+        let end = ((sample_rate * PITCHSHIFT_SEARCH) as usize).min(search.next + PITCHSHIFT_TRIES);
+        let buffer = &self.buffer;
+        let mono = |d: f64| {
+            let (l, r) = read_line(buffer, d);
+            l + r
+        };
+        for o in search.next..end {
+            // compares the signals at both pointers from the same point in time
+            let shift = landing + o as f64 - other;
+            let from = (-shift).max(0.0);
+            let mut diff = 0.0;
+            for k in (0..len).step_by(PITCHSHIFT_MATCH_STEP) {
+                diff += (mono(from + k as f64) - mono(from + shift + k as f64)).powi(2);
+                if diff >= search.diff {
+                    break;
+                }
+            }
+            if diff < search.diff {
+                search.best = o;
+                search.diff = diff;
+            }
+        }
+        search.next = end;
+    }
+}
+
+impl Walk for PitchShift {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.semitones) {
+            self.semitones.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for PitchShift {
+    fn name(&self) -> &'static str {
+        "pitchshift"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.semitones.clone(),
+            name: "semitones".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.semitones) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.semitones.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("pitchshift".to_string(), slots)
+    }
+}
+
+impl Operate for PitchShift {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "semitones" => Ok(self.semitones.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("pitchshift/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "pitchshift/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "semitones" => {
+                self.semitones = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("pitchshift/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "semitones" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("pitchshift/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("pitchshift/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "semitones" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["semitones", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "semitones" => Some(ParamInfo::new(-24.0, 24.0, 0.0, ParamUnit::Semitones)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for PitchShift {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        self.buffer.pop_back();
+        self.buffer.push_front(flush_denormal(sig));
+
+        let window = transport.sample_rate as f64 * PITCHSHIFT_WINDOW;
+        let ratio = 2.0f64.powf(self.semitones.proc(transport).0 / 12.0);
+        // delays shrink when reading faster than writing, and grow when slower
+        let prev = self.ph;
+        self.ph = (self.ph + (1.0 - ratio) / window).rem_euclid(1.0);
+
+        let phs = [self.ph, (self.ph + 0.5) % 1.0];
+        for (i, ph) in phs.iter().enumerate() {
+            // jumped to the other end of the window, where it is silent
+            if (ph - (prev + 0.5 * i as f64) % 1.0).abs() > 0.5 {
+                self.offsets[i] = if self.search.tap == i {
+                    self.search.best as f64
+                } else {
+                    0.0
+                };
+                self.search = OffsetSearch::new(1 - i);
+            }
+        }
+        let landing = if ratio > 1.0 { window } else { 0.0 };
+        self.search_offset(window, landing, transport.sample_rate as f64);
+
+        let (mut l, mut r) = (0.0, 0.0);
+        for (ph, offset) in phs.iter().zip(self.offsets.iter()) {
+            let (dl, dr) = read_line(&self.buffer, ph * window + offset);
+            let w = (std::f64::consts::PI * ph).sin().powi(2);
+            l += dl * w;
+            r += dr * w;
+        }
+        (l, r)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+        self.ph = 0.0;
+        self.offsets = [0.0, 0.0];
+        self.search = OffsetSearch::new(0);
+    }
+}
+
+// longest delay the all-pass and comb filters can hold
+const FILTER_MAX_DELAY: f64 = 1.0;
+
+fn filter_buffer(env: &Env) -> VecDeque<Signal> {
+    let len = (env.sample_rate() as f64 * FILTER_MAX_DELAY) as usize;
+    let mut buffer = VecDeque::with_capacity(len);
+    for _n in 0..len {
+        buffer.push_back((0.0, 0.0));
+    }
+    buffer
+}
+
+pub struct Allpass {
+    buffer: VecDeque<Signal>,
+    delay: Aug,
+    gain: Aug,
+    src: Aug,
+}
+
+impl Allpass {
+    pub fn new(delay: Aug, gain: Aug, src: Aug, env: &Env) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Allpass {
+            buffer: filter_buffer(env),
+            delay,
+            gain,
+            src,
+        }))))
+    }
+}
+
+impl Walk for Allpass {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.delay) {
+            self.delay.walk(f);
+        }
+        if f(&self.gain) {
+            self.gain.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Allpass {
+    fn name(&self) -> &'static str {
+        "allpass"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.delay.clone(),
+            name: "delay".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.delay) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.delay.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.gain.clone(),
+            name: "gain".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gain) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.gain.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("allpass".to_string(), slots)
+    }
+}
+
+impl Operate for Allpass {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "delay" => Ok(self.delay.clone()),
+            "gain" => Ok(self.gain.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("allpass/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "allpass/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "delay" => {
+                self.delay = ug;
+                Ok(true)
+            }
+            "gain" => {
+                self.gain = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("allpass/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "delay" | "gain" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("allpass/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("allpass/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "delay" | "gain" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["delay", "gain", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "delay" => Some(ParamInfo::new(0.0, 1.0, 0.005, ParamUnit::Seconds)),
+            "gain" => Some(ParamInfo::new(-1.0, 1.0, 0.5, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Allpass {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let g = self.gain.proc(transport).0;
+        let dt = transport.sec_to_samples(self.delay.proc(transport).0) as usize;
+        let dt = dt.max(1).min(self.buffer.len());
+
+        // w[n] = x[n] + g w[n-D], y[n] = -g w[n] + w[n-D]
+        let (dl, dr) = self.buffer[dt - 1];
+        let (wl, wr) = (l + g * dl, r + g * dr);
+        self.buffer.pop_back();
+        self.buffer.push_front(flush_denormal((wl, wr)));
+
+        (-g * wl + dl, -g * wr + dr)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombMode {
+    FeedForward,
+    FeedBack,
+}
+
+impl CombMode {
+    pub fn parse(s: &str) -> Option<CombMode> {
+        match s {
+            "ff" => Some(CombMode::FeedForward),
+            "fb" => Some(CombMode::FeedBack),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            CombMode::FeedForward => "ff",
+            CombMode::FeedBack => "fb",
+        }
+    }
+}
+
+pub struct Comb {
+    buffer: VecDeque<Signal>,
+    delay: Aug,
+    gain: Aug,
+    mode: CombMode,
+    src: Aug,
+}
+
+impl Comb {
+    pub fn new(delay: Aug, gain: Aug, mode: CombMode, src: Aug, env: &Env) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Comb {
+            buffer: filter_buffer(env),
+            delay,
+            gain,
+            mode,
+            src,
+        }))))
+    }
+}
+
+impl Walk for Comb {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.delay) {
+            self.delay.walk(f);
+        }
+        if f(&self.gain) {
+            self.gain.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Comb {
+    fn name(&self) -> &'static str {
+        "comb"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.delay.clone(),
+            name: "delay".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.delay) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.delay.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.gain.clone(),
+            name: "gain".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gain) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.gain.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "mode".to_string(),
+            value: Value::Symbol(self.mode.to_str().to_string()),
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("comb".to_string(), slots)
+    }
+}
+
+impl Operate for Comb {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "delay" => Ok(self.delay.clone()),
+            "gain" => Ok(self.gain.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("comb/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "mode" {
+            return Ok(self.mode.to_str().to_string());
+        }
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "comb/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "delay" => {
+                self.delay = ug;
+                Ok(true)
+            }
+            "gain" => {
+                self.gain = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("comb/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "mode" => match CombMode::parse(&data) {
+                Some(mode) => {
+                    self.mode = mode;
+                    Ok(true)
+                }
+                None => Err(OperateError::CannotParseSymbol(
+                    format!("comb/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "delay" | "gain" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("comb/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("comb/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "mode" => self.mode = CombMode::FeedForward,
+            "delay" | "gain" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["delay", "gain", "mode", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "delay" => Some(ParamInfo::new(0.0, 1.0, 0.01, ParamUnit::Seconds)),
+            "gain" => Some(ParamInfo::new(-1.0, 1.0, 0.5, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Comb {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let g = self.gain.proc(transport).0;
+        let dt = transport.sec_to_samples(self.delay.proc(transport).0) as usize;
+        let dt = dt.max(1).min(self.buffer.len());
+
+        let (dl, dr) = self.buffer[dt - 1];
+        let (yl, yr) = (l + g * dl, r + g * dr);
+        self.buffer.pop_back();
+        match self.mode {
+            // y[n] = x[n] + g x[n-D]
+            CombMode::FeedForward => self.buffer.push_front((l, r)),
+            // y[n] = x[n] + g y[n-D]
+            CombMode::FeedBack => self.buffer.push_front(flush_denormal((yl, yr))),
+        }
+
+        (yl, yr)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+    }
+}
+
+// the sweep covers 0 to this at full depth
+const FLANGER_MAX_DELAY: f64 = 0.01;
+
+pub struct Flanger {
+    buffer: VecDeque<Signal>,
+    rate: Aug,
+    depth: Aug,
+    feedback: Aug,
+    mix: Aug,
+    src: Aug,
+    ph: f64,
+}
+
+impl Flanger {
+    pub fn new(rate: Aug, depth: Aug, feedback: Aug, mix: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (env.sample_rate() as f64 * FLANGER_MAX_DELAY) as usize + 2;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back((0.0, 0.0));
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(Flanger {
+            buffer,
+            rate,
+            depth,
+            feedback,
+            mix,
+            src,
+            ph: 0.0,
+        }))))
+    }
+
+    // read position in samples behind the previous input
+    fn delay(&self, depth: f64, sample_rate: f64) -> f64 {
+        let lfo = 0.5 + 0.5 * (2.0 * std::f64::consts::PI * self.ph).sin();
+        depth * FLANGER_MAX_DELAY * sample_rate * lfo
+    }
+}
+
+impl Walk for Flanger {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.rate) {
+            self.rate.walk(f);
+        }
+        if f(&self.depth) {
+            self.depth.walk(f);
+        }
+        if f(&self.feedback) {
+            self.feedback.walk(f);
+        }
+        if f(&self.mix) {
+            self.mix.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Flanger {
+    fn name(&self) -> &'static str {
+        "flanger"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.rate.clone(),
+            name: "rate".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.rate) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.rate.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.depth.clone(),
+            name: "depth".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.depth) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.depth.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.feedback.clone(),
+            name: "feedback".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.feedback) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.feedback.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.mix.clone(),
+            name: "mix".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.mix) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.mix.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("flanger".to_string(), slots)
+    }
+}
+
+impl Operate for Flanger {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "rate" => Ok(self.rate.clone()),
+            "depth" => Ok(self.depth.clone()),
+            "feedback" => Ok(self.feedback.clone()),
+            "mix" => Ok(self.mix.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("flanger/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "flanger/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "rate" => {
+                self.rate = ug;
+                Ok(true)
+            }
+            "depth" => {
+                self.depth = ug;
+                Ok(true)
+            }
+            "feedback" => {
+                self.feedback = ug;
+                Ok(true)
+            }
+            "mix" => {
+                self.mix = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("flanger/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "rate" | "depth" | "feedback" | "mix" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("flanger/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("flanger/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "rate" | "depth" | "feedback" | "mix" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["rate", "depth", "feedback", "mix", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "rate" => Some(ParamInfo::new(0.0, 10.0, 0.25, ParamUnit::Hz)),
+            "depth" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            "feedback" => Some(ParamInfo::new(-1.0, 1.0, 0.5, ParamUnit::Bipolar)),
+            "mix" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Flanger {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let rate = self.rate.proc(transport).0;
+        let depth = self.depth.proc(transport).0.clamp(0.0, 1.0);
+        let fb = self.feedback.proc(transport).0;
+        let mix = self.mix.proc(transport).0;
+        let sr = transport.sample_rate as f64;
+
+        let (wl, wr) = read_line(&self.buffer, self.delay(depth, sr));
+        self.ph = (self.ph + rate / sr) % 1.0;
+        self.buffer.pop_back();
+        self.buffer
+            .push_front(flush_denormal((l + fb * wl, r + fb * wr)));
+
+        (l * (1.0 - mix) + wl * mix, r * (1.0 - mix) + wr * mix)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+        self.ph = 0.0;
+    }
+}
+
+pub struct MultiTapDelay {
+    buffer: VecDeque<Signal>,
+    src: Aug,
+    taps: Vec<(Aug, Aug)>,
+}
+
+impl MultiTapDelay {
+    pub fn new(src: Aug, taps: Vec<(Aug, Aug)>, env: &Env) -> Aug {
+        let len = (env.sample_rate() * 2) as usize;
+        let mut buffer = VecDeque::with_capacity(len);
+        for _n in 0..len {
+            buffer.push_back((0.0, 0.0));
+        }
+        Aug::new(UGen::new(UG::Proc(Box::new(MultiTapDelay {
+            buffer,
+            src,
+            taps,
+        }))))
+    }
+
+    fn tap_index(pname: &str, prefix: &str) -> Option<usize> {
+        pname
+            .strip_prefix(prefix)
+            .and_then(|idx| idx.parse::<usize>().ok())
+    }
+}
+
+impl Walk for MultiTapDelay {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+        for (time, gain) in self.taps.iter() {
+            if f(time) {
+                time.walk(f);
+            }
+            if f(gain) {
+                gain.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for MultiTapDelay {
+    fn name(&self) -> &'static str {
+        "multitap"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+        let mut values = Vec::new();
+
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        for (time, gain) in self.taps.iter() {
+            for u in [time, gain].iter() {
+                match shared_ug.iter().position(|e| *e == **u) {
+                    Some(n) => values.push(Box::new(Value::Shared(n, shared_ug[n].clone()))),
+                    None => values.push(Box::new(Value::Ug((*u).clone()))),
+                }
+            }
+        }
+        UgNode::UgRest("multitap".to_string(), slots, "tap".to_string(), values)
+    }
+}
+
+impl Operate for MultiTapDelay {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        let time = MultiTapDelay::tap_index(pname, "time");
+        let gain = MultiTapDelay::tap_index(pname, "gain");
+        match (pname, time, gain) {
+            ("src", _, _) => Ok(self.src.clone()),
+            (_, Some(idx), _) if idx < self.taps.len() => Ok(self.taps[idx].0.clone()),
+            (_, _, Some(idx)) if idx < self.taps.len() => Ok(self.taps[idx].1.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("multitap/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "multitap/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        let time = MultiTapDelay::tap_index(pname, "time");
+        let gain = MultiTapDelay::tap_index(pname, "gain");
+        if pname == "src" {
+            self.src = ug;
+            return Ok(true);
+        }
+
+        match time.or(gain) {
+            Some(idx) => {
+                // setting a tap past the end adds silent taps up to it
+                while self.taps.len() <= idx {
+                    self.taps.push((Aug::val(0.0), Aug::val(0.0)));
+                }
+                if time.is_some() {
+                    self.taps[idx].0 = ug;
+                } else {
+                    self.taps[idx].1 = ug;
+                }
+                Ok(true)
+            }
+            None => Err(OperateError::ParamNotFound(format!("multitap/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        if let Ok(v) = data.parse::<f64>() {
+            self.set(pname, Aug::val(v))
+        } else {
+            let err = OperateError::CannotParseNumber(format!("multitap/{}", pname), data.clone());
+            Err(err)
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        let _ = self.set(pname, Aug::val(0.0));
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["src".to_string()];
+        for idx in 0..self.taps.len() {
+            params.push(format!("time{}", idx));
+            params.push(format!("gain{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        if MultiTapDelay::tap_index(pname, "time").is_some() {
+            Some(ParamInfo::new(0.0, 2.0, 0.25, ParamUnit::Seconds))
+        } else if MultiTapDelay::tap_index(pname, "gain").is_some() {
+            Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized))
+        } else {
+            None
+        }
+    }
+}
+
+impl Proc for MultiTapDelay {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        self.buffer.pop_back();
+        self.buffer.push_front(sig);
+
+        let (mut l, mut r) = (0.0, 0.0);
+        for (time, gain) in self.taps.iter_mut() {
+            let dt = transport.sec_to_samples(time.proc(transport).0) as usize;
+            let g = gain.proc(transport).0;
+            if dt < self.buffer.len() {
+                let (tl, tr) = self.buffer[dt];
+                l += tl * g;
+                r += tr * g;
+            }
+        }
+        (l, r)
+    }
+
+    fn reset(&mut self) {
+        for s in self.buffer.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+    }
+}
+
+// the longest loop `freeze` can capture
+const FREEZE_MAX_LENGTH: f64 = 10.0;
+
+// captures `src` while `record` is high, up to `length` seconds, and loops it afterwards.
+// `src` passes through until something is captured
+pub struct Freeze {
+    pub record: Aug,
+    pub length: Aug,
+    pub src: Aug,
+    buffer: Vec<Signal>,
+    // samples captured in `buffer` by the last take
+    taken: usize,
+    recording: bool,
+    pos: usize,
+}
+
+impl Freeze {
+    pub fn new(record: Aug, length: Aug, src: Aug, env: &Env) -> Aug {
+        let len = (FREEZE_MAX_LENGTH * env.sample_rate() as f64) as usize;
+        Aug::new(UGen::new(UG::Proc(Box::new(Freeze {
+            record,
+            length,
+            src,
+            buffer: vec![(0.0, 0.0); len],
+            taken: 0,
+            recording: false,
+            pos: 0,
+        }))))
+    }
+}
+
+impl Walk for Freeze {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.record) {
+            self.record.walk(f);
+        }
+        if f(&self.length) {
+            self.length.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Freeze {
+    fn name(&self) -> &'static str {
+        "freeze"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.record.clone(),
+            name: "record".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.record) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.record.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.length.clone(),
+            name: "length".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.length) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.length.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("freeze".to_string(), slots)
+    }
+}
+
+impl Operate for Freeze {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "record" => Ok(self.record.clone()),
+            "length" => Ok(self.length.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("freeze/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "freeze/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "record" => {
+                self.record = ug;
+                Ok(true)
+            }
+            "length" => {
+                self.length = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("freeze/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "record" | "length" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("freeze/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("freeze/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "record" | "length" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["record", "length", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "length" => Some(ParamInfo::new(
+                0.0,
+                FREEZE_MAX_LENGTH,
+                1.0,
+                ParamUnit::Seconds,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Freeze {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        let record = self.record.proc(transport).0 > 0.0;
+        let length = self.length.proc(transport).0.clamp(0.0, FREEZE_MAX_LENGTH);
+
+        if record {
+            // a new take starts over
+            if !self.recording {
+                self.taken = 0;
+                self.recording = true;
+                self.pos = 0;
+            }
+            let limit = (transport.sec_to_samples(length) as usize).min(self.buffer.len());
+            if self.taken < limit {
+                self.buffer[self.taken] = sig;
+                self.taken += 1;
+            }
+            return sig;
+        }
+        self.recording = false;
+
+        if self.taken == 0 {
+            return sig;
+        }
+        // loops only what was captured when it's shorter than `length`
+        let v = self.buffer[self.pos % self.taken];
+        self.pos = (self.pos + 1) % self.taken;
+        v
+    }
+
+    fn reset(&mut self) {
+        self.taken = 0;
+        self.recording = false;
+        self.pos = 0;
+    }
+}
+
+// RBJ shelving with slope 1; returns A, cos(w0) and 2 sqrt(A) alpha
+fn shelf_params(freq: f64, gain: f64, transport: &Transport) -> (f64, f64, f64) {
+    let a = 10.0f64.powf(gain / 40.0);
+    let w = (2.0 * std::f64::consts::PI * freq) / transport.sample_rate as f64;
+    let alpha = w.sin() / 2.0 * 2.0f64.sqrt();
+    (a, w.cos(), 2.0 * a.sqrt() * alpha)
+}
+
+pub struct LowShelf {
+    inbuf: [Signal; 2],
+    outbuf: [Signal; 2],
+    freq: Aug,
+    gain: Aug,
+    src: Aug,
+}
+
+impl LowShelf {
+    pub fn new(freq: Aug, gain: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(LowShelf {
+            inbuf: [(0.0, 0.0), (0.0, 0.0)],
+            outbuf: [(0.0, 0.0), (0.0, 0.0)],
+            freq,
+            gain,
+            src,
+        }))))
+    }
+}
+
+impl Walk for LowShelf {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.freq) {
+            self.freq.walk(f);
+        }
+        if f(&self.gain) {
+            self.gain.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for LowShelf {
+    fn name(&self) -> &'static str {
+        "lowshelf"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.freq.clone(),
+            name: "freq".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.freq) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.freq.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.gain.clone(),
+            name: "gain".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gain) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.gain.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("lowshelf".to_string(), slots)
+    }
+}
+
+impl Operate for LowShelf {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "freq" => Ok(self.freq.clone()),
+            "gain" => Ok(self.gain.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("lowshelf/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "lowshelf/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "freq" => {
+                self.freq = ug;
+                Ok(true)
+            }
+            "gain" => {
+                self.gain = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("lowshelf/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "freq" | "gain" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("lowshelf/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("lowshelf/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "freq" | "gain" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["freq", "gain", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 1000.0, ParamUnit::Hz)),
+            "gain" => Some(ParamInfo::new(-24.0, 24.0, 0.0, ParamUnit::Decibel)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for LowShelf {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let f = self.freq.proc(transport).0;
+        let gain = self.gain.proc(transport).0;
+        let sig = self.src.proc(transport);
+
+        let (a, cw, beta) = shelf_params(f, gain, transport);
+        let b0 = a * ((a + 1.0) - (a - 1.0) * cw + beta);
+        let b1 = 2.0 * a * ((a - 1.0) - (a + 1.0) * cw);
+        let b2 = a * ((a + 1.0) - (a - 1.0) * cw - beta);
+        let a0 = (a + 1.0) + (a - 1.0) * cw + beta;
+        let a1 = -2.0 * ((a - 1.0) + (a + 1.0) * cw);
+        let a2 = (a + 1.0) + (a - 1.0) * cw - beta;
+
+        biquad(
+            (b0, b1, b2),
+            (a0, a1, a2),
+            &mut self.inbuf,
+            &mut self.outbuf,
+            sig,
+        )
+    }
+
+    fn reset(&mut self) {
+        self.inbuf = [(0.0, 0.0); 2];
+        self.outbuf = [(0.0, 0.0); 2];
+    }
+}
+
+pub struct HighShelf {
+    inbuf: [Signal; 2],
+    outbuf: [Signal; 2],
+    freq: Aug,
+    gain: Aug,
+    src: Aug,
+}
+
+impl HighShelf {
+    pub fn new(freq: Aug, gain: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(HighShelf {
+            inbuf: [(0.0, 0.0), (0.0, 0.0)],
+            outbuf: [(0.0, 0.0), (0.0, 0.0)],
+            freq,
+            gain,
+            src,
+        }))))
+    }
+}
+
+impl Walk for HighShelf {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.freq) {
+            self.freq.walk(f);
+        }
+        if f(&self.gain) {
+            self.gain.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for HighShelf {
+    fn name(&self) -> &'static str {
+        "highshelf"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.freq.clone(),
+            name: "freq".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.freq) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.freq.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.gain.clone(),
+            name: "gain".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gain) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.gain.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("highshelf".to_string(), slots)
+    }
+}
+
+impl Operate for HighShelf {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "freq" => Ok(self.freq.clone()),
+            "gain" => Ok(self.gain.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("highshelf/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "highshelf/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "freq" => {
+                self.freq = ug;
+                Ok(true)
+            }
+            "gain" => {
+                self.gain = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("highshelf/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "freq" | "gain" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("highshelf/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("highshelf/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "freq" | "gain" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["freq", "gain", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 1000.0, ParamUnit::Hz)),
+            "gain" => Some(ParamInfo::new(-24.0, 24.0, 0.0, ParamUnit::Decibel)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for HighShelf {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let f = self.freq.proc(transport).0;
+        let gain = self.gain.proc(transport).0;
+        let sig = self.src.proc(transport);
+
+        let (a, cw, beta) = shelf_params(f, gain, transport);
+        let b0 = a * ((a + 1.0) + (a - 1.0) * cw + beta);
+        let b1 = -2.0 * a * ((a - 1.0) + (a + 1.0) * cw);
+        let b2 = a * ((a + 1.0) + (a - 1.0) * cw - beta);
+        let a0 = (a + 1.0) - (a - 1.0) * cw + beta;
+        let a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cw);
+        let a2 = (a + 1.0) - (a - 1.0) * cw - beta;
+
+        biquad(
+            (b0, b1, b2),
+            (a0, a1, a2),
+            &mut self.inbuf,
+            &mut self.outbuf,
+            sig,
+        )
+    }
+
+    fn reset(&mut self) {
+        self.inbuf = [(0.0, 0.0); 2];
+        self.outbuf = [(0.0, 0.0); 2];
+    }
+}
+
+// maps the source from -1.0..1.0 onto `table` as a transfer curve
+pub struct WaveShaper {
+    pub table: Aug,
+    pub src: Aug,
+}
+
+impl WaveShaper {
+    pub fn new(table: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(WaveShaper { table, src }))))
+    }
+
+    fn shape(table: &[f64], v: f64) -> f64 {
+        if table.is_empty() {
+            return 0.0;
+        }
+        let last = (table.len() - 1) as f64;
+        let p = (v.clamp(-1.0, 1.0) + 1.0) / 2.0 * last;
+        let v1 = table[p.floor() as usize];
+        let v2 = table[p.ceil() as usize];
+        v1 + (v2 - v1) * p.fract()
+    }
+}
+
+impl Walk for WaveShaper {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.table) {
+            self.table.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for WaveShaper {
+    fn name(&self) -> &'static str {
+        "waveshaper"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.table.clone(),
+            name: "table".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.table) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.table.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("waveshaper".to_string(), slots)
+    }
+}
+
+impl Operate for WaveShaper {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "table" => Ok(self.table.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("waveshaper/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "waveshaper/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "table" => {
+                self.table = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("waveshaper/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n');
+
+        if pname == "table" {
+            return match Table::parse_str(data.clone()) {
+                Some(data) => self.set(pname, Aug::new(UGen::new(UG::Tab(Table::new(data))))),
+                None => Err(OperateError::CannotParseNumber(
+                    format!("waveshaper/{}", pname),
+                    data,
+                )),
+            };
+        }
+        data.retain(|c| c != ' ');
+
+        match pname {
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("waveshaper/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("waveshaper/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            // a cleared curve passes the source through
+            "table" => {
+                let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![-1.0, 1.0]))));
+                let _ = self.set(pname, table);
+            }
+            "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["table", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for WaveShaper {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        if let UG::Tab(table) = &self.table.0.lock().unwrap().ug {
+            let table = table.0.lock().unwrap();
+            (WaveShaper::shape(&table, l), WaveShaper::shape(&table, r))
+        } else {
+            (0.0, 0.0)
+        }
+    }
+}
+
+// removes DC offset with a one-pole high-pass: y[n] = x[n] - x[n-1] + R * y[n-1].
+// R closer to 1.0 lowers the cutoff (about 35 Hz at 44100 Hz)
+const DCBLOCK_R: f64 = 0.995;
+
+pub struct DCBlock {
+    pub src: Aug,
+    x1: Signal,
+    y1: Signal,
+}
+
+impl DCBlock {
+    pub fn new(src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(DCBlock {
+            src,
+            x1: (0.0, 0.0),
+            y1: (0.0, 0.0),
+        }))))
+    }
+}
+
+impl Walk for DCBlock {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for DCBlock {
+    fn name(&self) -> &'static str {
+        "dcblock"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("dcblock".to_string(), slots)
+    }
+}
+
+impl Operate for DCBlock {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("dcblock/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "dcblock/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("dcblock/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("dcblock/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("dcblock/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for DCBlock {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let y = flush_denormal((
+            l - self.x1.0 + DCBLOCK_R * self.y1.0,
+            r - self.x1.1 + DCBLOCK_R * self.y1.1,
+        ));
+        self.x1 = (l, r);
+        self.y1 = y;
+        y
+    }
+
+    fn reset(&mut self) {
+        self.x1 = (0.0, 0.0);
+        self.y1 = (0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::time::Clock;
+    use crate::ugens::core::Ramp;
+    use crate::ugens::misc::Add;
+    use crate::ugens::osc::Sine;
+
+    fn render(ug: &mut Aug, n: usize) -> Vec<Signal> {
+        ug.proc_n(&mut Transport::default(), n)
+    }
+
+    fn sine(hz: f64) -> Aug {
+        Sine::new(Aug::val(0.0), Aug::val(hz))
+    }
+
+    #[test]
+    fn widener_spreads_channels_by_amount() {
+        let env = Env::default();
+        let out = render(&mut Widener::new(Aug::val(0.0), sine(440.0), &env), 4410);
+        assert!(out.iter().all(|(l, r)| l == r));
+
+        let out = render(&mut Widener::new(Aug::val(0.5), sine(440.0), &env), 4410);
+        let diff = out[2000..]
+            .iter()
+            .map(|(l, r)| (l - r).abs())
+            .fold(0.0, f64::max);
+        assert!(diff > 0.1);
+    }
+
+    fn comp(level: f64) -> Aug {
+        // 4:1 over 0.5 with 10 ms attack and 100 ms release
+        let (threshold, ratio) = (Aug::val(0.5), Aug::val(4.0));
+        let (attack, release) = (Aug::val(0.01), Aug::val(0.1));
+        Compressor::new(threshold, ratio, attack, release, Aug::val(level))
+    }
+
+    #[test]
+    fn compressor_reduces_loud_bursts_after_attack() {
+        let out = render(&mut comp(1.0), 4410);
+        // 441 samples is the attack time
+        assert!(out[441].0 < 0.8);
+        // settles at 0.5 + (1.0 - 0.5) / 4
+        assert!((out[4409].0 - 0.625).abs() < 1e-3);
+        // and moves smoothly toward it
+        assert!(out.windows(2).all(|w| (w[1].0 - w[0].0).abs() < 0.01));
+    }
+
+    #[test]
+    fn compressor_passes_signals_under_threshold() {
+        let out = render(&mut comp(0.3), 441);
+        assert!(out.iter().all(|s| *s == (0.3, 0.3)));
+    }
+
+    // frequencies measured between rising zero crossings
+    fn periods(samples: &[f64]) -> Vec<f64> {
+        let ups: Vec<usize> = (1..samples.len())
+            .filter(|i| samples[i - 1] < 0.0 && samples[*i] >= 0.0)
+            .collect();
+        ups.windows(2)
+            .map(|w| 44100.0 / (w[1] - w[0]) as f64)
+            .collect()
+    }
+
+    #[test]
+    fn chorus_wobbles_pitch_around_dry() {
+        let env = Env::default();
+        let wet = |depth: f64| {
+            let mut chorus = Chorus::new(
+                Aug::val(2.0),
+                Aug::val(depth),
+                Aug::val(1.0),
+                sine(440.0),
+                &env,
+            );
+            render(&mut chorus, 88200)
+        };
+
+        let dry = render(&mut sine(440.0), 88200);
+        assert_eq!(wet(0.0), dry);
+
+        // two whole LFO cycles after the delay line is filled
+        let out: Vec<f64> = wet(1.0)[4410..4410 + 44100].iter().map(|s| s.0).collect();
+        let freqs = periods(&out);
+        // cycles over the time they took
+        let mean = freqs.len() as f64 / freqs.iter().map(|f| 1.0 / f).sum::<f64>();
+        assert!((mean - 440.0).abs() < 5.0);
+        assert!(freqs.iter().cloned().fold(f64::MAX, f64::min) < 420.0);
+        assert!(freqs.iter().cloned().fold(0.0, f64::max) > 460.0);
+    }
+
+    fn magnitude(samples: &[f64], freq: f64) -> f64 {
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, v) in samples.iter().enumerate() {
+            let t = 2.0 * std::f64::consts::PI * freq * i as f64 / 44100.0;
+            re += v * t.cos();
+            im += v * t.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / samples.len() as f64
+    }
+
+    #[test]
+    fn freq_shift_moves_a_sine_up() {
+        let mut shifter = FreqShift::new(Aug::val(100.0), sine(1000.0));
+        let out: Vec<f64> = render(&mut shifter, 44100 + 4410)[4410..]
+            .iter()
+            .map(|s| s.0)
+            .collect();
+        assert!(magnitude(&out, 1100.0) > 0.9);
+        // neither the source nor the mirrored sideband is left
+        assert!(magnitude(&out, 1000.0) < 0.05);
+        assert!(magnitude(&out, 900.0) < 0.05);
+    }
+
+    // phase of `freq` in `samples` against a cosine
+    fn phase(samples: &[f64], freq: f64) -> f64 {
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, v) in samples.iter().enumerate() {
+            let t = 2.0 * std::f64::consts::PI * freq * i as f64 / 44100.0;
+            re += v * t.cos();
+            im -= v * t.sin();
+        }
+        im.atan2(re)
+    }
+
+    fn wrap(ph: f64) -> f64 {
+        let pi = std::f64::consts::PI;
+        (ph + pi).rem_euclid(2.0 * pi) - pi
+    }
+
+    #[test]
+    fn allpass_keeps_magnitude_and_disperses_phase() {
+        let env = Env::default();
+        let mut shifts = Vec::new();
+        for hz in [1000.0, 1050.0, 1100.0].iter() {
+            let mut allpass = Allpass::new(Aug::val(0.005), Aug::val(0.7), sine(*hz), &env);
+            // after the feedback has settled
+            let out: Vec<f64> = render(&mut allpass, 8820 + 4410)[8820..]
+                .iter()
+                .map(|s| s.0)
+                .collect();
+            let dry: Vec<f64> = render(&mut sine(*hz), 8820 + 4410)[8820..]
+                .iter()
+                .map(|s| s.0)
+                .collect();
+            assert!((magnitude(&out, *hz) - magnitude(&dry, *hz)).abs() < 0.02);
+            shifts.push(phase(&out, *hz) - phase(&dry, *hz));
+        }
+        // a plain delay would shift phase linearly with frequency
+        assert!(wrap(shifts[2] - 2.0 * shifts[1] + shifts[0]).abs() > 0.1);
+    }
+
+    #[test]
+    fn feedback_comb_rings_at_its_delay() {
+        let env = Env::default();
+        // an impulse into a 44 sample delay, which resonates at 44100 / 44 Hz
+        let impulse = Ramp::new(1.0, 0.0, 1);
+        let mut comb = Comb::new(
+            Aug::val(0.001),
+            Aug::val(0.9),
+            CombMode::FeedBack,
+            impulse,
+            &env,
+        );
+        let out: Vec<f64> = render(&mut comb, 44100).iter().map(|s| s.0).collect();
+        let resonance = 44100.0 / 44.0;
+        assert!(magnitude(&out, resonance) > 5.0 * magnitude(&out, resonance * 1.5));
+        assert!(magnitude(&out, resonance * 2.0) > 5.0 * magnitude(&out, resonance * 2.5));
+        // and keeps ringing long after the impulse
+        assert!(out[44 * 20].abs() > 0.1);
+    }
+
+    #[test]
+    fn flanger_sweeps_delay_over_one_lfo_period() {
+        // a 10 Hz sweep takes 4410 samples
+        let mut flanger = Flanger {
+            buffer: VecDeque::from(vec![(0.0, 0.0); 443]),
+            rate: Aug::val(10.0),
+            depth: Aug::val(1.0),
+            feedback: Aug::val(0.0),
+            mix: Aug::val(0.5),
+            src: Aug::val(0.0),
+            ph: 0.0,
+        };
+        let mut transport = Transport::default();
+        let mut delays = Vec::new();
+        for _ in 0..4410 {
+            transport.inc();
+            delays.push(flanger.delay(1.0, 44100.0));
+            flanger.proc(&transport);
+        }
+        let min = delays.iter().cloned().fold(f64::MAX, f64::min);
+        let max = delays.iter().cloned().fold(0.0, f64::max);
+        assert!(min < 0.01);
+        assert!((max - FLANGER_MAX_DELAY * 44100.0).abs() < 0.01);
+        // back where it started
+        assert!((flanger.delay(1.0, 44100.0) - delays[0]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn multitap_delay_repeats_impulse_at_each_tap() {
+        let env = Env::default();
+        let taps = [(0.01, 0.8), (0.02, 0.5), (0.05, 0.25)];
+        let mut delay = MultiTapDelay::new(
+            Ramp::new(1.0, 0.0, 1),
+            taps.iter()
+                .map(|(t, g)| (Aug::val(*t), Aug::val(*g)))
+                .collect(),
+            &env,
+        );
+        let out: Vec<f64> = render(&mut delay, 4410).iter().map(|s| s.0).collect();
+
+        let transport = Transport::default();
+        let mut offsets = Vec::new();
+        for (time, gain) in taps.iter() {
+            let offset = transport.sec_to_samples(*time) as usize;
+            assert_eq!(out[offset], *gain);
+            offsets.push(offset);
+        }
+        // and nothing else
+        for (i, v) in out.iter().enumerate() {
+            if !offsets.contains(&i) {
+                assert_eq!(*v, 0.0);
+            }
+        }
+    }
+
+    fn settled(ug: &mut Aug) -> Vec<f64> {
+        render(ug, 4410 + 4410)[4410..]
+            .iter()
+            .map(|s| s.0)
+            .collect()
+    }
+
+    #[test]
+    fn low_shelf_boosts_lows_only() {
+        // +12 dB under 200 Hz
+        let shelf = |src: Aug| LowShelf::new(Aug::val(200.0), Aug::val(12.0), src);
+        let boost = 10.0f64.powf(12.0 / 20.0);
+
+        let dc = settled(&mut shelf(Aug::val(0.1)));
+        assert!((dc[4409] - 0.1 * boost).abs() < 1e-3);
+        let low = settled(&mut shelf(sine(50.0)));
+        assert!(magnitude(&low, 50.0) > 0.9 * boost);
+        let high = settled(&mut shelf(sine(10000.0)));
+        assert!((magnitude(&high, 10000.0) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn flat_shelves_are_transparent() {
+        for hz in [50.0, 1000.0, 10000.0].iter() {
+            let dry = settled(&mut sine(*hz));
+            let low = settled(&mut LowShelf::new(
+                Aug::val(200.0),
+                Aug::val(0.0),
+                sine(*hz),
+            ));
+            let high = settled(&mut HighShelf::new(
+                Aug::val(5000.0),
+                Aug::val(0.0),
+                sine(*hz),
+            ));
+            for i in 0..dry.len() {
+                assert!((low[i] - dry[i]).abs() < 1e-6);
+                assert!((high[i] - dry[i]).abs() < 1e-6);
+            }
+        }
+    }
+
+    // runs a unit held as is, so that its state can be looked into
+    fn drive(unit: &mut dyn Proc, n: usize) {
+        let mut transport = Transport::default();
+        for _ in 0..n {
+            transport.inc();
+            unit.proc(&transport);
+        }
+    }
+
+    #[test]
+    fn silent_tails_are_flushed_to_zero() {
+        let mut lpf = LPFilter {
+            inbuf: [(0.0, 0.0); 2],
+            outbuf: [(0.0, 0.0); 2],
+            freq: Aug::val(1000.0),
+            q: Aug::val(0.7),
+            src: Ramp::new(1.0, 0.0, 1),
+        };
+        // the impulse response would sit at subnormals around 1e-323 by now
+        drive(&mut lpf, 8820);
+        assert_eq!(lpf.outbuf, [(0.0, 0.0); 2]);
+    }
+
+    #[test]
+    fn reset_silences_a_delay_tail() {
+        let env = Env::default();
+        let mut delay = Delay::new(
+            Aug::val(0.01),
+            Aug::val(0.5),
+            Aug::val(1.0),
+            Aug::val(1.0),
+            &env,
+        );
+        let mut transport = Transport::default();
+        delay.proc_n(&mut transport, 10);
+        delay.set("src", Aug::val(0.0)).unwrap();
+        let tail = delay.proc_n(&mut transport, 441);
+        assert!(tail.iter().any(|s| s.0 > 0.1));
+
+        delay.reset();
+        let out = delay.proc_n(&mut transport, 441 * 4);
+        assert!(out.iter().all(|s| *s == (0.0, 0.0)));
+    }
+
+    #[test]
+    fn delay_buffer_follows_env_sample_rate() {
+        // 1.9 seconds fits in the two seconds of buffer only when it's sized at 48 kHz
+        let echo_at = |env: &Env| {
+            let mut delay = Delay::new(
+                Aug::val(1.9),
+                Aug::val(0.5),
+                Aug::val(1.0),
+                Ramp::new(1.0, 0.0, 1),
+                env,
+            );
+            let mut transport = Transport::new(48000);
+            let n = (1.9 * 48000.0) as usize;
+            delay.proc_n(&mut transport, n + 1)[n].0
+        };
+        assert_eq!(echo_at(&Env::with_sample_rate(48000)), 0.5);
+        assert_eq!(echo_at(&Env::with_sample_rate(44100)), 0.0);
+    }
+
+    #[test]
+    fn waveshaper_follows_its_transfer_table() {
+        let shaped = |data: Vec<f64>| {
+            let table = Aug::new(UGen::new(UG::Tab(Table::new(data))));
+            let mut shaper = WaveShaper::new(table, sine(441.0));
+            render(&mut shaper, 200)
+        };
+        let dry = render(&mut sine(441.0), 200);
+
+        let identity = shaped(vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        let inverting = shaped(vec![1.0, 0.0, -1.0]);
+        for ((d, id), inv) in dry.iter().zip(identity.iter()).zip(inverting.iter()) {
+            assert!((id.0 - d.0).abs() < 1e-9 && (id.1 - d.1).abs() < 1e-9);
+            assert!((inv.0 + d.0).abs() < 1e-9 && (inv.1 + d.1).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn sweeping_delay_time_has_no_steps() {
+        // 441 to 529 samples over a second, so whole-sample reads would step every 500 samples
+        let mut delay = Delay::new(
+            Ramp::new(0.01, 0.012, 44100),
+            Aug::val(0.3),
+            Aug::val(1.0),
+            sine(500.0),
+            &Env::default(),
+        );
+        let out: Vec<f64> = render(&mut delay, 44100).iter().map(|s| s.0).collect();
+
+        // the second difference of the sum of 500 Hz sines stays under 0.008, while a step
+        // in the first echo would make it about 0.3 * 0.07. the onsets of echoes are skipped
+        let curvature = out[4410..]
+            .windows(3)
+            .map(|w| (w[0] - 2.0 * w[1] + w[2]).abs())
+            .fold(0.0, f64::max);
+        assert!(curvature < 0.015);
+    }
+
+    #[test]
+    fn freeze_loops_what_was_recorded() {
+        // records about 100 samples of a rising ramp, much shorter than `length`
+        let ramp = || Ramp::new(0.0, 1.0, 1000);
+        let gate = Ramp::new(1.0, 0.0, 100);
+        let mut freeze = Freeze::new(gate, Aug::val(1.0), ramp(), &Env::default());
+        let out: Vec<f64> = render(&mut freeze, 1000).iter().map(|s| s.0).collect();
+        let dry: Vec<f64> = render(&mut ramp(), 1000).iter().map(|s| s.0).collect();
+
+        // passes the source while recording
+        let stop = (0..out.len()).find(|i| out[*i] != dry[*i]).unwrap();
+        assert!((95..=105).contains(&stop));
+        let take = &out[..stop];
+        assert!(take.windows(2).all(|w| w[1] > w[0]));
+        for (i, v) in out[stop..].iter().enumerate() {
+            assert_eq!(*v, take[i % take.len()]);
+        }
+    }
+
+    #[test]
+    fn pingpong_echoes_alternate_between_channels() {
+        let mut pingpong = PingPong::new(
+            Aug::val(0.1),
+            Aug::val(0.1),
+            Aug::val(0.5),
+            Aug::val(1.0),
+            Ramp::new(1.0, 0.0, 1),
+            &Env::default(),
+        );
+        let out = render(&mut pingpong, 4410 * 5);
+        let impulse = out.iter().position(|s| *s == (1.0, 1.0)).unwrap();
+
+        let echoes: Vec<(usize, Signal)> = out
+            .iter()
+            .enumerate()
+            .skip(impulse + 1)
+            .filter(|(_, s)| s.0.abs() > 1e-9 || s.1.abs() > 1e-9)
+            .map(|(i, s)| (i, *s))
+            .collect();
+        assert_eq!(echoes.len(), 4);
+        for (n, (i, (l, r))) in echoes.iter().enumerate() {
+            assert_eq!(*i, impulse + 4410 * (n + 1));
+            // the left line takes the source, so echoes start from the left
+            let (bounced, silent) = if n % 2 == 0 { (l, r) } else { (r, l) };
+            assert!((bounced - 0.5f64.powi(n as i32)).abs() < 1e-9);
+            assert_eq!(*silent, 0.0);
+        }
+    }
+
+    #[test]
+    fn runaway_feedback_is_clamped() {
+        let delay = |feedback: f64, mix: f64| {
+            let mut delay = Delay::new(
+                Aug::val(0.1),
+                Aug::val(feedback),
+                Aug::val(mix),
+                sine(441.0),
+                &Env::default(),
+            );
+            render(&mut delay, 44100 * 3)
+        };
+        let runaway = delay(1.5, 1.0);
+        // the sum of every tap at the largest feedback, on top of the dry signal
+        let bound = 1.0 + DELAY_MAX_FEEDBACK / (1.0 - DELAY_MAX_FEEDBACK);
+        assert!(runaway
+            .iter()
+            .all(|s| s.0.abs() <= bound && s.1.abs() <= bound));
+        assert!(runaway == delay(DELAY_MAX_FEEDBACK, 1.0));
+
+        // the dry signal passes as is
+        assert_eq!(delay(1.5, 0.0), render(&mut sine(441.0), 44100 * 3));
+    }
+
+    #[test]
+    fn dcblock_settles_an_offset_sine_to_zero_mean() {
+        let offset = Add::new(vec![sine(441.0), Aug::val(0.5)]);
+        let out = render(&mut DCBlock::new(offset), 44100);
+        // the last 100 periods of 100 samples each
+        let tail = &out[out.len() - 10000..];
+        let mean = tail.iter().map(|s| s.0).sum::<f64>() / tail.len() as f64;
+        assert!(mean.abs() < 1e-3, "mean {}", mean);
+        // while the sine itself passes
+        let peak = tail.iter().fold(0.0f64, |p, s| p.max(s.0.abs()));
+        assert!(peak > 0.95 && peak < 1.05, "peak {}", peak);
+        assert!(tail.iter().all(|s| s.0 == s.1));
+    }
+
+    // the frequency in 1 Hz steps within `range` where the DFT of `samples` is the largest
+    fn dominant_freq(samples: &[f64], range: std::ops::Range<usize>) -> usize {
+        let rate = Transport::default().sample_rate as f64;
+        let power = |hz: usize| {
+            let w = 2.0 * std::f64::consts::PI * hz as f64 / rate;
+            let (re, im) = samples
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(re, im), (n, v)| {
+                    (re + v * (w * n as f64).cos(), im + v * (w * n as f64).sin())
+                });
+            re * re + im * im
+        };
+        range
+            .map(|hz| (hz, power(hz)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn pitchshift_moves_a_sine_up_an_octave() {
+        let env = Env::default();
+        let out = render(
+            &mut PitchShift::new(Aug::val(12.0), sine(220.0), &env),
+            44100,
+        );
+        // after the first window has filled
+        let tail: Vec<f64> = out[4410..].iter().map(|s| s.0).collect();
+        let hz = dominant_freq(&tail, 200..700);
+        assert!((hz as i64 - 440).abs() <= 2, "dominant at {} Hz", hz);
+
+        let out = render(
+            &mut PitchShift::new(Aug::val(0.0), sine(220.0), &env),
+            44100,
+        );
+        let tail: Vec<f64> = out[4410..].iter().map(|s| s.0).collect();
+        assert!((dominant_freq(&tail, 200..700) as i64 - 220).abs() <= 2);
+    }
 }
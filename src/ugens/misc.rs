@@ -1,9 +1,16 @@
 extern crate num;
 
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::musical_time::event::{NoteNum, Pitch, Tuning};
+use crate::musical_time::scale::Scale;
 use crate::musical_time::time::Transport;
+use crate::musical_time::utils::{to_note, to_str, to_tuned_freq};
 
 use super::core::{
-    Aug, Dump, Operate, OperateError, Proc, Signal, Slot, UGen, UgNode, Value, Walk, UG,
+    Aug, Dump, Operate, OperateError, ParamInfo, ParamUnit, Pattern, PatternSteps, Proc, Signal,
+    Slot, UGen, UgNode, Value, Walk, UG,
 };
 
 pub struct Pan {
@@ -29,6 +36,10 @@ impl Walk for Pan {
 }
 
 impl Dump for Pan {
+    fn name(&self) -> &'static str {
+        "pan"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -131,6 +142,17 @@ impl Operate for Pan {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["pan", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "pan" => Some(ParamInfo::new(-1.0, 1.0, 0.0, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for Pan {
@@ -148,76 +170,112 @@ impl Proc for Pan {
     }
 }
 
-pub struct Clip {
-    pub min: Aug,
-    pub max: Aug,
-    pub src: Aug,
+pub enum ClipMode {
+    Hard,
+    Soft,
+    Fold,
 }
 
-impl Clip {
-    pub fn new(min: Aug, max: Aug, src: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Clip {
-            min: min,
-            max: max,
-            src: src,
-        }))))
+impl ClipMode {
+    pub fn parse(s: &str) -> Option<ClipMode> {
+        match s {
+            "hard" => Some(ClipMode::Hard),
+            "soft" => Some(ClipMode::Soft),
+            "fold" => Some(ClipMode::Fold),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            ClipMode::Hard => "hard",
+            ClipMode::Soft => "soft",
+            ClipMode::Fold => "fold",
+        }
+    }
+
+    fn apply(&self, v: f64, min: f64, max: f64) -> f64 {
+        let width = max - min;
+        if width <= 0.0 {
+            return min;
+        }
+        match self {
+            ClipMode::Hard => num::clamp(v, min, max),
+            ClipMode::Soft => {
+                let center = min + width / 2.0;
+                center + width / 2.0 * ((v - center) / (width / 2.0)).tanh()
+            }
+            ClipMode::Fold => {
+                // reflects at the bounds, so it repeats every two widths
+                let t = (v - min).rem_euclid(width * 2.0);
+                if t <= width {
+                    min + t
+                } else {
+                    min + width * 2.0 - t
+                }
+            }
+        }
     }
 }
 
-impl Walk for Clip {
+// takes the left channel of `left` and the left channel of `right` as a stereo signal
+pub struct Stereo {
+    pub left: Aug,
+    pub right: Aug,
+}
+
+impl Stereo {
+    pub fn new(left: Aug, right: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Stereo { left, right }))))
+    }
+}
+
+impl Walk for Stereo {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        if f(&self.min) {
-            self.min.walk(f);
-        }
-        if f(&self.max) {
-            self.max.walk(f);
+        if f(&self.left) {
+            self.left.walk(f);
         }
-        if f(&self.src) {
-            self.src.walk(f);
+        if f(&self.right) {
+            self.right.walk(f);
         }
     }
 }
 
-impl Dump for Clip {
+impl Dump for Stereo {
+    fn name(&self) -> &'static str {
+        "stereo"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
         slots.push(Slot {
-            ug: self.min.clone(),
-            name: "min".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.min) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.min.clone()),
-            },
-        });
-        slots.push(Slot {
-            ug: self.max.clone(),
-            name: "max".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.max) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.max.clone()),
+            ug: self.left.clone(),
+            name: "left".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.left) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.left.clone()),
             },
         });
         slots.push(Slot {
-            ug: self.src.clone(),
-            name: "src".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.src) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.src.clone()),
+            ug: self.right.clone(),
+            name: "right".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.right) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.right.clone()),
             },
         });
 
-        UgNode::Ug("clip".to_string(), slots)
+        UgNode::Ug("stereo".to_string(), slots)
     }
 }
 
-impl Operate for Clip {
+impl Operate for Stereo {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            "min" => Ok(self.min.clone()),
-            "max" => Ok(self.max.clone()),
-            "src" => Ok(self.src.clone()),
-            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
+            "left" => Ok(self.left.clone()),
+            "right" => Ok(self.right.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("stereo/{}", pname))),
         }
     }
 
@@ -228,7 +286,7 @@ impl Operate for Clip {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "clip/{}",
+                        "stereo/{}",
                         pname
                     )))
                 }
@@ -239,138 +297,106 @@ impl Operate for Clip {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            "min" => {
-                self.min = ug;
-                Ok(true)
-            }
-            "max" => {
-                self.max = ug;
+            "left" => {
+                self.left = ug;
                 Ok(true)
             }
-            "src" => {
-                self.src = ug;
+            "right" => {
+                self.right = ug;
                 Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("stereo/{}", pname))),
         }
     }
+
     fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
         let mut data = data.clone();
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            "min" => {
-                if let Ok(v) = data.parse::<f64>() {
-                    self.min = Aug::val(v);
-                    Ok(true)
-                } else {
-                    let err =
-                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
-                    Err(err)
-                }
-            }
-            "max" => {
-                if let Ok(v) = data.parse::<f64>() {
-                    self.max = Aug::val(v);
-                    Ok(true)
-                } else {
-                    let err =
-                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
-                    Err(err)
-                }
-            }
-            "src" => {
+            "left" | "right" => {
                 if let Ok(v) = data.parse::<f64>() {
-                    self.src = Aug::val(v);
-                    Ok(true)
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("stereo/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("stereo/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "min" => {
-                let _ = self.set(pname, Aug::val(0.0));
-            }
-            "max" => {
-                let _ = self.set(pname, Aug::val(0.0));
-            }
-            "src" => {
+            "left" | "right" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["left", "right"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for Clip {
+impl Proc for Stereo {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let (l, r) = self.src.proc(&transport);
-        let min = self.min.proc(&transport).0;
-        let max = self.max.proc(&transport).0;
-        (num::clamp(l, min, max), num::clamp(r, min, max))
+        let l = self.left.proc(transport).0;
+        let r = self.right.proc(transport).0;
+        (l, r)
     }
 }
 
-pub struct Offset {
-    pub val: Aug,
+pub struct Swap {
     pub src: Aug,
 }
 
-impl Offset {
-    pub fn new(val: Aug, src: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Offset { val: val, src: src }))))
+impl Swap {
+    pub fn new(src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Swap { src }))))
     }
 }
 
-impl Walk for Offset {
+impl Walk for Swap {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        if f(&self.val) {
-            self.val.walk(f);
-        }
         if f(&self.src) {
             self.src.walk(f);
         }
     }
 }
 
-impl Dump for Offset {
+impl Dump for Swap {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
-        slots.push(Slot {
-            ug: self.val.clone(),
-            name: "val".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.val) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.val.clone()),
-            },
-        });
         slots.push(Slot {
             ug: self.src.clone(),
             name: "src".to_string(),
             value: match shared_ug.iter().position(|e| *e == self.src) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
                 None => Value::Ug(self.src.clone()),
             },
         });
 
-        UgNode::Ug("offset".to_string(), slots)
+        UgNode::Ug("swap".to_string(), slots)
     }
 }
 
-impl Operate for Offset {
+impl Operate for Swap {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            "val" => Ok(self.val.clone()),
             "src" => Ok(self.src.clone()),
-            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("swap/{}", pname))),
         }
     }
 
@@ -381,7 +407,7 @@ impl Operate for Offset {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "offset/{}",
+                        "swap/{}",
                         pname
                     )))
                 }
@@ -392,15 +418,11 @@ impl Operate for Offset {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            "val" => {
-                self.val = ug;
-                Ok(true)
-            }
             "src" => {
                 self.src = ug;
                 Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("swap/{}", pname))),
         }
     }
 
@@ -409,66 +431,56 @@ impl Operate for Offset {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            "val" => {
-                if let Ok(v) = data.parse::<f64>() {
-                    self.val = Aug::val(v);
-                    Ok(true)
-                } else {
-                    let err =
-                        OperateError::CannotParseNumber(format!("offset/{}", pname), data.clone());
-                    Err(err)
-                }
-            }
             "src" => {
                 if let Ok(v) = data.parse::<f64>() {
-                    self.src = Aug::val(v);
-                    Ok(true)
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("offset/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("swap/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("swap/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "val" => {
-                let _ = self.set(pname, Aug::val(0.0));
-            }
             "src" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for Offset {
+impl Proc for Swap {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let (l, r) = self.src.proc(&transport);
-        let val = self.val.proc(&transport).0;
-        (l + val, r + val)
+        let (l, r) = self.src.proc(transport);
+        (r, l)
     }
 }
 
-pub struct Gain {
-    pub gain: Aug,
+// folds the source into mono, putting the average of left and right on both channels
+pub struct ToMono {
     pub src: Aug,
 }
 
-impl Gain {
-    pub fn new(gain: Aug, src: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Gain {
-            gain: gain,
-            src: src,
-        }))))
+impl ToMono {
+    pub fn new(src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(ToMono { src }))))
     }
 }
 
-impl Walk for Gain {
+impl Walk for ToMono {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
         if f(&self.src) {
             self.src.walk(f);
@@ -476,37 +488,32 @@ impl Walk for Gain {
     }
 }
 
-impl Dump for Gain {
+impl Dump for ToMono {
+    fn name(&self) -> &'static str {
+        "mono"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
-        slots.push(Slot {
-            ug: self.gain.clone(),
-            name: "gain".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.gain) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.gain.clone()),
-            },
-        });
         slots.push(Slot {
             ug: self.src.clone(),
             name: "src".to_string(),
             value: match shared_ug.iter().position(|e| *e == self.src) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
                 None => Value::Ug(self.src.clone()),
             },
         });
 
-        UgNode::Ug("gain".to_string(), slots)
+        UgNode::Ug("mono".to_string(), slots)
     }
 }
 
-impl Operate for Gain {
+impl Operate for ToMono {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            "gain" => Ok(self.gain.clone()),
             "src" => Ok(self.src.clone()),
-            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("mono/{}", pname))),
         }
     }
 
@@ -517,7 +524,7 @@ impl Operate for Gain {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "gain/{}",
+                        "mono/{}",
                         pname
                     )))
                 }
@@ -528,115 +535,103 @@ impl Operate for Gain {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            "gain" => {
-                self.gain = ug;
-                Ok(true)
-            }
             "src" => {
                 self.src = ug;
                 Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("mono/{}", pname))),
         }
     }
+
     fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
         let mut data = data.clone();
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            "gain" => {
-                if let Ok(v) = data.parse::<f64>() {
-                    self.gain = Aug::val(v);
-                    Ok(true)
-                } else {
-                    let err =
-                        OperateError::CannotParseNumber(format!("gain/{}", pname), data.clone());
-                    Err(err)
-                }
-            }
             "src" => {
                 if let Ok(v) = data.parse::<f64>() {
-                    self.src = Aug::val(v);
-                    Ok(true)
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("gain/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("mono/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("mono/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "gain" => {
-                let _ = self.set(pname, Aug::val(0.0));
-            }
             "src" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for Gain {
+impl Proc for ToMono {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let (l, r) = self.src.proc(&transport);
-        let gain = self.gain.proc(&transport).0;
-        (l * gain, r * gain)
+        let (l, r) = self.src.proc(transport);
+        let m = (l + r) / 2.0;
+        (m, m)
     }
 }
 
-pub struct Add {
-    pub sources: Vec<Aug>,
+// spreads the left channel of the source to both channels, as oscillators do with their output
+pub struct Dup {
+    pub src: Aug,
 }
 
-impl Add {
-    pub fn new(sources: Vec<Aug>) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Add { sources: sources }))))
+impl Dup {
+    pub fn new(src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Dup { src }))))
     }
 }
 
-impl Walk for Add {
+impl Walk for Dup {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        for s in self.sources.iter() {
-            if f(s) {
-                s.walk(f);
-            }
+        if f(&self.src) {
+            self.src.walk(f);
         }
     }
 }
 
-impl Dump for Add {
+impl Dump for Dup {
+    fn name(&self) -> &'static str {
+        "dup"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
-        let mut values = Vec::new();
+        let mut slots = Vec::new();
 
-        for u in self.sources.iter() {
-            match shared_ug.iter().position(|e| *e == *u) {
-                Some(n) => values.push(Box::new(Value::Shared(
-                    n,
-                    shared_ug.iter().nth(n).unwrap().clone(),
-                ))),
-                None => values.push(Box::new(Value::Ug(u.clone()))),
-            };
-        }
-        UgNode::UgRest("+".to_string(), Vec::new(), "src".to_string(), values)
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("dup".to_string(), slots)
     }
 }
 
-impl Operate for Add {
+impl Operate for Dup {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    Ok(self.sources[idx].clone())
-                } else {
-                    Err(OperateError::ParamNotFound(format!("add/{}", pname)))
-                }
-            }
-            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("dup/{}", pname))),
         }
     }
 
@@ -647,7 +642,7 @@ impl Operate for Add {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "add/{}",
+                        "dup/{}",
                         pname
                     )))
                 }
@@ -658,18 +653,11 @@ impl Operate for Add {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    while self.sources.len() <= idx {
-                        self.sources.push(Aug::val(0.0));
-                    }
-                    self.sources[idx] = ug;
-                    Ok(true)
-                } else {
-                    Err(OperateError::ParamNotFound(format!("add/{}", pname)))
-                }
+            "src" => {
+                self.src = ug;
+                Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("dup/{}", pname))),
         }
     }
 
@@ -678,91 +666,89 @@ impl Operate for Add {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(val) = data.parse::<f64>() {
-                    self.set(pname, Aug::val(val))
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("add/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("dup/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("dup/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            name if name.starts_with("src") => {
+            "src" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for Add {
+impl Proc for Dup {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let mut l = 0.0;
-        let mut r = 0.0;
-        for u in self.sources.iter_mut() {
-            let (l2, r2) = u.proc(&transport);
-            l += l2;
-            r += r2;
-        }
-        (l, r)
+        let (l, _) = self.src.proc(transport);
+        (l, l)
     }
 }
 
-pub struct Multiply {
-    pub sources: Vec<Aug>,
+// converts left/right into mid/side; scaled by 1/sqrt(2) so applying it twice restores the source
+pub struct MidSide {
+    pub src: Aug,
 }
 
-impl Multiply {
-    pub fn new(sources: Vec<Aug>) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Multiply { sources: sources }))))
+impl MidSide {
+    pub fn new(src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(MidSide { src }))))
     }
 }
 
-impl Walk for Multiply {
+impl Walk for MidSide {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        for s in self.sources.iter() {
-            if f(s) {
-                s.walk(f);
-            }
+        if f(&self.src) {
+            self.src.walk(f);
         }
     }
 }
 
-impl Dump for Multiply {
+impl Dump for MidSide {
+    fn name(&self) -> &'static str {
+        "ms"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
-        let mut values = Vec::new();
+        let mut slots = Vec::new();
 
-        for u in self.sources.iter() {
-            match shared_ug.iter().position(|e| *e == *u) {
-                Some(n) => values.push(Box::new(Value::Shared(
-                    n,
-                    shared_ug.iter().nth(n).unwrap().clone(),
-                ))),
-                None => values.push(Box::new(Value::Ug(u.clone()))),
-            };
-        }
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
 
-        UgNode::UgRest("*".to_string(), Vec::new(), "src".to_string(), values)
+        UgNode::Ug("ms".to_string(), slots)
     }
 }
 
-impl Operate for Multiply {
+impl Operate for MidSide {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    Ok(self.sources[idx].clone())
-                } else {
-                    Err(OperateError::ParamNotFound(format!("mul/{}", pname)))
-                }
-            }
-            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("ms/{}", pname))),
         }
     }
 
@@ -773,7 +759,7 @@ impl Operate for Multiply {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "mul/{}",
+                        "ms/{}",
                         pname
                     )))
                 }
@@ -784,18 +770,11 @@ impl Operate for Multiply {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    while self.sources.len() <= idx {
-                        self.sources.push(Aug::val(0.0));
-                    }
-                    self.sources[idx] = ug;
-                    Ok(true)
-                } else {
-                    Err(OperateError::ParamNotFound(format!("mul/{}", pname)))
-                }
+            "src" => {
+                self.src = ug;
+                Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("ms/{}", pname))),
         }
     }
 
@@ -804,119 +783,140 @@ impl Operate for Multiply {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            name if name.starts_with("src") => {
-                if let Ok(val) = data.parse::<f64>() {
-                    self.set(pname, Aug::val(val))
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("mul/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("ms/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("ms/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            name if name.starts_with("src") => {
+            "src" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for Multiply {
+impl Proc for MidSide {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let mut l = 1.0;
-        let mut r = 1.0;
-        for u in self.sources.iter_mut() {
-            let (l2, r2) = u.proc(&transport);
-            l *= l2;
-            r *= r2;
-        }
-        (l, r)
+        let (l, r) = self.src.proc(transport);
+        let k = std::f64::consts::FRAC_1_SQRT_2;
+        ((l + r) * k, (l - r) * k)
     }
 }
 
-pub struct Out {
-    vol: Aug,
-    sources: Vec<Aug>,
+pub struct Clip {
+    pub min: Aug,
+    pub max: Aug,
+    pub mode: ClipMode,
+    pub src: Aug,
 }
 
-impl Out {
-    pub fn new(vol: Aug, sources: Vec<Aug>) -> Aug {
-        Aug::new(UGen::new(UG::Proc(Box::new(Out {
-            vol: vol,
-            sources: sources,
+impl Clip {
+    pub fn new(min: Aug, max: Aug, mode: ClipMode, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Clip {
+            min,
+            max,
+            mode,
+            src,
         }))))
     }
 }
 
-impl Walk for Out {
+impl Walk for Clip {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        if f(&self.vol) {
-            self.vol.walk(f);
+        if f(&self.min) {
+            self.min.walk(f);
         }
-        for s in self.sources.iter() {
-            if f(s) {
-                s.walk(f);
-            }
+        if f(&self.max) {
+            self.max.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
         }
     }
 }
 
-impl Dump for Out {
+impl Dump for Clip {
+    fn name(&self) -> &'static str {
+        "clip"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
-        let mut values = Vec::new();
 
         slots.push(Slot {
-            ug: self.vol.clone(),
-            name: "vol".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.vol) {
+            ug: self.min.clone(),
+            name: "min".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.min) {
                 Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.vol.clone()),
+                None => Value::Ug(self.min.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.max.clone(),
+            name: "max".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.max) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.max.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "mode".to_string(),
+            value: Value::Symbol(self.mode.to_str().to_string()),
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.src.clone()),
             },
         });
 
-        for u in self.sources.iter() {
-            match shared_ug.iter().position(|e| *e == *u) {
-                Some(n) => values.push(Box::new(Value::Shared(
-                    n,
-                    shared_ug.iter().nth(n).unwrap().clone(),
-                ))),
-                None => values.push(Box::new(Value::Ug(u.clone()))),
-            }
-        }
-        UgNode::UgRest("out".to_string(), slots, "src".to_string(), values)
+        UgNode::Ug("clip".to_string(), slots)
     }
 }
 
-impl Operate for Out {
+impl Operate for Clip {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            "vol" => Ok(self.vol.clone()),
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    Ok(self.sources[idx].clone())
-                } else {
-                    Err(OperateError::ParamNotFound(format!("out/{}", pname)))
-                }
-            }
-            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+            "min" => Ok(self.min.clone()),
+            "max" => Ok(self.max.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
         }
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "mode" {
+            return Ok(self.mode.to_str().to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "out/{}",
+                        "clip/{}",
                         pname
                     )))
                 }
@@ -927,22 +927,198 @@ impl Operate for Out {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            "vol" => {
-                self.vol = ug;
+            "min" => {
+                self.min = ug;
                 Ok(true)
             }
-            name if name.starts_with("src") => {
-                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
-                    while self.sources.len() <= idx {
-                        self.sources.push(Aug::val(0.0));
-                    }
-                    self.sources[idx] = ug;
-                    Ok(true)
-                } else {
-                    Err(OperateError::ParamNotFound(format!("out/{}", pname)))
-                }
+            "max" => {
+                self.max = ug;
+                Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
+        }
+    }
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "mode" => match ClipMode::parse(&data) {
+                Some(mode) => {
+                    self.mode = mode;
+                    Ok(true)
+                }
+                None => Err(OperateError::CannotParseSymbol(
+                    format!("clip/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "min" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.min = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            "max" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.max = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.src = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("clip/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("clip/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "mode" => self.mode = ClipMode::Hard,
+            "min" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            "max" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["min", "max", "mode", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "min" => Some(ParamInfo::new(-1.0, 1.0, -1.0, ParamUnit::Bipolar)),
+            "max" => Some(ParamInfo::new(-1.0, 1.0, 1.0, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Clip {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(&transport);
+        let min = self.min.proc(&transport).0;
+        let max = self.max.proc(&transport).0;
+        (self.mode.apply(l, min, max), self.mode.apply(r, min, max))
+    }
+}
+
+pub struct Offset {
+    pub val: Aug,
+    pub src: Aug,
+}
+
+impl Offset {
+    pub fn new(val: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Offset { val: val, src: src }))))
+    }
+}
+
+impl Walk for Offset {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.val) {
+            self.val.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Offset {
+    fn name(&self) -> &'static str {
+        "offset"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.val.clone(),
+            name: "val".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.val) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.val.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("offset".to_string(), slots)
+    }
+}
+
+impl Operate for Offset {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "val" => Ok(self.val.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "offset/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "val" => {
+                self.val = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
         }
     }
 
@@ -951,52 +1127,3611 @@ impl Operate for Out {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            "vol" => {
-                if let Ok(vol) = data.parse::<f64>() {
-                    self.vol = Aug::val(vol);
+            "val" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.val = Aug::val(v);
                     Ok(true)
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("out/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("offset/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            name if name.starts_with("src") => {
-                if let Ok(val) = data.parse::<f64>() {
-                    self.set(pname, Aug::val(val))
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.src = Aug::val(v);
+                    Ok(true)
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("out/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("offset/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("offset/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "vol" => {
+            "val" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
-            name if name.starts_with("src") => {
+            "src" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["val", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "val" => Some(ParamInfo::new(-1.0, 1.0, 0.0, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
 }
 
-impl Proc for Out {
+impl Proc for Offset {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let mut l = 0.0;
-        let mut r = 0.0;
-        let vol = self.vol.proc(&transport).0;
-        for u in self.sources.iter_mut() {
-            let (l2, r2) = u.proc(&transport);
-            l += l2;
-            r += r2;
+        let (l, r) = self.src.proc(&transport);
+        let val = self.val.proc(&transport).0;
+        (l + val, r + val)
+    }
+}
+
+pub fn db_to_gain(db: f64) -> f64 {
+    10.0f64.powf(db / 20.0)
+}
+
+pub struct Gain {
+    pub gain: Aug,
+    pub src: Aug,
+}
+
+impl Gain {
+    pub fn new(gain: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Gain {
+            gain: gain,
+            src: src,
+        }))))
+    }
+}
+
+impl Walk for Gain {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.src) {
+            self.src.walk(f);
         }
-        (l * vol, r * vol)
+    }
+}
+
+impl Dump for Gain {
+    fn name(&self) -> &'static str {
+        "gain"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.gain.clone(),
+            name: "gain".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gain) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.gain.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("gain".to_string(), slots)
+    }
+}
+
+impl Operate for Gain {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "gain" => Ok(self.gain.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "gain/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "gain" => {
+                self.gain = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+        }
+    }
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "gain" => {
+                // accepts decibels with a `dB` suffix, e.g. "-6dB"
+                let parsed = match data.strip_suffix("dB").or_else(|| data.strip_suffix("db")) {
+                    Some(db) => db.parse::<f64>().map(db_to_gain),
+                    None => data.parse::<f64>(),
+                };
+                if let Ok(v) = parsed {
+                    self.gain = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("gain/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.src = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("gain/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("gain/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "gain" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["gain", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "gain" => Some(ParamInfo::new(0.0, 4.0, 1.0, ParamUnit::Ratio)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Gain {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(&transport);
+        let gain = self.gain.proc(&transport).0;
+        (l * gain, r * gain)
+    }
+}
+
+pub struct Add {
+    pub sources: Vec<Aug>,
+}
+
+impl Add {
+    pub fn new(sources: Vec<Aug>) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Add { sources: sources }))))
+    }
+}
+
+impl Walk for Add {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        for s in self.sources.iter() {
+            if f(s) {
+                s.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for Add {
+    fn name(&self) -> &'static str {
+        "+"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut values = Vec::new();
+
+        for u in self.sources.iter() {
+            match shared_ug.iter().position(|e| *e == *u) {
+                Some(n) => values.push(Box::new(Value::Shared(
+                    n,
+                    shared_ug.iter().nth(n).unwrap().clone(),
+                ))),
+                None => values.push(Box::new(Value::Ug(u.clone()))),
+            };
+        }
+        UgNode::UgRest("+".to_string(), Vec::new(), "src".to_string(), values)
+    }
+}
+
+impl Operate for Add {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    Ok(self.sources[idx].clone())
+                } else {
+                    Err(OperateError::ParamNotFound(format!("add/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "add/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    while self.sources.len() <= idx {
+                        self.sources.push(Aug::val(0.0));
+                    }
+                    self.sources[idx] = ug;
+                    Ok(true)
+                } else {
+                    Err(OperateError::ParamNotFound(format!("add/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(val) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(val))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("add/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("add/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            name if name.starts_with("src") => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        (0..self.sources.len())
+            .map(|idx| format!("src{}", idx))
+            .collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Add {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let mut l = 0.0;
+        let mut r = 0.0;
+        for u in self.sources.iter_mut() {
+            let (l2, r2) = u.proc(&transport);
+            l += l2;
+            r += r2;
+        }
+        (l, r)
+    }
+}
+
+// sums the sources divided by their count, so that N equal inputs stay in range.
+// use `+` for raw summation
+pub struct AvgMix {
+    pub sources: Vec<Aug>,
+}
+
+impl AvgMix {
+    pub fn new(sources: Vec<Aug>) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(AvgMix { sources }))))
+    }
+}
+
+impl Walk for AvgMix {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        for s in self.sources.iter() {
+            if f(s) {
+                s.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for AvgMix {
+    fn name(&self) -> &'static str {
+        "avg"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut values = Vec::new();
+
+        for u in self.sources.iter() {
+            match shared_ug.iter().position(|e| *e == *u) {
+                Some(n) => values.push(Box::new(Value::Shared(n, shared_ug[n].clone()))),
+                None => values.push(Box::new(Value::Ug(u.clone()))),
+            };
+        }
+        UgNode::UgRest("avg".to_string(), Vec::new(), "src".to_string(), values)
+    }
+}
+
+impl Operate for AvgMix {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    Ok(self.sources[idx].clone())
+                } else {
+                    Err(OperateError::ParamNotFound(format!("avg/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("avg/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "avg/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    while self.sources.len() <= idx {
+                        self.sources.push(Aug::val(0.0));
+                    }
+                    self.sources[idx] = ug;
+                    Ok(true)
+                } else {
+                    Err(OperateError::ParamNotFound(format!("avg/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("avg/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(val) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(val))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("avg/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("avg/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            name if name.starts_with("src") => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        (0..self.sources.len())
+            .map(|idx| format!("src{}", idx))
+            .collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for AvgMix {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let mut l = 0.0;
+        let mut r = 0.0;
+        for u in self.sources.iter_mut() {
+            let (l2, r2) = u.proc(transport);
+            l += l2;
+            r += r2;
+        }
+        if self.sources.is_empty() {
+            return (0.0, 0.0);
+        }
+        let n = self.sources.len() as f64;
+        (l / n, r / n)
+    }
+}
+
+pub struct Multiply {
+    pub sources: Vec<Aug>,
+}
+
+impl Multiply {
+    pub fn new(sources: Vec<Aug>) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Multiply { sources: sources }))))
+    }
+}
+
+impl Walk for Multiply {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        for s in self.sources.iter() {
+            if f(s) {
+                s.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for Multiply {
+    fn name(&self) -> &'static str {
+        "*"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut values = Vec::new();
+
+        for u in self.sources.iter() {
+            match shared_ug.iter().position(|e| *e == *u) {
+                Some(n) => values.push(Box::new(Value::Shared(
+                    n,
+                    shared_ug.iter().nth(n).unwrap().clone(),
+                ))),
+                None => values.push(Box::new(Value::Ug(u.clone()))),
+            };
+        }
+
+        UgNode::UgRest("*".to_string(), Vec::new(), "src".to_string(), values)
+    }
+}
+
+impl Operate for Multiply {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    Ok(self.sources[idx].clone())
+                } else {
+                    Err(OperateError::ParamNotFound(format!("mul/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "mul/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    while self.sources.len() <= idx {
+                        self.sources.push(Aug::val(0.0));
+                    }
+                    self.sources[idx] = ug;
+                    Ok(true)
+                } else {
+                    Err(OperateError::ParamNotFound(format!("mul/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            name if name.starts_with("src") => {
+                if let Ok(val) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(val))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("mul/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("mul/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            name if name.starts_with("src") => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        (0..self.sources.len())
+            .map(|idx| format!("src{}", idx))
+            .collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Multiply {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let mut l = 1.0;
+        let mut r = 1.0;
+        for u in self.sources.iter_mut() {
+            let (l2, r2) = u.proc(&transport);
+            l *= l2;
+            r *= r2;
+        }
+        (l, r)
+    }
+}
+
+pub struct Subtract {
+    pub a: Aug,
+    pub b: Aug,
+}
+
+impl Subtract {
+    pub fn new(a: Aug, b: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Subtract { a, b }))))
+    }
+}
+
+impl Walk for Subtract {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.a) {
+            self.a.walk(f);
+        }
+        if f(&self.b) {
+            self.b.walk(f);
+        }
+    }
+}
+
+impl Dump for Subtract {
+    fn name(&self) -> &'static str {
+        "-"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.a.clone(),
+            name: "a".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.a) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.a.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.b.clone(),
+            name: "b".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.b) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.b.clone()),
+            },
+        });
+
+        UgNode::Ug("-".to_string(), slots)
+    }
+}
+
+impl Operate for Subtract {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "a" => Ok(self.a.clone()),
+            "b" => Ok(self.b.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("sub/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "sub/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "a" => {
+                self.a = ug;
+                Ok(true)
+            }
+            "b" => {
+                self.b = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("sub/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "a" | "b" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("sub/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("sub/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "a" | "b" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["a", "b"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Subtract {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (al, ar) = self.a.proc(transport);
+        let (bl, br) = self.b.proc(transport);
+        (al - bl, ar - br)
+    }
+}
+
+pub struct Out {
+    vol: Aug,
+    sources: Vec<Aug>,
+}
+
+impl Out {
+    pub fn new(vol: Aug, sources: Vec<Aug>) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Out {
+            vol: vol,
+            sources: sources,
+        }))))
+    }
+}
+
+impl Walk for Out {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.vol) {
+            self.vol.walk(f);
+        }
+        for s in self.sources.iter() {
+            if f(s) {
+                s.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for Out {
+    fn name(&self) -> &'static str {
+        "out"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+        let mut values = Vec::new();
+
+        slots.push(Slot {
+            ug: self.vol.clone(),
+            name: "vol".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.vol) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.vol.clone()),
+            },
+        });
+
+        for u in self.sources.iter() {
+            match shared_ug.iter().position(|e| *e == *u) {
+                Some(n) => values.push(Box::new(Value::Shared(
+                    n,
+                    shared_ug.iter().nth(n).unwrap().clone(),
+                ))),
+                None => values.push(Box::new(Value::Ug(u.clone()))),
+            }
+        }
+        UgNode::UgRest("out".to_string(), slots, "src".to_string(), values)
+    }
+}
+
+impl Operate for Out {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "vol" => Ok(self.vol.clone()),
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    Ok(self.sources[idx].clone())
+                } else {
+                    Err(OperateError::ParamNotFound(format!("out/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "out/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "vol" => {
+                self.vol = ug;
+                Ok(true)
+            }
+            name if name.starts_with("src") => {
+                if let Ok(idx) = name[3..].to_string().parse::<usize>() {
+                    while self.sources.len() <= idx {
+                        self.sources.push(Aug::val(0.0));
+                    }
+                    self.sources[idx] = ug;
+                    Ok(true)
+                } else {
+                    Err(OperateError::ParamNotFound(format!("out/{}", pname)))
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "vol" => {
+                if let Ok(vol) = data.parse::<f64>() {
+                    self.vol = Aug::val(vol);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("out/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            name if name.starts_with("src") => {
+                if let Ok(val) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(val))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("out/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("out/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "vol" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            name if name.starts_with("src") => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["vol".to_string()];
+        for idx in 0..self.sources.len() {
+            params.push(format!("src{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "vol" => Some(ParamInfo::new(0.0, 1.0, 1.0, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Out {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let mut l = 0.0;
+        let mut r = 0.0;
+        let vol = self.vol.proc(&transport).0;
+        for u in self.sources.iter_mut() {
+            let (l2, r2) = u.proc(&transport);
+            l += l2;
+            r += r2;
+        }
+        (l * vol, r * vol)
+    }
+}
+
+pub struct XFade {
+    pub a: Aug,
+    pub b: Aug,
+    pub x: Aug,
+}
+
+impl XFade {
+    pub fn new(a: Aug, b: Aug, x: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(XFade { a, b, x }))))
+    }
+}
+
+impl Walk for XFade {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.a) {
+            self.a.walk(f);
+        }
+        if f(&self.b) {
+            self.b.walk(f);
+        }
+        if f(&self.x) {
+            self.x.walk(f);
+        }
+    }
+}
+
+impl Dump for XFade {
+    fn name(&self) -> &'static str {
+        "xfade"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.a.clone(),
+            name: "a".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.a) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.a.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.b.clone(),
+            name: "b".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.b) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.b.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.x.clone(),
+            name: "x".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.x) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.x.clone()),
+            },
+        });
+
+        UgNode::Ug("xfade".to_string(), slots)
+    }
+}
+
+impl Operate for XFade {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "a" => Ok(self.a.clone()),
+            "b" => Ok(self.b.clone()),
+            "x" => Ok(self.x.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("xfade/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "xfade/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "a" => {
+                self.a = ug;
+                Ok(true)
+            }
+            "b" => {
+                self.b = ug;
+                Ok(true)
+            }
+            "x" => {
+                self.x = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("xfade/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "a" | "b" | "x" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("xfade/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("xfade/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "a" | "b" | "x" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["a", "b", "x"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "x" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for XFade {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (al, ar) = self.a.proc(transport);
+        let (bl, br) = self.b.proc(transport);
+        let x = num::clamp(self.x.proc(transport).0, 0.0, 1.0);
+
+        // equal-power curves keep a * a + b * b constant along the fade
+        let theta = x * std::f64::consts::FRAC_PI_2;
+        let (ga, gb) = (theta.cos(), theta.sin());
+        (al * ga + bl * gb, ar * ga + br * gb)
+    }
+}
+
+pub struct RingMod {
+    pub a: Aug,
+    pub b: Aug,
+}
+
+impl RingMod {
+    pub fn new(a: Aug, b: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(RingMod { a, b }))))
+    }
+}
+
+impl Walk for RingMod {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.a) {
+            self.a.walk(f);
+        }
+        if f(&self.b) {
+            self.b.walk(f);
+        }
+    }
+}
+
+impl Dump for RingMod {
+    fn name(&self) -> &'static str {
+        "ring"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.a.clone(),
+            name: "a".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.a) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.a.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.b.clone(),
+            name: "b".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.b) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.b.clone()),
+            },
+        });
+
+        UgNode::Ug("ring".to_string(), slots)
+    }
+}
+
+impl Operate for RingMod {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "a" => Ok(self.a.clone()),
+            "b" => Ok(self.b.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("ring/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "ring/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "a" => {
+                self.a = ug;
+                Ok(true)
+            }
+            "b" => {
+                self.b = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("ring/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "a" | "b" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("ring/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("ring/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "a" | "b" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["a", "b"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for RingMod {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (al, ar) = self.a.proc(transport);
+        let (bl, br) = self.b.proc(transport);
+        (al * bl, ar * br)
+    }
+}
+
+pub struct Glide {
+    pub time: Aug,
+    pub src: Aug,
+    last: Signal,
+}
+
+impl Glide {
+    pub fn new(time: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Glide {
+            time,
+            src,
+            last: (0.0, 0.0),
+        }))))
+    }
+}
+
+impl Walk for Glide {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.time) {
+            self.time.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Glide {
+    fn name(&self) -> &'static str {
+        "glide"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.time.clone(),
+            name: "time".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.time) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.time.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("glide".to_string(), slots)
+    }
+}
+
+impl Operate for Glide {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "time" => Ok(self.time.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("glide/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "glide/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "time" => {
+                self.time = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("glide/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "time" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("glide/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("glide/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "time" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["time", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "time" => Some(ParamInfo::new(0.0, 10.0, 0.1, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Glide {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let time = self.time.proc(transport).0;
+
+        if time <= 0.0 {
+            self.last = (l, r);
+        } else {
+            // one-pole lowpass; `time` is its time constant
+            let a = (-1.0 / (time * transport.sample_rate as f64)).exp();
+            self.last = (l + a * (self.last.0 - l), r + a * (self.last.1 - r));
+        }
+        self.last
+    }
+
+    fn reset(&mut self) {
+        self.last = (0.0, 0.0);
+    }
+}
+
+// rectifies the source and smooths it with separate attack and release times
+pub struct EnvFollow {
+    pub attack: Aug,
+    pub release: Aug,
+    pub src: Aug,
+    env: Signal,
+}
+
+impl EnvFollow {
+    pub fn new(attack: Aug, release: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(EnvFollow {
+            attack,
+            release,
+            src,
+            env: (0.0, 0.0),
+        }))))
+    }
+}
+
+impl Walk for EnvFollow {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.attack) {
+            self.attack.walk(f);
+        }
+        if f(&self.release) {
+            self.release.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for EnvFollow {
+    fn name(&self) -> &'static str {
+        "follow"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.attack.clone(),
+            name: "attack".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.attack) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.attack.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.release.clone(),
+            name: "release".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.release) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.release.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("follow".to_string(), slots)
+    }
+}
+
+impl Operate for EnvFollow {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "attack" => Ok(self.attack.clone()),
+            "release" => Ok(self.release.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("follow/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "follow/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "attack" => {
+                self.attack = ug;
+                Ok(true)
+            }
+            "release" => {
+                self.release = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("follow/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "attack" | "release" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("follow/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("follow/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "attack" | "release" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["attack", "release", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "attack" => Some(ParamInfo::new(0.0, 1.0, 0.01, ParamUnit::Seconds)),
+            "release" => Some(ParamInfo::new(0.0, 2.0, 0.1, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for EnvFollow {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let attack = self.attack.proc(transport).0;
+        let release = self.release.proc(transport).0;
+
+        // one-pole lowpass whose time constant depends on rising or falling
+        let follow = |last: f64, v: f64| {
+            let v = v.abs();
+            let time = if v > last { attack } else { release };
+            if time <= 0.0 {
+                v
+            } else {
+                let a = (-1.0 / (time * transport.sample_rate as f64)).exp();
+                v + a * (last - v)
+            }
+        };
+        self.env = (follow(self.env.0, l), follow(self.env.1, r));
+        self.env
+    }
+
+    fn reset(&mut self) {
+        self.env = (0.0, 0.0);
+    }
+}
+
+// limits how fast the source changes; `rise` and `fall` are in units per second
+pub struct Slew {
+    pub rise: Aug,
+    pub fall: Aug,
+    pub src: Aug,
+    last: Signal,
+}
+
+impl Slew {
+    pub fn new(rise: Aug, fall: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Slew {
+            rise,
+            fall,
+            src,
+            last: (0.0, 0.0),
+        }))))
+    }
+}
+
+impl Walk for Slew {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.rise) {
+            self.rise.walk(f);
+        }
+        if f(&self.fall) {
+            self.fall.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Slew {
+    fn name(&self) -> &'static str {
+        "slew"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.rise.clone(),
+            name: "rise".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.rise) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.rise.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.fall.clone(),
+            name: "fall".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.fall) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.fall.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("slew".to_string(), slots)
+    }
+}
+
+impl Operate for Slew {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "rise" => Ok(self.rise.clone()),
+            "fall" => Ok(self.fall.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("slew/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "slew/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "rise" => {
+                self.rise = ug;
+                Ok(true)
+            }
+            "fall" => {
+                self.fall = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("slew/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "rise" | "fall" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("slew/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("slew/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "rise" | "fall" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["rise", "fall", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "rise" => Some(ParamInfo::new(0.0, 10000.0, 100.0, ParamUnit::Hz)),
+            "fall" => Some(ParamInfo::new(0.0, 10000.0, 100.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Slew {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let rise = self.rise.proc(transport).0.abs() / transport.sample_rate as f64;
+        let fall = self.fall.proc(transport).0.abs() / transport.sample_rate as f64;
+
+        let limit = |last: f64, v: f64| last + (v - last).clamp(-fall, rise);
+        self.last = (limit(self.last.0, l), limit(self.last.1, r));
+        self.last
+    }
+
+    fn reset(&mut self) {
+        self.last = (0.0, 0.0);
+    }
+}
+
+// snaps a frequency in Hz to the nearest note of the scale built on `root`
+pub struct Quantize {
+    pub scale: Scale,
+    pub root: Aug,
+    pub src: Aug,
+}
+
+impl Quantize {
+    pub fn new(scale: Scale, root: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Quantize { scale, root, src }))))
+    }
+
+    fn quantize(&self, freq: f64, root: f64) -> f64 {
+        if freq <= 0.0 || root <= 0.0 {
+            return 0.0;
+        }
+        let semitones = 12.0 * (freq / root).log2();
+        let octave = (semitones / 12.0).floor() as i32;
+        let mut nearest = 0;
+        let mut distance = f64::MAX;
+        for o in octave - 1..=octave + 1 {
+            for d in self.scale.degrees().iter() {
+                let n = o * 12 + d;
+                if (n as f64 - semitones).abs() < distance {
+                    nearest = n;
+                    distance = (n as f64 - semitones).abs();
+                }
+            }
+        }
+        root * 2.0f64.powf(nearest as f64 / 12.0)
+    }
+}
+
+impl Walk for Quantize {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.root) {
+            self.root.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for Quantize {
+    fn name(&self) -> &'static str {
+        "quantize"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "scale".to_string(),
+            value: Value::Symbol(self.scale.to_str().to_string()),
+        });
+        slots.push(Slot {
+            ug: self.root.clone(),
+            name: "root".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.root) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.root.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("quantize".to_string(), slots)
+    }
+}
+
+impl Operate for Quantize {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "root" => Ok(self.root.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("quantize/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "scale" {
+            return Ok(self.scale.to_str().to_string());
+        }
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "quantize/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "root" => {
+                self.root = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("quantize/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "scale" => match Scale::parse(&data) {
+                Some(scale) => {
+                    self.scale = scale;
+                    Ok(true)
+                }
+                None => Err(OperateError::CannotParseSymbol(
+                    format!("quantize/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "root" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("quantize/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("quantize/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "scale" => self.scale = Scale::Chromatic,
+            "root" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["scale", "root", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "root" => Some(ParamInfo::new(20.0, 20000.0, 261.63, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Quantize {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let root = self.root.proc(transport).0;
+        (self.quantize(l, root), self.quantize(r, root))
+    }
+}
+
+// clicks on every beat of the measure, with an `accent` tone on the first beat of bars
+pub struct Metronome {
+    pub freq: Aug,
+    pub accent: Aug,
+    pub decay: Aug,
+    last_beat: Option<(u64, u64)>,
+    tone: f64,
+    env: f64,
+    ph: f64,
+}
+
+impl Metronome {
+    pub fn new(freq: Aug, accent: Aug, decay: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Metronome {
+            freq,
+            accent,
+            decay,
+            last_beat: None,
+            tone: 0.0,
+            env: 0.0,
+            ph: 0.0,
+        }))))
+    }
+}
+
+impl Walk for Metronome {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.freq) {
+            self.freq.walk(f);
+        }
+        if f(&self.accent) {
+            self.accent.walk(f);
+        }
+        if f(&self.decay) {
+            self.decay.walk(f);
+        }
+    }
+}
+
+impl Dump for Metronome {
+    fn name(&self) -> &'static str {
+        "metro"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.freq.clone(),
+            name: "freq".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.freq) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.freq.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.accent.clone(),
+            name: "accent".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.accent) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.accent.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.decay.clone(),
+            name: "decay".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.decay) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.decay.clone()),
+            },
+        });
+
+        UgNode::Ug("metro".to_string(), slots)
+    }
+}
+
+impl Operate for Metronome {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "freq" => Ok(self.freq.clone()),
+            "accent" => Ok(self.accent.clone()),
+            "decay" => Ok(self.decay.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("metro/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "metro/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "freq" => {
+                self.freq = ug;
+                Ok(true)
+            }
+            "accent" => {
+                self.accent = ug;
+                Ok(true)
+            }
+            "decay" => {
+                self.decay = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("metro/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "freq" | "accent" | "decay" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("metro/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("metro/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "freq" | "accent" | "decay" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["freq", "accent", "decay"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 1000.0, ParamUnit::Hz)),
+            "accent" => Some(ParamInfo::new(20.0, 20000.0, 1500.0, ParamUnit::Hz)),
+            "decay" => Some(ParamInfo::new(0.001, 1.0, 0.03, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Metronome {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let freq = self.freq.proc(transport).0;
+        let accent = self.accent.proc(transport).0;
+        let decay = self.decay.proc(transport).0;
+        let sample_rate = transport.sample_rate as f64;
+
+        let beat = (transport.pos.bar, transport.pos.beat);
+        if self.last_beat != Some(beat) {
+            self.last_beat = Some(beat);
+            self.tone = if beat.1 == 0 { accent } else { freq };
+            self.env = 1.0;
+            self.ph = 0.0;
+        }
+
+        let v = self.env * self.ph.sin();
+        self.ph += 2.0 * std::f64::consts::PI * self.tone / sample_rate;
+        if decay > 0.0 {
+            self.env *= (-1.0 / (decay * sample_rate)).exp();
+        } else {
+            self.env = 0.0;
+        }
+
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.last_beat = None;
+        self.env = 0.0;
+        self.ph = 0.0;
+    }
+}
+
+// maps `src` in 0.0..1.0 to `min`..`max` exponentially, e.g. for sweeping frequencies
+pub struct ExpScale {
+    pub min: Aug,
+    pub max: Aug,
+    pub src: Aug,
+}
+
+impl ExpScale {
+    pub fn new(min: Aug, max: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(ExpScale { min, max, src }))))
+    }
+}
+
+impl Walk for ExpScale {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.min) {
+            self.min.walk(f);
+        }
+        if f(&self.max) {
+            self.max.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for ExpScale {
+    fn name(&self) -> &'static str {
+        "expscale"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.min.clone(),
+            name: "min".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.min) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.min.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.max.clone(),
+            name: "max".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.max) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.max.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("expscale".to_string(), slots)
+    }
+}
+
+impl Operate for ExpScale {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "min" => Ok(self.min.clone()),
+            "max" => Ok(self.max.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("expscale/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "expscale/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "min" => {
+                self.min = ug;
+                Ok(true)
+            }
+            "max" => {
+                self.max = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("expscale/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "min" | "max" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("expscale/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("expscale/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "min" | "max" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["min", "max", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "min" => Some(ParamInfo::new(20.0, 20000.0, 20.0, ParamUnit::Hz)),
+            "max" => Some(ParamInfo::new(20.0, 20000.0, 20000.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for ExpScale {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let (l, r) = self.src.proc(transport);
+        let min = self.min.proc(transport).0;
+        let max = self.max.proc(transport).0;
+
+        // min and max must have the same sign for the ratio to have a real power
+        let scale = |v: f64| {
+            if min * max > 0.0 {
+                min * (max / min).powf(v)
+            } else {
+                min
+            }
+        };
+        (scale(l), scale(r))
+    }
+}
+
+// rising edge detection shared by units reacting to triggers
+#[derive(Default)]
+pub struct RisingEdge {
+    prev: f64,
+}
+
+impl RisingEdge {
+    pub fn new() -> RisingEdge {
+        RisingEdge { prev: 0.0 }
+    }
+
+    // true only on the sample where `v` goes above `threshold`
+    pub fn detect(&mut self, v: f64, threshold: f64) -> bool {
+        let rising = self.prev <= threshold && v > threshold;
+        self.prev = v;
+        rising
+    }
+
+    pub fn reset(&mut self) {
+        self.prev = 0.0;
+    }
+}
+
+// outputs 1.0 for one sample each time `src` rises above `threshold`.
+// named `edge` as `trig` is the envelope trigger
+pub struct EdgeTrigger {
+    pub threshold: Aug,
+    pub src: Aug,
+    edge: RisingEdge,
+}
+
+impl EdgeTrigger {
+    pub fn new(threshold: Aug, src: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(EdgeTrigger {
+            threshold,
+            src,
+            edge: RisingEdge::new(),
+        }))))
+    }
+}
+
+impl Walk for EdgeTrigger {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.threshold) {
+            self.threshold.walk(f);
+        }
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+    }
+}
+
+impl Dump for EdgeTrigger {
+    fn name(&self) -> &'static str {
+        "edge"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.threshold.clone(),
+            name: "threshold".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.threshold) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.threshold.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+
+        UgNode::Ug("edge".to_string(), slots)
+    }
+}
+
+impl Operate for EdgeTrigger {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "threshold" => Ok(self.threshold.clone()),
+            "src" => Ok(self.src.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("edge/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "edge/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "threshold" => {
+                self.threshold = ug;
+                Ok(true)
+            }
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("edge/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "threshold" | "src" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("edge/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("edge/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "threshold" | "src" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["threshold", "src"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "threshold" => Some(ParamInfo::new(-1.0, 1.0, 0.0, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for EdgeTrigger {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let threshold = self.threshold.proc(transport).0;
+        let v = self.src.proc(transport).0;
+        if self.edge.detect(v, threshold) {
+            (1.0, 1.0)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.edge.reset();
+    }
+}
+
+pub struct SampleHold {
+    pub src: Aug,
+    pub trigger: Aug,
+    edge: RisingEdge,
+    held: Signal,
+}
+
+impl SampleHold {
+    pub fn new(src: Aug, trigger: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(SampleHold {
+            src,
+            trigger,
+            edge: RisingEdge::new(),
+            held: (0.0, 0.0),
+        }))))
+    }
+}
+
+impl Walk for SampleHold {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.src) {
+            self.src.walk(f);
+        }
+        if f(&self.trigger) {
+            self.trigger.walk(f);
+        }
+    }
+}
+
+impl Dump for SampleHold {
+    fn name(&self) -> &'static str {
+        "sh"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.src.clone(),
+            name: "src".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.src) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.src.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.trigger.clone(),
+            name: "trigger".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.trigger) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.trigger.clone()),
+            },
+        });
+
+        UgNode::Ug("sh".to_string(), slots)
+    }
+}
+
+impl Operate for SampleHold {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "src" => Ok(self.src.clone()),
+            "trigger" => Ok(self.trigger.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("sh/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "sh/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "src" => {
+                self.src = ug;
+                Ok(true)
+            }
+            "trigger" => {
+                self.trigger = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("sh/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "src" | "trigger" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("sh/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("sh/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "src" | "trigger" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["src", "trigger"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for SampleHold {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let sig = self.src.proc(transport);
+        let trigger = self.trigger.proc(transport).0;
+
+        // sample only on rising edges crossing zero
+        if self.edge.detect(trigger, 0.0) {
+            self.held = sig;
+        }
+        self.held
+    }
+
+    fn reset(&mut self) {
+        self.edge.reset();
+        self.held = (0.0, 0.0);
+    }
+}
+
+// steps through `values` on each rising edge of `trigger`, e.g. for cutoff or pitch.
+// wrap it in `glide` to slide between steps
+pub struct StepSeq {
+    pub trigger: Aug,
+    pub values: Vec<f64>,
+    step: usize,
+    started: bool,
+    edge: RisingEdge,
+}
+
+impl StepSeq {
+    pub fn new(trigger: Aug, values: Vec<f64>) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(StepSeq {
+            trigger,
+            values,
+            step: 0,
+            started: false,
+            edge: RisingEdge::new(),
+        }))))
+    }
+
+    fn step_index(pname: &str) -> Option<usize> {
+        pname
+            .strip_prefix("step")
+            .and_then(|idx| idx.parse::<usize>().ok())
+    }
+}
+
+impl Walk for StepSeq {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.trigger) {
+            self.trigger.walk(f);
+        }
+    }
+}
+
+impl Dump for StepSeq {
+    fn name(&self) -> &'static str {
+        "stepseq"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+        let mut values = Vec::new();
+
+        slots.push(Slot {
+            ug: self.trigger.clone(),
+            name: "trigger".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.trigger) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.trigger.clone()),
+            },
+        });
+
+        for v in self.values.iter() {
+            values.push(Box::new(Value::Number(*v)));
+        }
+        UgNode::UgRest("stepseq".to_string(), slots, "step".to_string(), values)
+    }
+}
+
+impl Operate for StepSeq {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match (pname, StepSeq::step_index(pname)) {
+            ("trigger", _) => Ok(self.trigger.clone()),
+            (_, Some(idx)) if idx < self.values.len() => Ok(Aug::val(self.values[idx])),
+            _ => Err(OperateError::ParamNotFound(format!("stepseq/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "stepseq/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match (pname, StepSeq::step_index(pname)) {
+            ("trigger", _) => {
+                self.trigger = ug;
+                Ok(true)
+            }
+            (_, Some(idx)) => match ug.to_val() {
+                Some(v) => {
+                    // setting a step past the end adds zero steps up to it
+                    while self.values.len() <= idx {
+                        self.values.push(0.0);
+                    }
+                    self.values[idx] = v;
+                    Ok(true)
+                }
+                None => Err(OperateError::TypeMismatch(
+                    format!("stepseq/{}", pname),
+                    "number".to_string(),
+                )),
+            },
+            _ => Err(OperateError::ParamNotFound(format!("stepseq/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        if let Ok(v) = data.parse::<f64>() {
+            self.set(pname, Aug::val(v))
+        } else {
+            let err = OperateError::CannotParseNumber(format!("stepseq/{}", pname), data.clone());
+            Err(err)
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        let _ = self.set(pname, Aug::val(0.0));
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["trigger".to_string()];
+        for idx in 0..self.values.len() {
+            params.push(format!("step{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for StepSeq {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let trigger = self.trigger.proc(transport).0;
+        if self.values.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        // the first trigger plays the first step instead of skipping it
+        if self.edge.detect(trigger, 0.0) {
+            if self.started {
+                self.step = (self.step + 1) % self.values.len();
+            }
+            self.started = true;
+        }
+        let v = self.values[self.step % self.values.len()];
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.edge.reset();
+        self.step = 0;
+        self.started = false;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ArpMode {
+    Up,
+    Down,
+    UpDown,
+    Random,
+}
+
+impl ArpMode {
+    pub fn parse(s: &str) -> Option<ArpMode> {
+        match s {
+            "up" => Some(ArpMode::Up),
+            "down" => Some(ArpMode::Down),
+            "updown" => Some(ArpMode::UpDown),
+            "random" => Some(ArpMode::Random),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            ArpMode::Up => "up",
+            ArpMode::Down => "down",
+            ArpMode::UpDown => "updown",
+            ArpMode::Random => "random",
+        }
+    }
+}
+
+// steps through chord notes every `rate`th note (e.g. 16 for sixteenths) in time with the
+// transport, outputting the frequency of the current note
+pub struct Arp {
+    pub mode: ArpMode,
+    pub rate: Aug,
+    pub notes: Vec<Pitch>,
+    tuning: Tuning,
+    root: NoteNum,
+    last_step: Option<u64>,
+    count: usize,
+    index: usize,
+    rng: SmallRng,
+}
+
+impl Arp {
+    pub fn new(mode: ArpMode, rate: Aug, notes: Vec<Pitch>, tuning: Tuning, root: NoteNum) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Arp {
+            mode,
+            rate,
+            notes,
+            tuning,
+            root,
+            last_step: None,
+            count: 0,
+            index: 0,
+            rng: SmallRng::seed_from_u64(0),
+        }))))
+    }
+
+    fn note_index(pname: &str) -> Option<usize> {
+        pname
+            .strip_prefix("note")
+            .and_then(|idx| idx.parse::<usize>().ok())
+    }
+
+    fn next_index(&mut self) -> usize {
+        let n = self.notes.len();
+        let i = self.count;
+        match self.mode {
+            ArpMode::Up => i % n,
+            ArpMode::Down => n - 1 - i % n,
+            // the top and bottom notes are not repeated at the turns
+            ArpMode::UpDown if n > 1 => {
+                let i = i % (2 * n - 2);
+                if i < n {
+                    i
+                } else {
+                    2 * n - 2 - i
+                }
+            }
+            ArpMode::UpDown => 0,
+            ArpMode::Random => self.rng.gen_range(0, n),
+        }
+    }
+}
+
+impl Walk for Arp {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.rate) {
+            self.rate.walk(f);
+        }
+    }
+}
+
+impl Dump for Arp {
+    fn name(&self) -> &'static str {
+        "arp"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+        let mut notes = Vec::new();
+
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "mode".to_string(),
+            value: Value::Symbol(self.mode.to_str().to_string()),
+        });
+        slots.push(Slot {
+            ug: self.rate.clone(),
+            name: "rate".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.rate) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.rate.clone()),
+            },
+        });
+
+        for pitch in self.notes.iter() {
+            notes.push(Box::new(Value::Symbol(to_str(pitch))));
+        }
+        UgNode::UgRest("arp".to_string(), slots, "note".to_string(), notes)
+    }
+}
+
+impl Operate for Arp {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match (pname, Arp::note_index(pname)) {
+            ("rate", _) => Ok(self.rate.clone()),
+            (_, Some(idx)) if idx < self.notes.len() => Ok(Aug::val(to_tuned_freq(
+                &self.notes[idx],
+                &self.tuning,
+                self.root,
+            ))),
+            _ => Err(OperateError::ParamNotFound(format!("arp/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match (pname, Arp::note_index(pname)) {
+            ("mode", _) => Ok(self.mode.to_str().to_string()),
+            (_, Some(idx)) if idx < self.notes.len() => Ok(to_str(&self.notes[idx])),
+            _ => match self.get(pname) {
+                Ok(aug) => {
+                    if let Some(v) = aug.to_val() {
+                        Ok(v.to_string())
+                    } else {
+                        Err(OperateError::CannotRepresentAsString(format!(
+                            "arp/{}",
+                            pname
+                        )))
+                    }
+                }
+                Err(err) => Err(err),
+            },
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "rate" => {
+                self.rate = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("arp/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match (pname, Arp::note_index(pname)) {
+            ("mode", _) => match ArpMode::parse(&data) {
+                Some(mode) => {
+                    self.mode = mode;
+                    Ok(true)
+                }
+                None => Err(OperateError::CannotParseSymbol(
+                    format!("arp/{}", pname),
+                    data.clone(),
+                )),
+            },
+            (_, Some(idx)) => match to_note(&data) {
+                Some(pitch @ Pitch::Pitch(_, _)) => {
+                    // setting a note past the end repeats the last one up to it
+                    while self.notes.len() < idx {
+                        let last = self.notes.last().unwrap_or(&pitch).clone();
+                        self.notes.push(last);
+                    }
+                    if idx < self.notes.len() {
+                        self.notes[idx] = pitch;
+                    } else {
+                        self.notes.push(pitch);
+                    }
+                    Ok(true)
+                }
+                _ => Err(OperateError::CannotParseSymbol(
+                    format!("arp/{}", pname),
+                    data.clone(),
+                )),
+            },
+            _ => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("arp/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "mode" => self.mode = ArpMode::Up,
+            "rate" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        }
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["mode".to_string(), "rate".to_string()];
+        for idx in 0..self.notes.len() {
+            params.push(format!("note{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Arp {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let rate = self.rate.proc(transport).0;
+        if self.notes.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        // steps are counted from the start of the song so they stay on the grid when the
+        // rate changes. a rate of zero or below holds the current note
+        if rate > 0.0 {
+            let beats = Pattern::to_beats(&transport.pos, &transport.measure);
+            let step = (beats * rate / transport.measure.note as f64).floor() as u64;
+            if self.last_step != Some(step) {
+                if self.last_step.is_some() {
+                    self.count += 1;
+                }
+                self.last_step = Some(step);
+                self.index = self.next_index();
+            }
+        }
+
+        let pitch = &self.notes[self.index % self.notes.len()];
+        let freq = to_tuned_freq(pitch, &self.tuning, self.root);
+        (freq, freq)
+    }
+
+    fn reset(&mut self) {
+        self.last_step = None;
+        self.count = 0;
+        self.index = 0;
+        self.rng = SmallRng::seed_from_u64(0);
+    }
+}
+
+pub struct Gate {
+    pub pattern: Aug,
+    // laid out from `pattern` on first use and whenever the slot or the measure changes
+    steps: Option<PatternSteps>,
+}
+
+impl Gate {
+    pub fn new(pattern: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Gate {
+            pattern,
+            steps: None,
+        }))))
+    }
+}
+
+impl Walk for Gate {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.pattern) {
+            self.pattern.walk(f);
+        }
+    }
+}
+
+impl Dump for Gate {
+    fn name(&self) -> &'static str {
+        "gate"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.pattern.clone(),
+            name: "pattern".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.pattern) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.pattern.clone()),
+            },
+        });
+
+        UgNode::Ug("gate".to_string(), slots)
+    }
+}
+
+impl Operate for Gate {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "pattern" => Ok(self.pattern.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("gate/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "gate/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "pattern" => {
+                self.pattern = ug;
+                self.steps = None;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("gate/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        match pname {
+            "pattern" => {
+                let mut data = data.clone();
+                data.retain(|c| c != '\n');
+
+                if let Ok(msgs) = Pattern::parse_str(data.clone()) {
+                    self.pattern = Aug::new(UGen::new(UG::Pat(Pattern::new(msgs))));
+                    self.steps = None;
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParsePattern(format!("gate/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("gate/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        if pname == "pattern" {
+            let pattern = Aug::new(UGen::new(UG::Pat(Pattern::new(Vec::new()))));
+            let _ = self.set(pname, pattern);
+        }
+    }
+
+    fn params(&self) -> Vec<String> {
+        vec!["pattern".to_string()]
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Gate {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let measure = &transport.measure;
+        let stale = match &self.steps {
+            Some(steps) => steps.measure.beat != measure.beat || steps.measure.note != measure.note,
+            None => true,
+        };
+        if stale {
+            if let UG::Pat(pat) = &self.pattern.0.lock().unwrap().ug {
+                self.steps = Some(pat.steps(measure));
+            }
+        }
+
+        let v = match &self.steps {
+            Some(steps) if steps.note_at(&transport.pos).is_some() => 1.0,
+            _ => 0.0,
+        };
+        (v, v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ugens::core::Ramp;
+    use crate::ugens::osc::Pulse;
+    use crate::ugens::osc::Sine;
+
+    fn render(ug: &mut Aug, n: usize) -> Vec<Signal> {
+        ug.proc_n(&mut Transport::default(), n)
+    }
+
+    #[test]
+    fn xfade_midpoint_mixes_sources_equally() {
+        let only_a = render(
+            &mut XFade::new(Aug::val(1.0), Aug::val(0.0), Aug::val(0.5)),
+            1,
+        )[0];
+        let only_b = render(
+            &mut XFade::new(Aug::val(0.0), Aug::val(1.0), Aug::val(0.5)),
+            1,
+        )[0];
+        assert!((only_a.0 - only_b.0).abs() < 1e-9);
+        assert!((only_a.0 - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+
+        // equal-power fades may go over the louder input, but not by much
+        for i in 0..=10 {
+            let x = Aug::val(i as f64 / 10.0);
+            let (l, r) = render(&mut XFade::new(Aug::val(1.0), Aug::val(0.5), x), 1)[0];
+            assert!(l <= 1.5 && r <= 1.5);
+        }
+    }
+
+    // amplitude of the `freq` Hz component over whole seconds of `samples`
+    fn magnitude(samples: &[f64], freq: f64) -> f64 {
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, v) in samples.iter().enumerate() {
+            let t = 2.0 * std::f64::consts::PI * freq * i as f64 / 44100.0;
+            re += v * t.cos();
+            im += v * t.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / samples.len() as f64
+    }
+
+    #[test]
+    fn ringmod_makes_sum_and_difference_frequencies() {
+        let a = Sine::new(Aug::val(0.0), Aug::val(1000.0));
+        let b = Sine::new(Aug::val(0.0), Aug::val(300.0));
+        let out: Vec<f64> = render(&mut RingMod::new(a, b), 44100)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+
+        assert!((magnitude(&out, 700.0) - 0.5).abs() < 0.01);
+        assert!((magnitude(&out, 1300.0) - 0.5).abs() < 0.01);
+        assert!(magnitude(&out, 1000.0) < 0.01);
+        assert!(magnitude(&out, 300.0) < 0.01);
+    }
+
+    #[test]
+    fn glide_reaches_63_percent_after_one_time_constant() {
+        // 0.01 seconds is 441 samples at 44100 Hz
+        let out = render(&mut Glide::new(Aug::val(0.01), Aug::val(1.0)), 441);
+        assert!((out[440].0 - (1.0 - (-1.0f64).exp())).abs() < 1e-6);
+        assert!(out[439].0 < out[440].0);
+    }
+
+    #[test]
+    fn sample_hold_updates_only_on_rising_edges() {
+        let src = Ramp::new(1.0, 1001.0, 1000);
+        // 441 Hz square, rising every 100 samples
+        let trigger = Pulse::new(Aug::val(0.0), Aug::val(441.0), Aug::val(0.5));
+        let mut sh = SampleHold::new(src, trigger.clone());
+
+        let mut transport = Transport::default();
+        let (mut prev_trigger, mut prev_out) = (0.0, 0.0);
+        let mut updates = 0;
+        for _ in 0..1000 {
+            let out = sh.proc_n(&mut transport, 1)[0].0;
+            // the trigger is cached within the tick, so this is what sh has seen
+            let trig = trigger.0.lock().unwrap().proc(&transport).0;
+            let rising = prev_trigger <= 0.0 && trig > 0.0;
+            assert_eq!(out != prev_out, rising);
+            if rising {
+                updates += 1;
+            }
+            prev_trigger = trig;
+            prev_out = out;
+        }
+        assert!(updates >= 9);
+    }
+
+    #[test]
+    fn gate_is_high_while_notes_sound() {
+        // each step is a quarter beat, 5512.5 samples at 120 bpm
+        let pattern = Pattern::new(Pattern::parse_str("c4:1 r:1 e4:1".to_string()).unwrap());
+        let mut gate = Gate::new(Aug::new(UGen::new(UG::Pat(pattern))));
+        let out = render(&mut gate, 22050);
+        let step = 5512.5;
+        for (n, high) in [true, false, true, false].iter().enumerate() {
+            let mid = (step * (n as f64 + 0.5)) as usize;
+            assert_eq!(out[mid].0, if *high { 1.0 } else { 0.0 });
+        }
+        // stays low after the last note as the pattern doesn't loop
+        assert!(out[(step * 3.1) as usize..].iter().all(|s| s.0 == 0.0));
+    }
+
+    #[test]
+    fn gate_follows_a_replaced_pattern() {
+        let pattern = Pattern::new(Pattern::parse_str("c4:1 r:1 loop".to_string()).unwrap());
+        let mut gate = Gate::new(Aug::new(UGen::new(UG::Pat(pattern))));
+        let mut transport = Transport::default();
+        // middle of the first step, a quarter beat being 5512.5 samples
+        assert_eq!(gate.proc_n(&mut transport, 2756)[2755].0, 1.0);
+
+        gate.set_str("pattern", "r:1 c4:1 loop".to_string())
+            .unwrap();
+        assert_eq!(gate.proc_n(&mut transport, 1)[0].0, 0.0);
+        gate.clear("pattern");
+        assert_eq!(gate.proc_n(&mut transport, 5512)[5511].0, 0.0);
+    }
+
+    #[test]
+    fn db_converts_to_linear_gain() {
+        assert!((db_to_gain(-6.0) - 0.501).abs() < 1e-3);
+        assert_eq!(db_to_gain(0.0), 1.0);
+
+        let mut gain = Gain::new(Aug::val(1.0), Aug::val(1.0));
+        gain.set_str("gain", "-6dB".to_string()).unwrap();
+        assert!((gain.get("gain").unwrap().to_val().unwrap() - 0.501).abs() < 1e-3);
+        assert!(gain.set_str("gain", "-6 decibels".to_string()).is_err());
+    }
+
+    #[test]
+    fn signal_math_on_dc() {
+        let first = |mut ug: Aug| render(&mut ug, 1)[0];
+        assert_eq!(
+            first(Add::new(vec![Aug::val(0.5), Aug::val(0.25)])),
+            (0.75, 0.75)
+        );
+        assert_eq!(
+            first(Multiply::new(vec![Aug::val(0.5), Aug::val(0.25)])),
+            (0.125, 0.125)
+        );
+        assert_eq!(
+            first(Subtract::new(Aug::val(0.5), Aug::val(0.25))),
+            (0.25, 0.25)
+        );
+    }
+
+    #[test]
+    fn signal_math_with_a_constant() {
+        let sine = || Sine::new(Aug::val(0.0), Aug::val(880.0));
+        let dry = render(&mut sine(), 441);
+        let sum = render(&mut Add::new(vec![sine(), Aug::val(1.0)]), 441);
+        let product = render(&mut Multiply::new(vec![sine(), Aug::val(0.5)]), 441);
+        let difference = render(&mut Subtract::new(Aug::val(1.0), sine()), 441);
+        for i in 0..dry.len() {
+            assert!((sum[i].0 - (dry[i].0 + 1.0)).abs() < 1e-12);
+            assert!((product[i].0 - dry[i].0 * 0.5).abs() < 1e-12);
+            assert!((difference[i].0 - (1.0 - dry[i].0)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn fold_clip_reflects_over_range_input() {
+        let clip = |mode: ClipMode, v: f64| {
+            let mut ug = Clip::new(Aug::val(-1.0), Aug::val(1.0), mode, Aug::val(v));
+            render(&mut ug, 1)[0].0
+        };
+        assert_eq!(clip(ClipMode::Hard, 1.25), 1.0);
+        assert!((clip(ClipMode::Fold, 1.25) - 0.75).abs() < 1e-12);
+        assert!((clip(ClipMode::Fold, -1.5) + 0.5).abs() < 1e-12);
+        // in range input is left as it is
+        assert!((clip(ClipMode::Fold, 0.3) - 0.3).abs() < 1e-12);
+        let soft = clip(ClipMode::Soft, 1.25);
+        assert!(soft < 1.0 && soft > 0.75);
+
+        let mut ug = Clip::new(
+            Aug::val(-1.0),
+            Aug::val(1.0),
+            ClipMode::Hard,
+            Aug::val(1.25),
+        );
+        ug.set_str("mode", "fold".to_string()).unwrap();
+        assert!((render(&mut ug, 1)[0].0 - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn follower_rises_over_attack_and_falls_over_release() {
+        // 10 ms attack is 441 samples and 100 ms release is 4410 samples. the source is
+        // negative to see it rectified
+        let mut follow = EnvFollow::new(Aug::val(0.01), Aug::val(0.1), Aug::val(-1.0));
+        let mut transport = Transport::default();
+        let rise = follow.proc_n(&mut transport, 4410);
+        let after_attack = 1.0 - (-1.0f64).exp();
+        assert!((rise[440].0 - after_attack).abs() < 0.01);
+        assert!(rise.windows(2).all(|w| w[1].0 >= w[0].0));
+
+        follow.set("src", Aug::val(0.0)).unwrap();
+        let fall = follow.proc_n(&mut transport, 4410);
+        assert!((fall[4409].0 - rise[4409].0 * (-1.0f64).exp()).abs() < 0.01);
+        assert!(fall.windows(2).all(|w| w[1].0 <= w[0].0));
+    }
+
+    #[test]
+    fn slew_limits_rise_and_fall_separately() {
+        // up by 441 per second is 0.01 per sample, down by 4410 is 0.1 per sample
+        let mut slew = Slew::new(Aug::val(441.0), Aug::val(4410.0), Aug::val(1.0));
+        let mut transport = Transport::default();
+        let rise = slew.proc_n(&mut transport, 200);
+        assert!((rise[49].0 - 0.5).abs() < 1e-9);
+        assert!((rise[99].0 - 1.0).abs() < 1e-9);
+        assert_eq!(rise[199].0, 1.0);
+
+        slew.set("src", Aug::val(0.0)).unwrap();
+        let fall = slew.proc_n(&mut transport, 20);
+        assert!((fall[4].0 - 0.5).abs() < 1e-9);
+        assert!(fall[9].0.abs() < 1e-9);
+        assert_eq!(fall[19].0, 0.0);
+    }
+
+    #[test]
+    fn quantizer_outputs_only_scale_notes() {
+        let c4 = 261.6256;
+        let sweep = Ramp::new(200.0, 1000.0, 44100);
+        let mut quantize = Quantize::new(Scale::Major, Aug::val(c4), sweep);
+        let mut degrees = Vec::new();
+        for (v, _) in render(&mut quantize, 44100).iter() {
+            let semitones = 12.0 * (v / c4).log2();
+            assert!((semitones - semitones.round()).abs() < 1e-9);
+            let degree = (semitones.round() as i32).rem_euclid(12);
+            assert!(Scale::Major.degrees().contains(&degree));
+            if !degrees.contains(&degree) {
+                degrees.push(degree);
+            }
+        }
+        // the sweep is over two octaves so every note of the scale appears
+        assert_eq!(degrees.len(), 7);
+    }
+
+    #[test]
+    fn stereo_carries_each_source_on_its_channel() {
+        let stereo = || Stereo::new(Aug::val(0.25), Aug::val(-0.5));
+        assert_eq!(render(&mut stereo(), 1)[0], (0.25, -0.5));
+        assert_eq!(render(&mut Swap::new(stereo()), 1)[0], (-0.5, 0.25));
+
+        // mid-side encoding twice gives back the channels
+        let (l, r) = render(&mut MidSide::new(MidSide::new(stereo())), 1)[0];
+        assert!((l - 0.25).abs() < 1e-12 && (r + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn metronome_clicks_on_beats_with_accents_on_bars() {
+        // 4/4 at 120 bpm, so a beat is 22050 samples
+        let mut metro = Metronome::new(Aug::val(1000.0), Aug::val(3000.0), Aug::val(0.01));
+        let out: Vec<f64> = render(&mut metro, 2 * 4 * 22050)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+
+        // a click starts where the signal gets loud after a silence longer than 1000 samples
+        let mut onsets = Vec::new();
+        let mut last_loud: Option<usize> = None;
+        for (i, v) in out.iter().enumerate() {
+            if v.abs() > 1e-3 {
+                if last_loud.is_none_or(|l| i - l > 1000) {
+                    onsets.push(i);
+                }
+                last_loud = Some(i);
+            }
+        }
+        assert_eq!(onsets.len(), 8);
+        for (n, onset) in onsets.iter().enumerate() {
+            assert!((*onset as i64 - n as i64 * 22050).abs() <= 2);
+
+            // zero crossings over 10 ms tell the tone: 20 for 1 kHz and 60 for 3 kHz
+            let click = &out[*onset..*onset + 441];
+            let crossings = click.windows(2).filter(|w| w[0] * w[1] < 0.0).count();
+            if n % 4 == 0 {
+                assert!((crossings as i64 - 60).abs() <= 2);
+            } else {
+                assert!((crossings as i64 - 20).abs() <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn edge_trigger_pulses_once_at_the_crossing() {
+        let ramp: Vec<f64> = render(&mut Ramp::new(0.0, 1.0, 100), 200)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+        let out = render(
+            &mut EdgeTrigger::new(Aug::val(0.5), Ramp::new(0.0, 1.0, 100)),
+            200,
+        );
+
+        let pulses: Vec<usize> = (0..out.len()).filter(|i| out[*i] == (1.0, 1.0)).collect();
+        assert_eq!(pulses.len(), 1);
+        assert!(out.iter().all(|s| *s == (0.0, 0.0) || *s == (1.0, 1.0)));
+        let at = pulses[0];
+        assert!(ramp[at - 1] <= 0.5 && ramp[at] > 0.5);
+    }
+
+    #[test]
+    fn expscale_midpoint_is_the_geometric_mean() {
+        let scaled = |src: f64| {
+            render(
+                &mut ExpScale::new(Aug::val(100.0), Aug::val(10000.0), Aug::val(src)),
+                1,
+            )[0]
+            .0
+        };
+        assert!((scaled(0.0) - 100.0).abs() < 1e-9);
+        // not 5050, the arithmetic mean
+        assert!((scaled(0.5) - 1000.0).abs() < 1e-9);
+        assert!((scaled(1.0) - 10000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stepseq_cycles_through_values_on_triggers() {
+        // rises every 100 samples
+        let clock = || Pulse::new(Aug::val(0.0), Aug::val(441.0), Aug::val(0.5));
+        let trigger: Vec<f64> = render(&mut clock(), 1000).iter().map(|s| s.0).collect();
+        let values = vec![0.1, 0.2, 0.3, 0.4];
+        let out = render(&mut StepSeq::new(clock(), values.clone()), 1000);
+
+        let mut edge = RisingEdge::new();
+        let edges: Vec<usize> = (0..trigger.len())
+            .filter(|i| edge.detect(trigger[*i], 0.0))
+            .collect();
+        assert!(edges.len() >= 9);
+        for (k, at) in edges.iter().enumerate() {
+            let until = edges.get(k + 1).copied().unwrap_or(out.len());
+            // holds the step until the next trigger, wrapping after the fourth
+            assert!(out[*at..until].iter().all(|s| s.0 == values[k % 4]));
+        }
+    }
+
+    #[test]
+    fn avgmix_keeps_the_level_of_equal_sources() {
+        let sources = || (0..4).map(|_| Aug::val(0.8)).collect::<Vec<Aug>>();
+        let (l, r) = render(&mut AvgMix::new(sources()), 2)[1];
+        assert!((l - 0.8).abs() < 1e-9 && (r - 0.8).abs() < 1e-9);
+        // `+` sums them as they are
+        let (l, r) = render(&mut Add::new(sources()), 2)[1];
+        assert!((l - 3.2).abs() < 1e-9 && (r - 3.2).abs() < 1e-9);
+
+        assert_eq!(render(&mut AvgMix::new(Vec::new()), 2)[1], (0.0, 0.0));
+    }
+
+    #[test]
+    fn mono_halves_a_hard_panned_source_on_both_channels() {
+        let sine = || Sine::new(Aug::val(0.0), Aug::val(880.0));
+        // pan 1.0 silences the left channel
+        let panned = render(&mut Pan::new(Aug::val(1.0), sine()), 200);
+        assert!(panned.iter().all(|s| s.0 == 0.0));
+
+        let mono = render(&mut ToMono::new(Pan::new(Aug::val(1.0), sine())), 200);
+        for (m, p) in mono.iter().zip(panned.iter()) {
+            assert_eq!(m.0, m.1);
+            assert_eq!(m.0, (p.0 + p.1) / 2.0);
+        }
+        let peak = mono.iter().fold(0.0f64, |p, s| p.max(s.0.abs()));
+        assert!((peak - 0.5).abs() < 1e-3);
+
+        // dup copies the left channel
+        let dup = render(&mut Dup::new(Stereo::new(Aug::val(0.3), Aug::val(-0.7))), 4);
+        assert!(dup.iter().all(|s| *s == (0.3, 0.3)));
+    }
+
+    #[test]
+    fn up_arp_steps_through_a_triad_at_its_rate() {
+        let triad: Vec<Pitch> = ["c4", "e4", "g4"]
+            .iter()
+            .filter_map(|n| to_note(n))
+            .collect();
+        // eighth notes, which are 11025 samples at 120 bpm
+        let mut arp = Arp::new(
+            ArpMode::Up,
+            Aug::val(8.0),
+            triad,
+            Tuning::EqualTemperament(440.0),
+            3,
+        );
+        let out = render(&mut arp, 11025 * 6);
+
+        let changes: Vec<usize> = (1..out.len()).filter(|i| out[*i] != out[i - 1]).collect();
+        // rendered samples start from tick 1
+        let expected: Vec<usize> = (1..=6).map(|n| 11025 * n - 1).collect();
+        assert_eq!(changes, expected);
+        let steps: Vec<f64> = (0..6).map(|n| out[11025 * n + 5000].0).collect();
+        assert!(steps[0] < steps[1] && steps[1] < steps[2]);
+        assert_eq!(&steps[..3], &steps[3..]);
+        // a major third and a fifth above the root
+        assert!((steps[1] / steps[0] - 2.0f64.powf(4.0 / 12.0)).abs() < 1e-9);
+        assert!((steps[2] / steps[0] - 2.0f64.powf(7.0 / 12.0)).abs() < 1e-9);
+
+        assert!(arp
+            .0
+            .lock()
+            .unwrap()
+            .set_str("mode", "down".to_string())
+            .is_ok());
+        arp.reset();
+        let out = render(&mut arp, 11025 * 3);
+        assert!(out[5000].0 > out[11025 + 5000].0 && out[11025 + 5000].0 > out[22050 + 5000].0);
     }
 }
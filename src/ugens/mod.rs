@@ -1,3 +1,4 @@
+pub mod builder;
 pub mod core;
 pub mod fx;
 pub mod misc;
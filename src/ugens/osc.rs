@@ -1,24 +1,39 @@
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 
-use crate::musical_time::time::{Clock, Pos, Transport};
+use crate::musical_time::time::Transport;
 
 use super::core::{
-    Aug, Dump, Operate, OperateError, Osc, Proc, Signal, Slot, Table, UGen, UgNode, Value, Walk,
-    ADSR, UG,
+    Aug, Dump, Operate, OperateError, Osc, ParamInfo, ParamUnit, Proc, Signal, Slot, Table, UGen,
+    UgNode, Value, Walk, ADSR, UG,
 };
-use super::misc::{Clip, Gain, Offset};
+use super::misc::{Clip, ClipMode, Gain, Offset, RisingEdge};
 
+// oscillators with their `clamp_freq` slot set limit their frequency to 0..nyquist
+// instead of running backwards or aliasing
+fn resolve_freq(freq: &mut Aug, clamp: bool, transport: &Transport) -> f64 {
+    let f = freq.proc(transport).0;
+    if clamp {
+        f.max(0.0).min(transport.sample_rate as f64 / 2.0)
+    } else {
+        f
+    }
+}
+
+// plays `osc` through one cycle per trigger of `eg`, or over and over while `eg` is held
+// when `looping` is set
 pub struct OneshotOsc {
     pub osc: Aug,
     pub eg: Aug,
+    pub looping: bool,
 }
 
 impl OneshotOsc {
-    pub fn new(osc: Aug, eg: Aug) -> Aug {
+    pub fn new(osc: Aug, eg: Aug, looping: bool) -> Aug {
         Aug::new(UGen::new(UG::Osc(Box::new(OneshotOsc {
             osc: osc.clone(),
             eg: eg.clone(),
+            looping,
         }))))
     }
 }
@@ -35,6 +50,10 @@ impl Walk for OneshotOsc {
 }
 
 impl Dump for OneshotOsc {
+    fn name(&self) -> &'static str {
+        "oneshot"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -54,6 +73,11 @@ impl Dump for OneshotOsc {
                 None => Value::Ug(self.eg.clone()),
             },
         });
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "loop".to_string(),
+            value: Value::Bool(self.looping),
+        });
 
         UgNode::Ug("oneshot".to_string(), slots)
     }
@@ -69,6 +93,9 @@ impl Operate for OneshotOsc {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "loop" {
+            return Ok(self.looping.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -103,6 +130,16 @@ impl Operate for OneshotOsc {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
+            "loop" => match data.parse::<bool>() {
+                Ok(looping) => {
+                    self.looping = looping;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("oneshot/{}", pname),
+                    data.clone(),
+                )),
+            },
             "osc" => {
                 if let Ok(v) = data.parse::<f64>() {
                     self.osc = Aug::val(v);
@@ -135,23 +172,39 @@ impl Operate for OneshotOsc {
             "eg" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "loop" => self.looping = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["osc", "eg", "loop"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
 impl Proc for OneshotOsc {
     fn proc(&mut self, transport: &Transport) -> Signal {
         let _ = self.eg.proc(transport).0;
-        let mut state = ADSR::None;
-        let mut ph = 0.0;
-
-        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
-            if let UG::Eg(ref mut eg) = &mut self.eg.0.lock().unwrap().ug {
-                ph = osc.get_ph();
-                state = eg.get_state();
-            }
-        }
+        // each lock is released before taking the next, as osc and eg may be the same unit
+        let ph = match &self.osc.0.lock().unwrap().ug {
+            UG::Osc(osc) => Some(osc.get_ph()),
+            _ => None,
+        };
+        let state = match &self.eg.0.lock().unwrap().ug {
+            UG::Eg(eg) => Some(eg.get_state()),
+            _ => None,
+        };
+        let (ph, state) = match (ph, state) {
+            (Some(ph), Some(state)) => (ph, state),
+            _ => (0.0, ADSR::None),
+        };
 
         if let ADSR::Attack | ADSR::Decay | ADSR::Sustin = state {
             let v = self.osc.proc(transport).0;
@@ -159,8 +212,10 @@ impl Proc for OneshotOsc {
                 if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
                     osc.set_ph(0.0);
                 }
-                if let UG::Eg(ref mut eg) = &mut self.eg.0.lock().unwrap().ug {
-                    eg.set_state(ADSR::None, 0);
+                if !self.looping {
+                    if let UG::Eg(ref mut eg) = &mut self.eg.0.lock().unwrap().ug {
+                        eg.set_state(ADSR::None, 0);
+                    }
                 }
             };
             return (v, v);
@@ -209,19 +264,32 @@ impl Osc for OneshotOsc {
 
 pub struct Rand {
     rng: SmallRng,
+    seed: u64,
     freq: Aug,
     count: u64,
     v: f64,
 }
 
 impl Rand {
-    pub fn new(freq: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Osc(Box::new(Rand {
+    // without `seed` the unit's id is used, so that each `rand` yields its own sequence
+    pub fn new(freq: Aug, seed: Option<u64>) -> Aug {
+        let mut ugen = UGen::new(UG::Osc(Box::new(Rand {
             rng: SmallRng::seed_from_u64(0),
+            seed: 0,
             freq: freq,
             count: 0,
             v: 0.15,
-        }))))
+        })));
+        let seed = seed.unwrap_or(ugen.id as u64);
+        let _ = ugen.set_str("seed", seed.to_string());
+        Aug::new(ugen)
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = SmallRng::seed_from_u64(seed);
+        self.count = 0;
+        self.v = 0.15;
     }
 }
 
@@ -230,6 +298,10 @@ impl Walk for Rand {
 }
 
 impl Dump for Rand {
+    fn name(&self) -> &'static str {
+        "rand"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -241,6 +313,11 @@ impl Dump for Rand {
                 None => Value::Ug(self.freq.clone()),
             },
         });
+        slots.push(Slot {
+            ug: Aug::val(self.seed as f64),
+            name: "seed".to_string(),
+            value: Value::Symbol(self.seed.to_string()),
+        });
 
         UgNode::Ug("rand".to_string(), slots)
     }
@@ -254,6 +331,9 @@ impl Operate for Rand {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "seed" {
+            return Ok(self.seed.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -291,6 +371,16 @@ impl Operate for Rand {
                     Err(err)
                 }
             }
+            "seed" => {
+                if let Ok(v) = data.parse::<u64>() {
+                    self.reseed(v);
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("rand/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
             _ => Err(OperateError::ParamNotFound(format!("rand/{}", pname))),
         }
     }
@@ -300,6 +390,14 @@ impl Operate for Rand {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
 impl Proc for Rand {
@@ -312,6 +410,11 @@ impl Proc for Rand {
         }
         (self.v, self.v)
     }
+
+    fn reset(&mut self) {
+        let seed = self.seed;
+        self.reseed(seed);
+    }
 }
 
 impl Osc for Rand {
@@ -325,7 +428,7 @@ impl Osc for Rand {
         self.freq = u.clone()
     }
     fn get_freq(&self) -> Aug {
-        Aug::val(0.0)
+        self.freq.clone()
     }
 }
 
@@ -333,6 +436,7 @@ pub struct Sine {
     pub init_ph: Aug,
     pub ph: f64,
     pub freq: Aug,
+    pub clamp_freq: bool,
 }
 
 impl Sine {
@@ -341,6 +445,7 @@ impl Sine {
             init_ph: init_ph,
             ph: 0.0,
             freq: freq,
+            clamp_freq: false,
         }))))
     }
 }
@@ -357,6 +462,10 @@ impl Walk for Sine {
 }
 
 impl Dump for Sine {
+    fn name(&self) -> &'static str {
+        "sine"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -391,6 +500,9 @@ impl Operate for Sine {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -425,6 +537,16 @@ impl Operate for Sine {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("sine/{}", pname),
+                    data.clone(),
+                )),
+            },
             "init_ph" => {
                 if let Ok(v) = data.parse::<f64>() {
                     self.init_ph = Aug::val(v);
@@ -457,20 +579,45 @@ impl Operate for Sine {
             "freq" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "clamp_freq" => self.clamp_freq = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["init_ph", "freq", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "init_ph" => Some(ParamInfo::new(
+                0.0,
+                2.0 * std::f64::consts::PI,
+                0.0,
+                ParamUnit::Radian,
+            )),
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 440.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for Sine {
     fn proc(&mut self, transport: &Transport) -> Signal {
         let init_ph = self.init_ph.proc(&transport).0;
         let v = (init_ph + self.ph).sin();
-        let ph_diff = transport.sample_rate as f64 / std::f64::consts::PI;
-        self.ph += self.freq.proc(&transport).0 / ph_diff;
+        let ph_diff = transport.sample_rate as f64 / (2.0 * std::f64::consts::PI);
+        self.ph += resolve_freq(&mut self.freq, self.clamp_freq, transport) / ph_diff;
 
         (v, v)
     }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+    }
 }
 
 impl Osc for Sine {
@@ -495,6 +642,7 @@ pub struct Tri {
     pub init_ph: Aug,
     pub ph: f64,
     pub freq: Aug,
+    pub clamp_freq: bool,
 }
 
 impl Tri {
@@ -503,6 +651,7 @@ impl Tri {
             init_ph: init_ph,
             ph: 0.0,
             freq: freq,
+            clamp_freq: false,
         }))))
     }
 }
@@ -519,6 +668,10 @@ impl Walk for Tri {
 }
 
 impl Dump for Tri {
+    fn name(&self) -> &'static str {
+        "tri"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -553,6 +706,9 @@ impl Operate for Tri {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -587,6 +743,16 @@ impl Operate for Tri {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("tri/{}", pname),
+                    data.clone(),
+                )),
+            },
             "init_ph" => {
                 if let Ok(v) = data.parse::<f64>() {
                     self.init_ph = Aug::val(v);
@@ -619,17 +785,33 @@ impl Operate for Tri {
             "freq" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "clamp_freq" => self.clamp_freq = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["init_ph", "freq", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "init_ph" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Cycle)),
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 440.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for Tri {
     fn proc(&mut self, transport: &Transport) -> Signal {
         let ph = self.init_ph.proc(&transport).0 + self.ph;
 
-        let ph_diff = transport.sample_rate as f64 * 2.0;
-        self.ph += self.freq.proc(&transport).0 / ph_diff;
+        let ph_diff = transport.sample_rate as f64;
+        self.ph += resolve_freq(&mut self.freq, self.clamp_freq, transport) / ph_diff;
 
         let x = ph % 1.0;
         let v;
@@ -642,6 +824,10 @@ impl Proc for Tri {
         }
         (v, v)
     }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+    }
 }
 
 impl Osc for Tri {
@@ -666,6 +852,7 @@ pub struct Saw {
     pub init_ph: Aug,
     pub ph: f64,
     pub freq: Aug,
+    pub clamp_freq: bool,
 }
 
 impl Saw {
@@ -674,6 +861,7 @@ impl Saw {
             init_ph: init_ph,
             ph: 0.0,
             freq: freq,
+            clamp_freq: false,
         }))))
     }
 }
@@ -690,6 +878,10 @@ impl Walk for Saw {
 }
 
 impl Dump for Saw {
+    fn name(&self) -> &'static str {
+        "saw"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -724,6 +916,9 @@ impl Operate for Saw {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -758,6 +953,16 @@ impl Operate for Saw {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("saw/{}", pname),
+                    data.clone(),
+                )),
+            },
             "init_ph" => {
                 if let Ok(v) = data.parse::<f64>() {
                     self.init_ph = Aug::val(v);
@@ -790,16 +995,32 @@ impl Operate for Saw {
             "freq" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "clamp_freq" => self.clamp_freq = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["init_ph", "freq", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "init_ph" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Cycle)),
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 440.0, ParamUnit::Hz)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for Saw {
     fn proc(&mut self, transport: &Transport) -> Signal {
         let ph = self.init_ph.proc(&transport).0 + self.ph;
-        let ph_diff = transport.sample_rate as f64 * 2.0;
-        self.ph += self.freq.proc(&transport).0 / ph_diff;
+        let ph_diff = transport.sample_rate as f64;
+        self.ph += resolve_freq(&mut self.freq, self.clamp_freq, transport) / ph_diff;
 
         let x = ph % 1.0;
         let v;
@@ -810,6 +1031,10 @@ impl Proc for Saw {
         }
         (v, v)
     }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+    }
 }
 
 impl Osc for Saw {
@@ -834,6 +1059,7 @@ pub struct Pulse {
     pub init_ph: Aug,
     pub ph: f64,
     pub freq: Aug,
+    pub clamp_freq: bool,
     pub duty: Aug,
 }
 
@@ -843,6 +1069,7 @@ impl Pulse {
             init_ph: init_ph,
             ph: 0.0,
             freq: freq,
+            clamp_freq: false,
             duty: duty,
         }))))
     }
@@ -863,6 +1090,10 @@ impl Walk for Pulse {
 }
 
 impl Dump for Pulse {
+    fn name(&self) -> &'static str {
+        "pulse"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -906,6 +1137,9 @@ impl Operate for Pulse {
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
@@ -944,6 +1178,16 @@ impl Operate for Pulse {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("pulse/{}", pname),
+                    data.clone(),
+                )),
+            },
             "init_ph" => {
                 if let Ok(v) = data.parse::<f64>() {
                     self.init_ph = Aug::val(v);
@@ -989,17 +1233,34 @@ impl Operate for Pulse {
             "duty" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "clamp_freq" => self.clamp_freq = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["init_ph", "freq", "duty", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "init_ph" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Cycle)),
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 440.0, ParamUnit::Hz)),
+            "duty" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for Pulse {
     fn proc(&mut self, transport: &Transport) -> Signal {
         let ph = self.init_ph.proc(&transport).0 + self.ph;
         let duty = self.duty.proc(&transport).0;
-        let ph_diff = transport.sample_rate as f64 * 2.0;
-        self.ph += self.freq.proc(&transport).0 / ph_diff;
+        let ph_diff = transport.sample_rate as f64;
+        self.ph += resolve_freq(&mut self.freq, self.clamp_freq, transport) / ph_diff;
 
         let x = ph % 1.0;
         let v;
@@ -1010,6 +1271,10 @@ impl Proc for Pulse {
         }
         (v, v)
     }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+    }
 }
 
 impl Osc for Pulse {
@@ -1030,72 +1295,114 @@ impl Osc for Pulse {
     }
 }
 
-pub struct Phase {
-    pub root: Aug,
-    pub osc: Aug,
+// pulse with PolyBLEP corrected edges to reduce aliasing
+pub struct BlPulse {
+    pub init_ph: Aug,
+    pub ph: f64,
+    pub freq: Aug,
+    pub clamp_freq: bool,
+    pub duty: Aug,
 }
 
-impl Phase {
-    pub fn new(u: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Osc(Box::new(Phase {
-            root: Phase::make_root(u.clone()),
-            osc: u.clone(),
+impl BlPulse {
+    pub fn new(init_ph: Aug, freq: Aug, duty: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(BlPulse {
+            init_ph,
+            ph: 0.0,
+            freq,
+            clamp_freq: false,
+            duty,
         }))))
     }
+}
 
-    fn make_root(u: Aug) -> Aug {
-        let offset_val = Aug::val(1.0);
-        let gain = Aug::val(0.5);
-        let clip_min = Aug::val(-1.0);
-        let clip_max = Aug::val(1.0);
-        Offset::new(
-            offset_val,
-            Gain::new(gain, Clip::new(clip_min, clip_max, u.clone())),
-        )
+// residual of a band-limited step at phase t where the phase advances dt per sample
+fn poly_blep(t: f64, dt: f64) -> f64 {
+    if dt <= 0.0 {
+        0.0
+    } else if t < dt {
+        let t = t / dt;
+        t + t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + t + t + 1.0
+    } else {
+        0.0
     }
 }
 
-impl Walk for Phase {
+impl Walk for BlPulse {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        if f(&self.osc) {
-            self.osc.walk(f);
+        if f(&self.init_ph) {
+            self.init_ph.walk(f);
+        }
+        if f(&self.freq) {
+            self.freq.walk(f);
+        }
+        if f(&self.duty) {
+            self.duty.walk(f);
         }
     }
 }
 
-impl Dump for Phase {
+impl Dump for BlPulse {
+    fn name(&self) -> &'static str {
+        "blpulse"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
         slots.push(Slot {
-            ug: self.osc.clone(),
-            name: "osc".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.osc) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.osc.clone()),
+            ug: self.init_ph.clone(),
+            name: "init_ph".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.init_ph) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.init_ph.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.freq.clone(),
+            name: "freq".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.freq) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.freq.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.duty.clone(),
+            name: "duty".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.duty) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.duty.clone()),
             },
         });
 
-        UgNode::Ug("phase".to_string(), slots)
+        UgNode::Ug("blpulse".to_string(), slots)
     }
 }
 
-impl Operate for Phase {
+impl Operate for BlPulse {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
         match pname {
-            "osc" => Ok(self.osc.clone()),
-            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+            "init_ph" => Ok(self.init_ph.clone()),
+            "freq" => Ok(self.freq.clone()),
+            "duty" => Ok(self.duty.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("blpulse/{}", pname))),
         }
     }
 
     fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
         match self.get(pname) {
             Ok(aug) => {
                 if let Some(v) = aug.to_val() {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "phase/{}",
+                        "blpulse/{}",
                         pname
                     )))
                 }
@@ -1106,12 +1413,19 @@ impl Operate for Phase {
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
-            "osc" => {
-                self.osc = ug;
-                self.root = Phase::make_root(self.osc.clone());
+            "init_ph" => {
+                self.init_ph = ug;
                 Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+            "freq" => {
+                self.freq = ug;
+                Ok(true)
+            }
+            "duty" => {
+                self.duty = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("blpulse/{}", pname))),
         }
     }
 
@@ -1120,154 +1434,1403 @@ impl Operate for Phase {
         data.retain(|c| c != '\n' && c != ' ');
 
         match pname {
-            "osc" => {
-                if let Ok(v) = data.parse::<f64>() {
-                    self.osc = Aug::val(v);
-                    self.root = Phase::make_root(self.osc.clone());
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
                     Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("blpulse/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "init_ph" | "freq" | "duty" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
                 } else {
                     let err =
-                        OperateError::CannotParseNumber(format!("phase/{}", pname), data.clone());
+                        OperateError::CannotParseNumber(format!("blpulse/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("blpulse/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "vol" => {
-                let _ = self.set(pname, Aug::val(0.0));
-            }
-            name if name.starts_with("src") => {
+            "init_ph" | "freq" | "duty" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
+            "clamp_freq" => self.clamp_freq = false,
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["init_ph", "freq", "duty", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "init_ph" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Cycle)),
+            "freq" => Some(ParamInfo::new(20.0, 20000.0, 440.0, ParamUnit::Hz)),
+            "duty" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
 }
 
-impl Proc for Phase {
+impl Proc for BlPulse {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        self.root.proc(transport)
+        let ph = self.init_ph.proc(transport).0 + self.ph;
+        let duty = self.duty.proc(transport).0.clamp(0.0, 1.0);
+        let ph_diff = transport.sample_rate as f64;
+        let dt = resolve_freq(&mut self.freq, self.clamp_freq, transport) / ph_diff;
+        self.ph += dt;
+
+        let x = ph.rem_euclid(1.0);
+        let mut v = if x < duty { 1.0 } else { -1.0 };
+        let dt = dt.abs().min(0.5);
+        v += poly_blep(x, dt);
+        v -= poly_blep((x - duty).rem_euclid(1.0), dt);
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
     }
 }
 
-impl Osc for Phase {
+impl Osc for BlPulse {
     fn set_ph(&mut self, ph: f64) {
-        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
-            osc.set_ph(ph);
-        }
+        self.ph = ph;
     }
 
     fn get_ph(&self) -> f64 {
-        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
-            osc.get_ph()
-        } else {
-            0.0
-        }
+        self.ph
     }
 
-    fn set_freq(&mut self, freq: Aug) {
-        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
-            osc.set_freq(freq);
-        }
+    fn set_freq(&mut self, u: Aug) {
+        self.freq = u;
     }
 
     fn get_freq(&self) -> Aug {
-        Aug::val(0.0)
+        self.freq.clone()
     }
 }
 
-pub struct WaveTable {
-    pub table: Aug,
-    pub ph: Aug,
+pub enum Waveform {
+    Sine,
+    Tri,
+    Saw,
+    Pulse,
 }
 
-impl WaveTable {
-    pub fn from_osc(osc: Aug, ph: Aug, transport: &Transport) -> Aug {
-        let mut table = Vec::new();
-        let table_len = 256;
-        let mut transport = Transport {
-            sample_rate: (table_len as f64 / 2.0) as u32,
-            tick: 0,
-            bpm: transport.bpm,
-            measure: transport.measure.clone(),
-            pos: Pos {
-                bar: 0,
-                beat: 0,
-                pos: 0.0,
-            },
-        };
-        for _i in 0..table_len {
-            let v = osc.0.lock().unwrap().proc(&transport).0;
-            table.push(v);
-            transport.inc();
+impl Waveform {
+    pub fn parse(s: &str) -> Option<Waveform> {
+        match s {
+            "sine" => Some(Waveform::Sine),
+            "tri" => Some(Waveform::Tri),
+            "saw" => Some(Waveform::Saw),
+            "pulse" => Some(Waveform::Pulse),
+            _ => None,
         }
-        let table = Aug::new(UGen::new(UG::Tab(Table::new(table))));
-        Aug::new(UGen::new(UG::Osc(Box::new(WaveTable {
-            table: table,
-            ph: ph,
-        }))))
     }
 
-    pub fn from_table(table: Aug, ph: Aug) -> Aug {
-        Aug::new(UGen::new(UG::Osc(Box::new(WaveTable {
-            table: table,
-            ph: ph,
-        }))))
+    pub fn to_str(&self) -> &str {
+        match self {
+            Waveform::Sine => "sine",
+            Waveform::Tri => "tri",
+            Waveform::Saw => "saw",
+            Waveform::Pulse => "pulse",
+        }
     }
 }
 
-fn linear_interpol(v1: f64, v2: f64, r: f64) -> f64 {
-    let r = r % 1.0;
-    v1 * r + v2 * (1.0 - r)
+// builds the oscillator of `kind`, so that waveforms can be chosen at runtime.
+// pulses have a duty of 0.5
+pub fn make_osc(kind: Waveform, init_ph: Aug, freq: Aug) -> Aug {
+    match kind {
+        Waveform::Sine => Sine::new(init_ph, freq),
+        Waveform::Tri => Tri::new(init_ph, freq),
+        Waveform::Saw => Saw::new(init_ph, freq),
+        Waveform::Pulse => Pulse::new(init_ph, freq, Aug::val(0.5)),
+    }
 }
 
-impl Walk for WaveTable {
-    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
-        if f(&self.table) {
-            self.table.walk(f);
-        }
-        if f(&self.ph) {
-            self.ph.walk(f);
+pub enum LfoShape {
+    Sine,
+    Tri,
+    Saw,
+    Square,
+}
+
+impl LfoShape {
+    pub fn parse(s: &str) -> Option<LfoShape> {
+        match s {
+            "sine" => Some(LfoShape::Sine),
+            "tri" => Some(LfoShape::Tri),
+            "saw" => Some(LfoShape::Saw),
+            "square" => Some(LfoShape::Square),
+            _ => None,
         }
     }
-}
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            LfoShape::Sine => "sine",
+            LfoShape::Tri => "tri",
+            LfoShape::Saw => "saw",
+            LfoShape::Square => "square",
+        }
+    }
+
+    // gives a value in [-1, 1] at phase x in [0, 1)
+    fn value(&self, x: f64) -> f64 {
+        match self {
+            LfoShape::Sine => (2.0 * std::f64::consts::PI * x).sin(),
+            LfoShape::Tri => {
+                if x < 0.25 {
+                    4.0 * x
+                } else if x < 0.75 {
+                    2.0 - 4.0 * x
+                } else {
+                    4.0 * x - 4.0
+                }
+            }
+            LfoShape::Saw => {
+                if x < 0.5 {
+                    2.0 * x
+                } else {
+                    2.0 * x - 2.0
+                }
+            }
+            LfoShape::Square => {
+                if x < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+// low frequency oscillator scaled into [min, max]; unlike other oscillators `freq` is in Hz as is
+pub struct Lfo {
+    pub freq: Aug,
+    pub clamp_freq: bool,
+    pub shape: LfoShape,
+    pub min: Aug,
+    pub max: Aug,
+    pub ph: f64,
+}
+
+impl Lfo {
+    pub fn new(freq: Aug, shape: LfoShape, min: Aug, max: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(Lfo {
+            freq,
+            clamp_freq: false,
+            shape,
+            min,
+            max,
+            ph: 0.0,
+        }))))
+    }
+}
+
+impl Walk for Lfo {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.freq) {
+            self.freq.walk(f);
+        }
+        if f(&self.min) {
+            self.min.walk(f);
+        }
+        if f(&self.max) {
+            self.max.walk(f);
+        }
+    }
+}
+
+impl Dump for Lfo {
+    fn name(&self) -> &'static str {
+        "lfo"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.freq.clone(),
+            name: "freq".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.freq) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.freq.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: Aug::val(0.0),
+            name: "shape".to_string(),
+            value: Value::Symbol(self.shape.to_str().to_string()),
+        });
+        slots.push(Slot {
+            ug: self.min.clone(),
+            name: "min".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.min) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.min.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.max.clone(),
+            name: "max".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.max) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.max.clone()),
+            },
+        });
+
+        UgNode::Ug("lfo".to_string(), slots)
+    }
+}
+
+impl Operate for Lfo {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "freq" => Ok(self.freq.clone()),
+            "min" => Ok(self.min.clone()),
+            "max" => Ok(self.max.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("lfo/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        if pname == "clamp_freq" {
+            return Ok(self.clamp_freq.to_string());
+        }
+        if pname == "shape" {
+            return Ok(self.shape.to_str().to_string());
+        }
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "lfo/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "freq" => {
+                self.freq = ug;
+                Ok(true)
+            }
+            "min" => {
+                self.min = ug;
+                Ok(true)
+            }
+            "max" => {
+                self.max = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("lfo/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "clamp_freq" => match data.parse::<bool>() {
+                Ok(clamp) => {
+                    self.clamp_freq = clamp;
+                    Ok(true)
+                }
+                Err(_) => Err(OperateError::CannotParseSymbol(
+                    format!("lfo/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "shape" => match LfoShape::parse(&data) {
+                Some(shape) => {
+                    self.shape = shape;
+                    Ok(true)
+                }
+                None => Err(OperateError::CannotParseSymbol(
+                    format!("lfo/{}", pname),
+                    data.clone(),
+                )),
+            },
+            "freq" | "min" | "max" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("lfo/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("lfo/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "shape" => self.shape = LfoShape::Sine,
+            "freq" | "min" | "max" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            "clamp_freq" => self.clamp_freq = false,
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["freq", "shape", "min", "max", "clamp_freq"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "freq" => Some(ParamInfo::new(0.01, 20.0, 1.0, ParamUnit::Hz)),
+            "min" => Some(ParamInfo::new(-1.0, 1.0, -1.0, ParamUnit::Bipolar)),
+            "max" => Some(ParamInfo::new(-1.0, 1.0, 1.0, ParamUnit::Bipolar)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Lfo {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let min = self.min.proc(transport).0;
+        let max = self.max.proc(transport).0;
+        let v = self.shape.value(self.ph.rem_euclid(1.0));
+        self.ph +=
+            resolve_freq(&mut self.freq, self.clamp_freq, transport) / transport.sample_rate as f64;
+
+        let v = min + (v + 1.0) / 2.0 * (max - min);
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.ph = 0.0;
+    }
+}
+
+impl Osc for Lfo {
+    fn set_ph(&mut self, ph: f64) {
+        self.ph = ph;
+    }
+
+    fn get_ph(&self) -> f64 {
+        self.ph
+    }
+
+    fn set_freq(&mut self, u: Aug) {
+        self.freq = u;
+    }
+
+    fn get_freq(&self) -> Aug {
+        self.freq.clone()
+    }
+}
+
+pub struct Phase {
+    pub root: Aug,
+    pub osc: Aug,
+}
+
+impl Phase {
+    pub fn new(u: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(Phase {
+            root: Phase::make_root(u.clone()),
+            osc: u.clone(),
+        }))))
+    }
+
+    fn make_root(u: Aug) -> Aug {
+        let offset_val = Aug::val(1.0);
+        let gain = Aug::val(0.5);
+        let clip_min = Aug::val(-1.0);
+        let clip_max = Aug::val(1.0);
+        Offset::new(
+            offset_val,
+            Gain::new(
+                gain,
+                Clip::new(clip_min, clip_max, ClipMode::Hard, u.clone()),
+            ),
+        )
+    }
+}
+
+impl Walk for Phase {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.osc) {
+            self.osc.walk(f);
+        }
+    }
+}
+
+impl Dump for Phase {
+    fn name(&self) -> &'static str {
+        "phase"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.osc.clone(),
+            name: "osc".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.osc) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.osc.clone()),
+            },
+        });
+
+        UgNode::Ug("phase".to_string(), slots)
+    }
+}
+
+impl Operate for Phase {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "osc" => Ok(self.osc.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "phase/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "osc" => {
+                self.osc = ug;
+                self.root = Phase::make_root(self.osc.clone());
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "osc" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.osc = Aug::val(v);
+                    self.root = Phase::make_root(self.osc.clone());
+                    Ok(true)
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("phase/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("phase/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "osc" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["osc"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for Phase {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        self.root.proc(transport)
+    }
+}
+
+impl Osc for Phase {
+    fn set_ph(&mut self, ph: f64) {
+        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
+            osc.set_ph(ph);
+        }
+    }
+
+    fn get_ph(&self) -> f64 {
+        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
+            osc.get_ph()
+        } else {
+            0.0
+        }
+    }
+
+    fn set_freq(&mut self, freq: Aug) {
+        if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
+            osc.set_freq(freq);
+        }
+    }
+
+    fn get_freq(&self) -> Aug {
+        if let UG::Osc(ref osc) = &self.osc.0.lock().unwrap().ug {
+            osc.get_freq()
+        } else {
+            Aug::val(0.0)
+        }
+    }
+}
+
+pub struct WaveTable {
+    pub table: Aug,
+    pub ph: Aug,
+}
+
+// points of a table rendered from an oscillator when no length is given
+pub const WAVETABLE_DEFAULT_LEN: usize = 256;
+
+impl WaveTable {
+    // renders one cycle of `osc` into `len` points. larger tables follow the waveform more
+    // closely between points, which matters for low pitches
+    pub fn from_osc(osc: Aug, ph: Aug, len: usize, transport: &Transport) -> Aug {
+        let table = Aug::new(UGen::new(UG::Tab(osc.to_table(len, transport))));
+        Aug::new(UGen::new(UG::Osc(Box::new(WaveTable {
+            table: table,
+            ph: ph,
+        }))))
+    }
+
+    pub fn from_table(table: Aug, ph: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(WaveTable {
+            table: table,
+            ph: ph,
+        }))))
+    }
+}
+
+fn linear_interpol(v1: f64, v2: f64, r: f64) -> f64 {
+    let r = r % 1.0;
+    v1 * r + v2 * (1.0 - r)
+}
+
+impl Walk for WaveTable {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.table) {
+            self.table.walk(f);
+        }
+        if f(&self.ph) {
+            self.ph.walk(f);
+        }
+    }
+}
 
 impl Dump for WaveTable {
+    fn name(&self) -> &'static str {
+        "wavetable"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.table.clone(),
+            name: "table".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.table) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.table.clone()),
+            },
+        });
+
+        slots.push(Slot {
+            ug: self.ph.clone(),
+            name: "ph".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.ph) {
+                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                None => Value::Ug(self.ph.clone()),
+            },
+        });
+
+        UgNode::Ug("wavetable".to_string(), slots)
+    }
+}
+
+impl Operate for WaveTable {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "table" => Ok(self.table.clone()),
+            "ph" => Ok(self.ph.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "wavetable/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "table" => {
+                self.table = ug;
+                Ok(true)
+            }
+            "ph" => {
+                self.ph = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        match pname {
+            "table" => {
+                let mut data = data.clone();
+                data.retain(|c| c != '\n');
+
+                if let Some(data) = Table::parse_str(data.clone()) {
+                    self.table = Aug::new(UGen::new(UG::Tab(Table::new(data))));
+                    Ok(true)
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("wavetable/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            "ph" => {
+                let mut data = data.clone();
+                data.retain(|c| c != '\n' && c != ' ');
+
+                if let Ok(v) = data.parse::<f64>() {
+                    self.ph = Aug::val(v);
+                    Ok(true)
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("wavetable/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "table" => {
+                let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 0.0]))));
+                let _ = self.set(pname, table);
+            }
+            "ph" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["table", "ph"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
+}
+
+impl Proc for WaveTable {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        if let UG::Tab(table) = &self.table.0.lock().unwrap().ug {
+            let table = table.0.lock().unwrap();
+            let len = table.len() as f64;
+            let p = self.ph.proc(&transport).0 * len;
+            let pos1 = (p.floor() % len) as usize;
+            let pos2 = (p.ceil() % len) as usize;
+            let v = linear_interpol(table[pos1], table[pos2], p.fract());
+            (v, v)
+        } else {
+            panic!("it's not a table!!");
+        }
+    }
+}
+
+impl Osc for WaveTable {
+    fn set_ph(&mut self, ph: f64) {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.set_ph(ph);
+        }
+    }
+
+    fn get_ph(&self) -> f64 {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.get_ph()
+        } else {
+            0.0
+        }
+    }
+
+    fn set_freq(&mut self, freq: Aug) {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.set_freq(freq);
+        }
+    }
+
+    fn get_freq(&self) -> Aug {
+        if let UG::Osc(ref osc) = &self.ph.0.lock().unwrap().ug {
+            osc.get_freq()
+        } else {
+            Aug::val(0.0)
+        }
+    }
+}
+
+const MAX_GRAINS: usize = 64;
+
+struct Grain {
+    start: f64,
+    age: usize,
+    len: usize,
+}
+
+// the sweep starts this many times above `pitch` and falls with this time constant
+const KICK_SWEEP_RATIO: f64 = 8.0;
+const KICK_SWEEP_TIME: f64 = 0.03;
+// length of the transient `click` adds at the onset
+const KICK_CLICK_TIME: f64 = 0.002;
+
+// a sine falling from a high pitch down to `pitch` (in Hz) on each rising edge of `trigger`,
+// fading out in `decay` seconds. e.g. triggered by the envelope of a `seq` playing `k` notes
+pub struct KickDrum {
+    pub trigger: Aug,
+    pub pitch: Aug,
+    pub decay: Aug,
+    pub click: Aug,
+    edge: RisingEdge,
+    ph: f64,
+    elapsed: Option<u64>,
+}
+
+impl KickDrum {
+    pub fn new(trigger: Aug, pitch: Aug, decay: Aug, click: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(KickDrum {
+            trigger,
+            pitch,
+            decay,
+            click,
+            edge: RisingEdge::new(),
+            ph: 0.0,
+            elapsed: None,
+        }))))
+    }
+}
+
+impl Walk for KickDrum {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.trigger) {
+            self.trigger.walk(f);
+        }
+        if f(&self.pitch) {
+            self.pitch.walk(f);
+        }
+        if f(&self.decay) {
+            self.decay.walk(f);
+        }
+        if f(&self.click) {
+            self.click.walk(f);
+        }
+    }
+}
+
+impl Dump for KickDrum {
+    fn name(&self) -> &'static str {
+        "kick"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.trigger.clone(),
+            name: "trigger".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.trigger) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.trigger.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.pitch.clone(),
+            name: "pitch".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.pitch) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.pitch.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.decay.clone(),
+            name: "decay".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.decay) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.decay.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.click.clone(),
+            name: "click".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.click) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.click.clone()),
+            },
+        });
+
+        UgNode::Ug("kick".to_string(), slots)
+    }
+}
+
+impl Operate for KickDrum {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "trigger" => Ok(self.trigger.clone()),
+            "pitch" => Ok(self.pitch.clone()),
+            "decay" => Ok(self.decay.clone()),
+            "click" => Ok(self.click.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("kick/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "kick/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "trigger" => {
+                self.trigger = ug;
+                Ok(true)
+            }
+            "pitch" => {
+                self.pitch = ug;
+                Ok(true)
+            }
+            "decay" => {
+                self.decay = ug;
+                Ok(true)
+            }
+            "click" => {
+                self.click = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("kick/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
+
+        match pname {
+            "trigger" | "pitch" | "decay" | "click" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err =
+                        OperateError::CannotParseNumber(format!("kick/{}", pname), data.clone());
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("kick/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "trigger" | "pitch" | "decay" | "click" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["trigger", "pitch", "decay", "click"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "pitch" => Some(ParamInfo::new(20.0, 200.0, 50.0, ParamUnit::Hz)),
+            "decay" => Some(ParamInfo::new(0.01, 2.0, 0.3, ParamUnit::Seconds)),
+            "click" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for KickDrum {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let trigger = self.trigger.proc(transport).0;
+        let pitch = self.pitch.proc(transport).0;
+        let decay = self.decay.proc(transport).0;
+        let click = self.click.proc(transport).0;
+
+        if self.edge.detect(trigger, 0.0) {
+            self.elapsed = Some(0);
+            self.ph = 0.0;
+        }
+        let elapsed = match self.elapsed {
+            Some(n) => n,
+            None => return (0.0, 0.0),
+        };
+
+        let t = elapsed as f64 / transport.sample_rate as f64;
+        if t >= decay {
+            self.elapsed = None;
+            return (0.0, 0.0);
+        }
+        let freq = pitch * (1.0 + (KICK_SWEEP_RATIO - 1.0) * (-t / KICK_SWEEP_TIME).exp());
+        // about -60 dB at `decay`
+        let amp = (-6.9 * t / decay).exp();
+        let v = amp * self.ph.sin() + click * (-t / KICK_CLICK_TIME).exp();
+        self.ph += 2.0 * std::f64::consts::PI * freq / transport.sample_rate as f64;
+        self.elapsed = Some(elapsed + 1);
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.edge.reset();
+        self.ph = 0.0;
+        self.elapsed = None;
+    }
+}
+
+// plays short Hann-windowed grains from `buffer` around `position` (0.0 to 1.0 of the buffer).
+// `density` grains start per second, each randomly offset from `position` by up to `spread`
+pub struct Granular {
+    pub buffer: Aug,
+    pub position: Aug,
+    pub grainsize: Aug,
+    pub density: Aug,
+    pub spread: Aug,
+    rng: SmallRng,
+    grains: Vec<Grain>,
+    next: f64,
+}
+
+impl Granular {
+    pub fn new(buffer: Aug, position: Aug, grainsize: Aug, density: Aug, spread: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Proc(Box::new(Granular {
+            buffer,
+            position,
+            grainsize,
+            density,
+            spread,
+            rng: SmallRng::seed_from_u64(0),
+            grains: Vec::new(),
+            next: 0.0,
+        }))))
+    }
+}
+
+impl Walk for Granular {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.buffer) {
+            self.buffer.walk(f);
+        }
+        if f(&self.position) {
+            self.position.walk(f);
+        }
+        if f(&self.grainsize) {
+            self.grainsize.walk(f);
+        }
+        if f(&self.density) {
+            self.density.walk(f);
+        }
+        if f(&self.spread) {
+            self.spread.walk(f);
+        }
+    }
+}
+
+impl Dump for Granular {
+    fn name(&self) -> &'static str {
+        "granular"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.buffer.clone(),
+            name: "buffer".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.buffer) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.buffer.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.position.clone(),
+            name: "position".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.position) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.position.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.grainsize.clone(),
+            name: "grainsize".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.grainsize) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.grainsize.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.density.clone(),
+            name: "density".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.density) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.density.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.spread.clone(),
+            name: "spread".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.spread) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.spread.clone()),
+            },
+        });
+
+        UgNode::Ug("granular".to_string(), slots)
+    }
+}
+
+impl Operate for Granular {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "buffer" => Ok(self.buffer.clone()),
+            "position" => Ok(self.position.clone()),
+            "grainsize" => Ok(self.grainsize.clone()),
+            "density" => Ok(self.density.clone()),
+            "spread" => Ok(self.spread.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("granular/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "granular/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "buffer" => {
+                self.buffer = ug;
+                Ok(true)
+            }
+            "position" => {
+                self.position = ug;
+                Ok(true)
+            }
+            "grainsize" => {
+                self.grainsize = ug;
+                Ok(true)
+            }
+            "density" => {
+                self.density = ug;
+                Ok(true)
+            }
+            "spread" => {
+                self.spread = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("granular/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n');
+
+        if pname == "buffer" {
+            return match Table::parse_str(data.clone()) {
+                Some(data) => self.set(pname, Aug::new(UGen::new(UG::Tab(Table::new(data))))),
+                None => Err(OperateError::CannotParseNumber(
+                    format!("granular/{}", pname),
+                    data,
+                )),
+            };
+        }
+        data.retain(|c| c != ' ');
+
+        match pname {
+            "position" | "grainsize" | "density" | "spread" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("granular/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("granular/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "buffer" => {
+                let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 0.0]))));
+                let _ = self.set(pname, table);
+            }
+            "position" | "grainsize" | "density" | "spread" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["buffer", "position", "grainsize", "density", "spread"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "position" => Some(ParamInfo::new(0.0, 1.0, 0.5, ParamUnit::Normalized)),
+            "grainsize" => Some(ParamInfo::new(0.001, 1.0, 0.05, ParamUnit::Seconds)),
+            "density" => Some(ParamInfo::new(0.0, 200.0, 20.0, ParamUnit::Hz)),
+            "spread" => Some(ParamInfo::new(0.0, 1.0, 0.1, ParamUnit::Normalized)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for Granular {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let position = self.position.proc(transport).0;
+        let grainsize = self.grainsize.proc(transport).0;
+        let density = self.density.proc(transport).0;
+        let spread = self.spread.proc(transport).0;
+        let sample_rate = transport.sample_rate as f64;
+
+        let buffer = self.buffer.0.lock().unwrap();
+        let table = match &buffer.ug {
+            UG::Tab(table) => table.0.lock().unwrap(),
+            _ => return (0.0, 0.0),
+        };
+        if table.is_empty() {
+            return (0.0, 0.0);
+        }
+        let len = table.len() as f64;
+
+        if density > 0.0 {
+            self.next -= 1.0;
+            if self.next <= 0.0 {
+                self.next += sample_rate / density;
+                if self.grains.len() < MAX_GRAINS {
+                    let offset = spread * (self.rng.gen::<f64>() * 2.0 - 1.0);
+                    let pos = (position + offset).clamp(0.0, 1.0);
+                    self.grains.push(Grain {
+                        start: pos * len,
+                        age: 0,
+                        len: ((grainsize * sample_rate) as usize).max(1),
+                    });
+                }
+            }
+        } else {
+            self.next = 0.0;
+        }
+
+        let mut v = 0.0;
+        for g in self.grains.iter_mut() {
+            let w = 0.5 - 0.5 * (2.0 * std::f64::consts::PI * g.age as f64 / g.len as f64).cos();
+            let p = g.start + g.age as f64;
+            let v1 = table[(p.floor() % len) as usize];
+            let v2 = table[(p.ceil() % len) as usize];
+            v += (v1 + (v2 - v1) * p.fract()) * w;
+            g.age += 1;
+        }
+        self.grains.retain(|g| g.age < g.len);
+
+        // keeps the level around the source's while grains overlap
+        let overlap = (density * grainsize).max(1.0);
+        let v = v / overlap;
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.grains.clear();
+        self.next = 0.0;
+    }
+}
+
+// crossfades between neighbouring tables picked by `pos`
+pub struct MorphTable {
+    pub tables: Vec<Aug>,
+    pub pos: Aug,
+    pub ph: Aug,
+}
+
+impl MorphTable {
+    pub fn new(tables: Vec<Aug>, pos: Aug, ph: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(MorphTable { tables, pos, ph }))))
+    }
+
+    fn table_index(pname: &str) -> Option<usize> {
+        pname
+            .strip_prefix("table")
+            .and_then(|idx| idx.parse::<usize>().ok())
+    }
+
+    fn read(table: &Aug, ph: f64) -> f64 {
+        if let UG::Tab(table) = &table.0.lock().unwrap().ug {
+            let table = table.0.lock().unwrap();
+            let len = table.len() as f64;
+            let p = ph * len;
+            let v1 = table[(p.floor() % len) as usize];
+            let v2 = table[(p.ceil() % len) as usize];
+            v1 + (v2 - v1) * p.fract()
+        } else {
+            panic!("it's not a table!!");
+        }
+    }
+}
+
+impl Walk for MorphTable {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.pos) {
+            self.pos.walk(f);
+        }
+        if f(&self.ph) {
+            self.ph.walk(f);
+        }
+        for t in self.tables.iter() {
+            if f(t) {
+                t.walk(f);
+            }
+        }
+    }
+}
+
+impl Dump for MorphTable {
+    fn name(&self) -> &'static str {
+        "morph"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
+        let mut values = Vec::new();
 
         slots.push(Slot {
-            ug: self.table.clone(),
-            name: "table".to_string(),
-            value: match shared_ug.iter().position(|e| *e == self.table) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
-                None => Value::Ug(self.table.clone()),
+            ug: self.pos.clone(),
+            name: "pos".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.pos) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.pos.clone()),
             },
         });
-
         slots.push(Slot {
             ug: self.ph.clone(),
             name: "ph".to_string(),
             value: match shared_ug.iter().position(|e| *e == self.ph) {
-                Some(n) => Value::Shared(n, shared_ug.iter().nth(n).unwrap().clone()),
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
                 None => Value::Ug(self.ph.clone()),
             },
         });
 
-        UgNode::Ug("wavetable".to_string(), slots)
+        for t in self.tables.iter() {
+            match shared_ug.iter().position(|e| *e == *t) {
+                Some(n) => values.push(Box::new(Value::Shared(n, shared_ug[n].clone()))),
+                None => values.push(Box::new(Value::Ug(t.clone()))),
+            }
+        }
+        UgNode::UgRest("morph".to_string(), slots, "table".to_string(), values)
     }
 }
 
-impl Operate for WaveTable {
+impl Operate for MorphTable {
     fn get(&self, pname: &str) -> Result<Aug, OperateError> {
-        match pname {
-            "table" => Ok(self.table.clone()),
-            "ph" => Ok(self.ph.clone()),
-            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+        match (pname, MorphTable::table_index(pname)) {
+            ("pos", _) => Ok(self.pos.clone()),
+            ("ph", _) => Ok(self.ph.clone()),
+            (_, Some(idx)) if idx < self.tables.len() => Ok(self.tables[idx].clone()),
+            _ => Err(OperateError::ParamNotFound(format!("morph/{}", pname))),
         }
     }
 
@@ -1278,7 +2841,7 @@ impl Operate for WaveTable {
                     Ok(v.to_string())
                 } else {
                     Err(OperateError::CannotRepresentAsString(format!(
-                        "wavetable/{}",
+                        "morph/{}",
                         pname
                     )))
                 }
@@ -1288,94 +2851,292 @@ impl Operate for WaveTable {
     }
 
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
-        match pname {
-            "table" => {
-                self.table = ug;
+        match (pname, MorphTable::table_index(pname)) {
+            ("pos", _) => {
+                self.pos = ug;
                 Ok(true)
             }
-            "ph" => {
+            ("ph", _) => {
                 self.ph = ug;
                 Ok(true)
             }
-            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+            (_, Some(idx)) if idx < self.tables.len() => {
+                self.tables[idx] = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("morph/{}", pname))),
         }
     }
 
     fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        if MorphTable::table_index(pname).is_some() {
+            data.retain(|c| c != '\n');
+            if let Some(data) = Table::parse_str(data.clone()) {
+                self.set(pname, Aug::new(UGen::new(UG::Tab(Table::new(data)))))
+            } else {
+                let err = OperateError::CannotParseNumber(format!("morph/{}", pname), data.clone());
+                Err(err)
+            }
+        } else {
+            data.retain(|c| c != '\n' && c != ' ');
+            if let Ok(v) = data.parse::<f64>() {
+                self.set(pname, Aug::val(v))
+            } else {
+                let err = OperateError::CannotParseNumber(format!("morph/{}", pname), data.clone());
+                Err(err)
+            }
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        if MorphTable::table_index(pname).is_some() {
+            let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 0.0]))));
+            let _ = self.set(pname, table);
+        } else {
+            let _ = self.set(pname, Aug::val(0.0));
+        }
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["pos".to_string(), "ph".to_string()];
+        for idx in 0..self.tables.len() {
+            params.push(format!("table{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        let last = self.tables.len().max(1) - 1;
         match pname {
-            "table" => {
-                let mut data = data.clone();
-                data.retain(|c| c != '\n');
+            "pos" => Some(ParamInfo::new(0.0, last as f64, 0.0, ParamUnit::Ratio)),
+            "ph" => Some(ParamInfo::new(0.0, 1.0, 0.0, ParamUnit::Cycle)),
+            _ => None,
+        }
+    }
+}
 
-                if let Some(data) = Table::parse_str(data.clone()) {
-                    self.table = Aug::new(UGen::new(UG::Tab(Table::new(data))));
-                    Ok(true)
+impl Proc for MorphTable {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let ph = self.ph.proc(transport).0;
+        let pos = self.pos.proc(transport).0;
+        if self.tables.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let last = self.tables.len() - 1;
+        let pos = pos.clamp(0.0, last as f64);
+        let idx = (pos.floor() as usize).min(last);
+        let v1 = MorphTable::read(&self.tables[idx], ph);
+        let v = if idx < last {
+            let v2 = MorphTable::read(&self.tables[idx + 1], ph);
+            v1 + (v2 - v1) * (pos - idx as f64)
+        } else {
+            v1
+        };
+        (v, v)
+    }
+}
+
+impl Osc for MorphTable {
+    fn set_ph(&mut self, ph: f64) {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.set_ph(ph);
+        }
+    }
+
+    fn get_ph(&self) -> f64 {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.get_ph()
+        } else {
+            0.0
+        }
+    }
+
+    fn set_freq(&mut self, freq: Aug) {
+        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+            osc.set_freq(freq);
+        }
+    }
+
+    fn get_freq(&self) -> Aug {
+        if let UG::Osc(ref osc) = &self.ph.0.lock().unwrap().ug {
+            osc.get_freq()
+        } else {
+            Aug::val(0.0)
+        }
+    }
+}
+
+pub struct Sync {
+    pub master: Aug,
+    pub slave: Aug,
+    prev_ph: f64,
+}
+
+impl Sync {
+    pub fn new(master: Aug, slave: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Osc(Box::new(Sync {
+            master,
+            slave,
+            prev_ph: 0.0,
+        }))))
+    }
+}
+
+impl Walk for Sync {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.master) {
+            self.master.walk(f);
+        }
+        if f(&self.slave) {
+            self.slave.walk(f);
+        }
+    }
+}
+
+impl Dump for Sync {
+    fn name(&self) -> &'static str {
+        "sync"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.master.clone(),
+            name: "master".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.master) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.master.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.slave.clone(),
+            name: "slave".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.slave) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.slave.clone()),
+            },
+        });
+
+        UgNode::Ug("sync".to_string(), slots)
+    }
+}
+
+impl Operate for Sync {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "master" => Ok(self.master.clone()),
+            "slave" => Ok(self.slave.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("sync/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
                 } else {
-                    let err = OperateError::CannotParseNumber(
-                        format!("wavetable/{}", pname),
-                        data.clone(),
-                    );
-                    Err(err)
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "sync/{}",
+                        pname
+                    )))
                 }
             }
-            "ph" => {
-                let mut data = data.clone();
-                data.retain(|c| c != '\n' && c != ' ');
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "master" => {
+                self.master = ug;
+                Ok(true)
+            }
+            "slave" => {
+                self.slave = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("sync/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n' && c != ' ');
 
+        match pname {
+            "master" | "slave" => {
                 if let Ok(v) = data.parse::<f64>() {
-                    self.ph = Aug::val(v);
-                    Ok(true)
+                    self.set(pname, Aug::val(v))
                 } else {
-                    let err = OperateError::CannotParseNumber(
-                        format!("wavetable/{}", pname),
-                        data.clone(),
-                    );
+                    let err =
+                        OperateError::CannotParseNumber(format!("sync/{}", pname), data.clone());
                     Err(err)
                 }
             }
-            _ => Err(OperateError::ParamNotFound(format!("wavetable/{}", pname))),
+            _ => Err(OperateError::ParamNotFound(format!("sync/{}", pname))),
         }
     }
 
     fn clear(&mut self, pname: &str) {
         match pname {
-            "table" => {
-                let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 0.0]))));
-                let _ = self.set(pname, table);
-            }
-            "ph" => {
+            "master" | "slave" => {
                 let _ = self.set(pname, Aug::val(0.0));
             }
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["master", "slave"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
-impl Proc for WaveTable {
+impl Proc for Sync {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        if let UG::Tab(table) = &self.table.0.lock().unwrap().ug {
-            let table = table.0.lock().unwrap();
-            let len = table.len() as f64;
-            let p = self.ph.proc(&transport).0 * len;
-            let pos1 = (p.floor() % len) as usize;
-            let pos2 = (p.ceil() % len) as usize;
-            let v = linear_interpol(table[pos1], table[pos2], p.fract());
-            (v, v)
+        self.master.proc(transport);
+
+        let ph = if let UG::Osc(ref osc) = &self.master.0.lock().unwrap().ug {
+            osc.get_ph()
         } else {
-            panic!("it's not a table!!");
+            0.0
+        };
+        // the master completed a cycle (or was reset) since the last sample
+        if ph.floor() > self.prev_ph.floor() || ph < self.prev_ph {
+            if let UG::Osc(ref mut osc) = &mut self.slave.0.lock().unwrap().ug {
+                osc.set_ph(0.0);
+            }
         }
+        self.prev_ph = ph;
+
+        self.slave.proc(transport)
+    }
+
+    fn reset(&mut self) {
+        self.prev_ph = 0.0;
     }
 }
 
-impl Osc for WaveTable {
+impl Osc for Sync {
     fn set_ph(&mut self, ph: f64) {
-        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+        if let UG::Osc(ref mut osc) = &mut self.master.0.lock().unwrap().ug {
+            osc.set_ph(ph);
+        }
+        if let UG::Osc(ref mut osc) = &mut self.slave.0.lock().unwrap().ug {
             osc.set_ph(ph);
         }
+        self.prev_ph = ph;
     }
 
     fn get_ph(&self) -> f64 {
-        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+        if let UG::Osc(ref osc) = &self.slave.0.lock().unwrap().ug {
             osc.get_ph()
         } else {
             0.0
@@ -1383,12 +3144,344 @@ impl Osc for WaveTable {
     }
 
     fn set_freq(&mut self, freq: Aug) {
-        if let UG::Osc(ref mut osc) = &mut self.ph.0.lock().unwrap().ug {
+        if let UG::Osc(ref mut osc) = &mut self.master.0.lock().unwrap().ug {
             osc.set_freq(freq);
         }
     }
 
     fn get_freq(&self) -> Aug {
-        Aug::val(0.0)
+        if let UG::Osc(ref osc) = &self.master.0.lock().unwrap().ug {
+            osc.get_freq()
+        } else {
+            Aug::val(0.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ugens::core::Ramp;
+    use crate::ugens::seq::AdsrEg;
+    use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
+
+    fn phase_of(osc: &Aug) -> f64 {
+        match &osc.0.lock().unwrap().ug {
+            UG::Osc(osc) => osc.get_ph(),
+            _ => panic!("not an oscillator"),
+        }
+    }
+
+    #[test]
+    fn sync_resets_slave_at_master_period() {
+        let master = Saw::new(Aug::val(0.0), Aug::val(100.0));
+        let slave = Saw::new(Aug::val(0.0), Aug::val(1234.0));
+        let mut sync = Sync::new(master.clone(), slave.clone());
+        let step = 1234.0 / 44100.0;
+
+        let mut transport = Transport::default();
+        let mut resets = Vec::new();
+        let mut prev = phase_of(&master);
+        for i in 0..441 * 4 + 10 {
+            sync.proc_n(&mut transport, 1);
+            let ph = phase_of(&master);
+            if ph.floor() > prev.floor() {
+                // the slave restarted from zero on this sample
+                assert!(phase_of(&slave) <= step + 1e-9);
+                resets.push(i);
+            }
+            prev = ph;
+        }
+        assert_eq!(resets.len(), 4);
+        for pair in resets.windows(2) {
+            assert!(((pair[1] - pair[0]) as i64 - 441).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn pulse_params() {
+        let pulse = Pulse::new(Aug::val(0.0), Aug::val(440.0), Aug::val(0.5));
+        assert_eq!(
+            pulse.params(),
+            vec!["init_ph", "freq", "duty", "clamp_freq"]
+        );
+        assert!(Aug::val(1.0).params().is_empty());
+    }
+
+    #[test]
+    fn param_ranges() {
+        let pulse = Pulse::new(Aug::val(0.0), Aug::val(440.0), Aug::val(0.5));
+        let duty = pulse.param_info("duty").unwrap();
+        assert_eq!((duty.min, duty.max), (0.0, 1.0));
+        assert_eq!(duty.unit, ParamUnit::Normalized);
+
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let freq = sine.param_info("freq").unwrap();
+        assert_eq!((freq.min, freq.max, freq.default), (20.0, 20000.0, 440.0));
+        assert_eq!(freq.unit, ParamUnit::Hz);
+        assert!(sine.param_info("duty").is_none());
+    }
+
+    fn table(data: Vec<f64>) -> Aug {
+        Aug::new(UGen::new(UG::Tab(Table::new(data))))
+    }
+
+    #[test]
+    fn morph_table_blends_neighbour_tables() {
+        let morph = |pos: f64, ph: f64| {
+            let ramp = table(vec![0.0, 0.25, 0.5, 0.75]);
+            let constant = table(vec![1.0; 4]);
+            let mut ug = MorphTable::new(vec![ramp, constant], Aug::val(pos), Aug::val(ph));
+            ug.proc_n(&mut Transport::default(), 1)[0].0
+        };
+        assert_eq!(morph(0.0, 0.25), 0.25);
+        assert_eq!(morph(1.0, 0.25), 1.0);
+        assert_eq!(morph(0.5, 0.25), 0.625);
+        assert_eq!(morph(0.25, 0.25), 0.4375);
+        // between samples within a table, and `pos` past the last table
+        assert_eq!(morph(0.0, 0.125), 0.125);
+        assert_eq!(morph(5.0, 0.125), 1.0);
+    }
+
+    fn magnitude(samples: &[f64], freq: f64) -> f64 {
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, v) in samples.iter().enumerate() {
+            let t = 2.0 * std::f64::consts::PI * freq * i as f64 / 44100.0;
+            re += v * t.cos();
+            im += v * t.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / samples.len() as f64
+    }
+
+    // energy of harmonics over the Nyquist frequency folded back under it
+    fn aliasing(mut osc: Aug, f0: f64) -> f64 {
+        let out: Vec<f64> = osc
+            .proc_n(&mut Transport::default(), 44100)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+        (8..40)
+            .map(|k| {
+                let f = (k as f64 * f0) % 44100.0;
+                magnitude(&out, f.min(44100.0 - f)).powi(2)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn blpulse_aliases_less_than_pulse() {
+        // harmonics of 3100 Hz fold back 700 Hz off them
+        let f0 = 3100.0;
+        let naive = aliasing(Pulse::new(Aug::val(0.0), Aug::val(f0), Aug::val(0.3)), f0);
+        let blep = aliasing(BlPulse::new(Aug::val(0.0), Aug::val(f0), Aug::val(0.3)), f0);
+        assert!(blep < naive * 0.25);
+    }
+
+    #[test]
+    fn oneshot_with_one_unit_as_osc_and_eg_does_not_hang() {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let shared = Sine::new(Aug::val(0.0), Aug::val(880.0));
+            let mut oneshot = OneshotOsc::new(shared.clone(), shared, false);
+            tx.send(oneshot.proc_n(&mut Transport::default(), 10))
+                .unwrap();
+        });
+        // not an envelope, so it is silent
+        let out = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("proc deadlocked");
+        assert!(out.iter().all(|s| *s == (0.0, 0.0)));
+    }
+
+    #[test]
+    fn lfo_stays_within_bounds_around_center() {
+        for shape in ["sine", "tri", "saw", "square"].iter() {
+            let shape = LfoShape::parse(shape).unwrap();
+            let mut lfo = Lfo::new(Aug::val(10.0), shape, Aug::val(100.0), Aug::val(200.0));
+            let out: Vec<f64> = lfo
+                .proc_n(&mut Transport::default(), 44100)
+                .iter()
+                .map(|s| s.0)
+                .collect();
+            assert!(out.iter().all(|v| (100.0..=200.0).contains(v)));
+            let mean = out.iter().sum::<f64>() / out.len() as f64;
+            assert!((mean - 150.0).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn phase_clear_resets_osc_and_its_chain() {
+        let mut phase = Phase::new(Sine::new(Aug::val(0.0), Aug::val(880.0)));
+        phase.clear("osc");
+        assert_eq!(phase.get("osc").unwrap().to_val(), Some(0.0));
+        // offset by 1.0 after halving the clipped source
+        let out = phase.proc_n(&mut Transport::default(), 10);
+        assert!(out.iter().all(|s| *s == (1.0, 1.0)));
+    }
+
+    #[test]
+    fn granular_sounds_only_with_density() {
+        let sine: Vec<f64> = (0..4410)
+            .map(|i| (2.0 * std::f64::consts::PI * i as f64 / 100.0).sin())
+            .collect();
+        let granular = |density: f64| {
+            let mut ug = Granular::new(
+                table(sine.clone()),
+                Aug::val(0.5),
+                Aug::val(0.05),
+                Aug::val(density),
+                Aug::val(0.3),
+            );
+            ug.proc_n(&mut Transport::default(), 44100)
+                .iter()
+                .map(|s| s.0)
+                .collect::<Vec<f64>>()
+        };
+
+        let out = granular(50.0);
+        let rms = (out.iter().map(|v| v * v).sum::<f64>() / out.len() as f64).sqrt();
+        assert!(rms > 0.1);
+        assert!(out.iter().all(|v| v.is_finite() && v.abs() <= 1.0));
+
+        assert!(granular(0.0).iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn rand_sequences_follow_their_seeds() {
+        // `freq` of `rand` is the number of samples a value is held for
+        let sequence = |seed: Option<u64>| {
+            Rand::new(Aug::val(10.0), seed)
+                .proc_n(&mut Transport::default(), 1000)
+                .iter()
+                .map(|s| s.0)
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(sequence(Some(1)), sequence(Some(1)));
+        assert_ne!(sequence(Some(1)), sequence(Some(2)));
+        // units without seeds differ from each other by default
+        assert_ne!(sequence(None), sequence(None));
+
+        // reseeding starts the sequence over
+        let mut rand = Rand::new(Aug::val(10.0), Some(7));
+        let first = rand.proc_n(&mut Transport::default(), 1000);
+        rand.0
+            .lock()
+            .unwrap()
+            .set_str("seed", "7".to_string())
+            .unwrap();
+        assert_eq!(rand.0.lock().unwrap().get_str("seed").unwrap(), "7");
+        assert_eq!(rand.proc_n(&mut Transport::default(), 1000), first);
+    }
+
+    #[test]
+    fn clamped_freq_stops_at_nyquist() {
+        let render = |freq: f64, clamp: bool| {
+            let mut sine = Sine::new(Aug::val(0.0), Aug::val(freq));
+            sine.set_str("clamp_freq", clamp.to_string()).unwrap();
+            sine.proc_n(&mut Transport::default(), 100)
+        };
+        let nyquist = render(22050.0, true);
+        assert_eq!(render(30000.0, true), nyquist);
+        assert!(render(-440.0, true).iter().all(|s| *s == (0.0, 0.0)));
+        assert_ne!(render(30000.0, false), nyquist);
+
+        let mut sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        assert_eq!(sine.get_str("clamp_freq").unwrap(), "false");
+        sine.set_str("clamp_freq", "true".to_string()).unwrap();
+        assert_eq!(sine.get_str("clamp_freq").unwrap(), "true");
+        sine.clear("clamp_freq");
+        assert_eq!(sine.get_str("clamp_freq").unwrap(), "false");
+    }
+
+    #[test]
+    fn make_osc_builds_the_named_waveform() {
+        let render = |mut osc: Aug| osc.proc_n(&mut Transport::default(), 200);
+        let made = make_osc(Waveform::Saw, Aug::val(0.25), Aug::val(440.0));
+        assert_eq!(
+            render(made),
+            render(Saw::new(Aug::val(0.25), Aug::val(440.0)))
+        );
+
+        assert!(matches!(Waveform::parse("saw"), Some(Waveform::Saw)));
+        assert!(Waveform::parse("noise").is_none());
+    }
+
+    #[test]
+    fn kick_sweeps_down_and_decays() {
+        let mut kick = KickDrum::new(
+            Ramp::new(1.0, 0.0, 1),
+            Aug::val(50.0),
+            Aug::val(0.3),
+            Aug::val(0.0),
+        );
+        let out: Vec<f64> = kick
+            .proc_n(&mut Transport::default(), 17640)
+            .iter()
+            .map(|s| s.0)
+            .collect();
+
+        let crossings: Vec<usize> = (1..out.len())
+            .filter(|i| out[i - 1] < 0.0 && out[*i] > 0.0)
+            .collect();
+        let periods: Vec<usize> = crossings.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(periods.len() >= 3);
+        assert!(periods.windows(2).all(|w| w[1] >= w[0]));
+        assert!(periods[0] * 2 < *periods.last().unwrap());
+
+        let peak =
+            |from: usize, to: usize| out[from..to].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        assert!(peak(0, 2205) > 0.5);
+        assert!(peak(8820, 11025) < peak(0, 2205) / 10.0);
+        // silent once `decay` has passed
+        assert!(out[13300..].iter().all(|v| *v == 0.0));
+    }
+
+    fn freq_of(osc: &Aug) -> Option<f64> {
+        match &osc.0.lock().unwrap().ug {
+            UG::Osc(osc) => osc.get_freq().to_val(),
+            _ => panic!("not an oscillator"),
+        }
+    }
+
+    #[test]
+    fn wrapping_oscillators_report_the_wrapped_freq() {
+        let sine = || Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let phase = Phase::new(sine());
+        assert_eq!(freq_of(&phase), Some(440.0));
+        if let UG::Osc(osc) = &mut phase.0.lock().unwrap().ug {
+            osc.set_freq(Aug::val(220.0));
+        }
+        assert_eq!(freq_of(&phase), Some(220.0));
+
+        let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 1.0, 0.0, -1.0]))));
+        assert_eq!(
+            freq_of(&WaveTable::from_table(table, Phase::new(sine()))),
+            Some(440.0)
+        );
+        let eg = AdsrEg::new(Aug::val(0.0), Aug::val(0.1), Aug::val(0.0), Aug::val(0.0));
+        assert_eq!(freq_of(&OneshotOsc::new(sine(), eg, false)), Some(440.0));
+        assert_eq!(freq_of(&Rand::new(Aug::val(10.0), Some(1))), Some(10.0));
+    }
+
+    #[test]
+    fn longer_tables_follow_a_low_sine_closer() {
+        let transport = Transport::default();
+        // one cycle of a 40 Hz sine
+        let expected =
+            Sine::new(Aug::val(0.0), Aug::val(40.0)).proc_n(&mut transport.clone(), 2205);
+        let error = |len: usize| {
+            let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+            let ph = Phase::new(Saw::new(Aug::val(0.0), Aug::val(40.0)));
+            let mut wavetable = WaveTable::from_osc(sine, ph, len, &transport);
+            let out = wavetable.proc_n(&mut transport.clone(), 2205);
+            out.iter()
+                .zip(expected.iter())
+                .fold(0.0f64, |e, (a, b)| e.max((a.0 - b.0).abs()))
+        };
+        let (coarse, fine) = (error(256), error(2048));
+        assert!(fine < 5e-3, "error with 2048 points {}", fine);
+        assert!(fine * 4.0 < coarse, "{} against {}", fine, coarse);
     }
 }
@@ -1,14 +1,14 @@
 use std::collections::VecDeque;
 
-use crate::musical_time::event::{Event, Message, Pitch};
+use crate::musical_time::event::{Event, Message, NoteNum, Pitch, Tuning, MAX_VELOCITY};
 use crate::musical_time::time::{Measure, Pos, PosOps, Transport};
-use crate::musical_time::utils::to_freq;
+use crate::musical_time::utils::to_tuned_freq;
 
 use super::core::{
-    Aug, Dump, Eg, Operate, OperateError, Pattern, Proc, Signal, Slot, UGen, UgNode, Value, Walk,
-    ADSR, UG,
+    Aug, Dump, Eg, Operate, OperateError, ParamInfo, ParamUnit, Pattern, Proc, Signal, Slot, Table,
+    UGen, UgNode, Value, Walk, ADSR, UG,
 };
-use super::misc::Add;
+use super::misc::{Add, RisingEdge};
 
 pub struct Trigger {
     eg: Aug,
@@ -35,6 +35,10 @@ impl Walk for Trigger {
 }
 
 impl Dump for Trigger {
+    fn name(&self) -> &'static str {
+        "trig"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
         let mut values = Vec::new();
@@ -141,6 +145,18 @@ impl Operate for Trigger {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        let mut params = vec!["eg".to_string()];
+        for idx in 0..self.egs.len() {
+            params.push(format!("src{}", idx));
+        }
+        params
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
 impl Proc for Trigger {
@@ -195,10 +211,6 @@ impl AdsrEg {
     }
 }
 
-fn sec_to_sample_num(sec: f64, transport: &Transport) -> u64 {
-    (transport.sample_rate as f64 * sec) as u64
-}
-
 impl Walk for AdsrEg {
     fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
         if f(&self.a) {
@@ -217,6 +229,10 @@ impl Walk for AdsrEg {
 }
 
 impl Dump for AdsrEg {
+    fn name(&self) -> &'static str {
+        "adsr"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -372,14 +388,28 @@ impl Operate for AdsrEg {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["a", "d", "s", "r"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "a" => Some(ParamInfo::new(0.0, 10.0, 0.0, ParamUnit::Seconds)),
+            "d" => Some(ParamInfo::new(0.0, 10.0, 0.0, ParamUnit::Seconds)),
+            "s" => Some(ParamInfo::new(0.0, 1.0, 1.0, ParamUnit::Normalized)),
+            "r" => Some(ParamInfo::new(0.0, 10.0, 0.0, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
 }
 
 impl Proc for AdsrEg {
     fn proc(&mut self, transport: &Transport) -> Signal {
-        let a = sec_to_sample_num(self.a.proc(transport).0, transport);
-        let d = sec_to_sample_num(self.d.proc(transport).0, transport);
+        let a = transport.sec_to_samples(self.a.proc(transport).0);
+        let d = transport.sec_to_samples(self.d.proc(transport).0);
         let s = self.s.proc(transport).0;
-        let r = sec_to_sample_num(self.r.proc(transport).0, transport);
+        let r = transport.sec_to_samples(self.r.proc(transport).0);
         let state = &self.state;
         let eplaced = self.eplaced;
         let v;
@@ -425,6 +455,11 @@ impl Proc for AdsrEg {
         self.eplaced += 1;
         (v, v)
     }
+
+    fn reset(&mut self) {
+        self.state = ADSR::None;
+        self.eplaced = 0;
+    }
 }
 
 impl Eg for AdsrEg {
@@ -438,6 +473,243 @@ impl Eg for AdsrEg {
     }
 }
 
+// plays `table` as an envelope over `duration` seconds, from the start of the table to its
+// end. it starts on rising edges of `gate` or on note-ons of `seq` and `trig`, and note-offs
+// are ignored so that the whole curve is drawn
+pub struct EnvTable {
+    table: Aug,
+    duration: Aug,
+    gate: Aug,
+    state: ADSR,
+    eplaced: u64,
+    edge: RisingEdge,
+}
+
+impl EnvTable {
+    pub fn new(table: Aug, duration: Aug, gate: Aug) -> Aug {
+        Aug::new(UGen::new(UG::Eg(Box::new(EnvTable {
+            table,
+            duration,
+            gate,
+            state: ADSR::None,
+            eplaced: 0,
+            edge: RisingEdge::new(),
+        }))))
+    }
+
+    fn read(table: &[f64], t: f64) -> f64 {
+        if table.is_empty() {
+            return 0.0;
+        }
+        let p = t.clamp(0.0, 1.0) * (table.len() - 1) as f64;
+        let v1 = table[p.floor() as usize];
+        let v2 = table[p.ceil() as usize];
+        v1 + (v2 - v1) * p.fract()
+    }
+}
+
+impl Walk for EnvTable {
+    fn walk(&self, f: &mut dyn FnMut(&Aug) -> bool) {
+        if f(&self.table) {
+            self.table.walk(f);
+        }
+        if f(&self.duration) {
+            self.duration.walk(f);
+        }
+        if f(&self.gate) {
+            self.gate.walk(f);
+        }
+    }
+}
+
+impl Dump for EnvTable {
+    fn name(&self) -> &'static str {
+        "envtable"
+    }
+
+    fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
+        let mut slots = Vec::new();
+
+        slots.push(Slot {
+            ug: self.table.clone(),
+            name: "table".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.table) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.table.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.duration.clone(),
+            name: "duration".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.duration) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.duration.clone()),
+            },
+        });
+        slots.push(Slot {
+            ug: self.gate.clone(),
+            name: "gate".to_string(),
+            value: match shared_ug.iter().position(|e| *e == self.gate) {
+                Some(n) => Value::Shared(n, shared_ug[n].clone()),
+                None => Value::Ug(self.gate.clone()),
+            },
+        });
+
+        UgNode::Ug("envtable".to_string(), slots)
+    }
+}
+
+impl Operate for EnvTable {
+    fn get(&self, pname: &str) -> Result<Aug, OperateError> {
+        match pname {
+            "table" => Ok(self.table.clone()),
+            "duration" => Ok(self.duration.clone()),
+            "gate" => Ok(self.gate.clone()),
+            _ => Err(OperateError::ParamNotFound(format!("envtable/{}", pname))),
+        }
+    }
+
+    fn get_str(&self, pname: &str) -> Result<String, OperateError> {
+        match self.get(pname) {
+            Ok(aug) => {
+                if let Some(v) = aug.to_val() {
+                    Ok(v.to_string())
+                } else {
+                    Err(OperateError::CannotRepresentAsString(format!(
+                        "envtable/{}",
+                        pname
+                    )))
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
+        match pname {
+            "table" => {
+                self.table = ug;
+                Ok(true)
+            }
+            "duration" => {
+                self.duration = ug;
+                Ok(true)
+            }
+            "gate" => {
+                self.gate = ug;
+                Ok(true)
+            }
+            _ => Err(OperateError::ParamNotFound(format!("envtable/{}", pname))),
+        }
+    }
+
+    fn set_str(&mut self, pname: &str, data: String) -> Result<bool, OperateError> {
+        let mut data = data.clone();
+        data.retain(|c| c != '\n');
+
+        if pname == "table" {
+            return match Table::parse_str(data.clone()) {
+                Some(data) => self.set(pname, Aug::new(UGen::new(UG::Tab(Table::new(data))))),
+                None => Err(OperateError::CannotParseNumber(
+                    format!("envtable/{}", pname),
+                    data,
+                )),
+            };
+        }
+        data.retain(|c| c != ' ');
+
+        match pname {
+            "duration" | "gate" => {
+                if let Ok(v) = data.parse::<f64>() {
+                    self.set(pname, Aug::val(v))
+                } else {
+                    let err = OperateError::CannotParseNumber(
+                        format!("envtable/{}", pname),
+                        data.clone(),
+                    );
+                    Err(err)
+                }
+            }
+            _ => Err(OperateError::ParamNotFound(format!("envtable/{}", pname))),
+        }
+    }
+
+    fn clear(&mut self, pname: &str) {
+        match pname {
+            "table" => {
+                let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0]))));
+                let _ = self.set(pname, table);
+            }
+            "duration" | "gate" => {
+                let _ = self.set(pname, Aug::val(0.0));
+            }
+            _ => (),
+        };
+    }
+
+    fn params(&self) -> Vec<String> {
+        ["table", "duration", "gate"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, pname: &str) -> Option<ParamInfo> {
+        match pname {
+            "duration" => Some(ParamInfo::new(0.0, 10.0, 1.0, ParamUnit::Seconds)),
+            _ => None,
+        }
+    }
+}
+
+impl Proc for EnvTable {
+    fn proc(&mut self, transport: &Transport) -> Signal {
+        let duration = transport.sec_to_samples(self.duration.proc(transport).0);
+        if self.edge.detect(self.gate.proc(transport).0, 0.0) {
+            self.state = ADSR::Attack;
+            self.eplaced = 0;
+        }
+        if let ADSR::None = self.state {
+            return (0.0, 0.0);
+        }
+        if self.eplaced >= duration {
+            self.state = ADSR::None;
+            return (0.0, 0.0);
+        }
+
+        let t = self.eplaced as f64 / duration as f64;
+        let v = if let UG::Tab(table) = &self.table.0.lock().unwrap().ug {
+            EnvTable::read(&table.0.lock().unwrap(), t)
+        } else {
+            0.0
+        };
+        self.eplaced += 1;
+        (v, v)
+    }
+
+    fn reset(&mut self) {
+        self.state = ADSR::None;
+        self.eplaced = 0;
+        self.edge.reset();
+    }
+}
+
+impl Eg for EnvTable {
+    fn get_state(&self) -> ADSR {
+        self.state.clone()
+    }
+
+    fn set_state(&mut self, state: ADSR, eplaced: u64) {
+        match state {
+            ADSR::Release => (),
+            state => {
+                self.state = state;
+                self.eplaced = eplaced;
+            }
+        }
+    }
+}
+
 pub type SeqBeatHook = fn(&Pos);
 pub type SeqEventHook = fn(&Event, &Pos);
 
@@ -448,23 +720,39 @@ pub struct Seq {
     osc_mod: Aug,
     eg: Aug,
 
+    velocity: f64,
+    tuning: Tuning,
+    root: NoteNum,
     fill: bool,
     prev_beat: u64,
+    prev_pos: Pos,
 
     beat_hook: SeqBeatHook,
     event_hook: SeqEventHook,
 }
 
 impl Seq {
-    pub fn new(pat: Aug, osc: Aug, osc_mod: Aug, eg: Aug, transport: &Transport) -> Aug {
+    pub fn new(
+        pat: Aug,
+        osc: Aug,
+        osc_mod: Aug,
+        eg: Aug,
+        tuning: Tuning,
+        root: NoteNum,
+        transport: &Transport,
+    ) -> Aug {
         let mut seq = Seq {
             pattern: pat,
             queue: VecDeque::new(),
             osc: osc,
             osc_mod: osc_mod,
             eg: eg,
+            velocity: 1.0,
+            tuning,
+            root,
             fill: false,
             prev_beat: 255,
+            prev_pos: transport.pos.clone(),
             beat_hook: |_| {},
             event_hook: |_, _| {},
         };
@@ -472,32 +760,101 @@ impl Seq {
         Aug::new(UGen::new(UG::Proc(Box::new(seq))))
     }
 
+    // seq drives one oscillator, so patterns with chords are refused
+    fn chord_error(pname: &str) -> OperateError {
+        OperateError::TypeMismatch(
+            format!("seq/{}", pname),
+            "pattern without chords".to_string(),
+        )
+    }
+
     pub fn fill_queue(&mut self, base: &Pos, measure: &Measure) {
         let mut pos = base.clone();
+        // length of the last step (extended by ties) and whether a note is sounding
+        let mut last_len: Option<Pos> = None;
+        let mut sounding = false;
+
         if let UG::Pat(pat) = &self.pattern.0.lock().unwrap().ug {
             for m in pat.0.lock().unwrap().iter() {
+                if let Message::Tie = &**m {
+                    if let Some(len) = &last_len {
+                        pos = pos.clone().add(len.clone(), &measure);
+                    }
+                    continue;
+                } else if sounding {
+                    self.queue.push_back(Box::new(Event::Off(pos.clone())));
+                    sounding = false;
+                }
+
                 match &**m {
-                    Message::Note(pitch, len) => match pitch {
-                        Pitch::Pitch(_, _) => {
-                            self.queue
-                                .push_back(Box::new(Event::On(pos.clone(), to_freq(pitch))));
-                            pos = pos.clone().add(len.clone(), &measure);
-                            self.queue.push_back(Box::new(Event::Off(pos.clone())));
+                    Message::Note(pitch, len, vel) => {
+                        match pitch {
+                            Pitch::Pitch(_, _) => {
+                                self.queue.push_back(Box::new(Event::On(
+                                    pos.clone(),
+                                    to_tuned_freq(pitch, &self.tuning, self.root),
+                                    *vel,
+                                )));
+                                sounding = true;
+                            }
+                            Pitch::Kick => {
+                                self.queue
+                                    .push_back(Box::new(Event::Kick(pos.clone(), *vel)));
+                                sounding = true;
+                            }
+                            Pitch::Rest => (),
                         }
-                        Pitch::Kick => {
-                            self.queue.push_back(Box::new(Event::Kick(pos.clone())));
-                            pos = pos.clone().add(len.clone(), &measure);
-                            self.queue.push_back(Box::new(Event::Off(pos.clone())));
+                        pos = pos.clone().add(len.clone(), &measure);
+                        last_len = Some(len.clone());
+                    }
+                    Message::Chord(notes) => {
+                        // seq is monophonic, so only the first note of chords which come
+                        // in by editing the pattern afterwards is played with one attack.
+                        // the note-off closes the longest note
+                        let mut chord_len: Option<Pos> = None;
+                        let mut onset = false;
+                        for n in notes.iter() {
+                            if let Message::Note(pitch, len, vel) = n {
+                                match pitch {
+                                    Pitch::Pitch(_, _) if !onset => {
+                                        self.queue.push_back(Box::new(Event::On(
+                                            pos.clone(),
+                                            to_tuned_freq(pitch, &self.tuning, self.root),
+                                            *vel,
+                                        )));
+                                        onset = true;
+                                    }
+                                    Pitch::Kick if !onset => {
+                                        self.queue
+                                            .push_back(Box::new(Event::Kick(pos.clone(), *vel)));
+                                        onset = true;
+                                    }
+                                    _ => (),
+                                }
+                                let longer = match &chord_len {
+                                    Some(l) => len > l,
+                                    None => true,
+                                };
+                                if longer {
+                                    chord_len = Some(len.clone());
+                                }
+                            }
                         }
-                        Pitch::Rest => {
-                            pos = pos.clone().add(len.clone(), &measure);
+                        if let Some(len) = &chord_len {
+                            pos = pos.clone().add(len.clone(), measure);
                         }
-                    },
+                        last_len = chord_len;
+                        sounding = true;
+                    }
                     Message::Loop => {
                         self.queue.push_back(Box::new(Event::Loop(pos.clone())));
                     }
+                    Message::Tie => (),
                 }
             }
+            if sounding {
+                self.queue.push_back(Box::new(Event::Off(pos.clone())));
+            }
         } else {
             println!("aug is not a pattern!!");
         }
@@ -530,6 +887,10 @@ impl Walk for Seq {
 }
 
 impl Dump for Seq {
+    fn name(&self) -> &'static str {
+        "seq"
+    }
+
     fn dump(&self, shared_ug: &Vec<Aug>) -> UgNode {
         let mut slots = Vec::new();
 
@@ -600,6 +961,11 @@ impl Operate for Seq {
     fn set(&mut self, pname: &str, ug: Aug) -> Result<bool, OperateError> {
         match pname {
             "pattern" => {
+                if let UG::Pat(pat) = &ug.0.lock().unwrap().ug {
+                    if pat.has_chords() {
+                        return Err(Seq::chord_error(pname));
+                    }
+                }
                 self.pattern = ug;
                 self.fill = true;
                 Ok(true)
@@ -627,7 +993,11 @@ impl Operate for Seq {
                 data.retain(|c| c != '\n');
 
                 if let Ok(msgs) = Pattern::parse_str(data.clone()) {
-                    self.pattern = Aug::new(UGen::new(UG::Pat(Pattern::new(msgs))));
+                    let pat = Pattern::new(msgs);
+                    if pat.has_chords() {
+                        return Err(Seq::chord_error(pname));
+                    }
+                    self.pattern = Aug::new(UGen::new(UG::Pat(pat)));
                     self.fill = true;
                     Ok(true)
                 } else {
@@ -682,7 +1052,8 @@ impl Operate for Seq {
     fn clear(&mut self, pname: &str) {
         match pname {
             "pattern" => {
-                let _ = self.set(pname, Aug::val(0.0));
+                let pattern = Aug::new(UGen::new(UG::Pat(Pattern::new(Vec::new()))));
+                let _ = self.set(pname, pattern);
             }
             "osc" => {
                 let _ = self.set(pname, Aug::val(0.0));
@@ -696,6 +1067,17 @@ impl Operate for Seq {
             _ => (),
         };
     }
+
+    fn params(&self) -> Vec<String> {
+        ["pattern", "osc", "osc_mod", "eg"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn param_info(&self, _pname: &str) -> Option<ParamInfo> {
+        None
+    }
 }
 
 impl Proc for Seq {
@@ -703,6 +1085,19 @@ impl Proc for Seq {
         self.osc_mod.proc(&transport);
         let (ol, or) = self.osc.proc(&transport);
         let (el, er) = self.eg.proc(&transport);
+
+        // the transport jumped back to its loop start so the pattern restarts from there
+        if transport.pos < self.prev_pos {
+            self.queue.clear();
+            let base = Pos {
+                bar: transport.pos.bar,
+                beat: 0,
+                pos: 0.0,
+            };
+            self.fill_queue(&base, &transport.measure_at(base.bar));
+        }
+        self.prev_pos = transport.pos.clone();
+
         let mut q = self.queue.iter().peekable();
 
         if transport.pos.beat != self.prev_beat {
@@ -711,11 +1106,12 @@ impl Proc for Seq {
 
         match q.peek() {
             Some(e) => {
-                (self.event_hook)(&e, &transport.pos);
+                (self.event_hook)(e, &transport.pos);
                 match &***e {
-                    Event::On(pos, _freq) => {
+                    Event::On(pos, _freq, _vel) => {
                         if pos <= &transport.pos {
-                            if let Event::On(_pos, freq) = *self.queue.pop_front().unwrap() {
+                            if let Event::On(_pos, freq, vel) = *self.queue.pop_front().unwrap() {
+                                self.velocity = vel as f64 / MAX_VELOCITY as f64;
                                 if let UG::Osc(ref mut osc) = &mut self.osc.0.lock().unwrap().ug {
                                     let freq = vec![
                                         self.osc_mod.clone(),
@@ -729,9 +1125,10 @@ impl Proc for Seq {
                             }
                         }
                     }
-                    Event::Kick(pos) => {
+                    Event::Kick(pos, _vel) => {
                         if pos <= &transport.pos {
-                            if let Event::Kick(_pos) = *self.queue.pop_front().unwrap() {
+                            if let Event::Kick(_pos, vel) = *self.queue.pop_front().unwrap() {
+                                self.velocity = vel as f64 / MAX_VELOCITY as f64;
                                 if let UG::Eg(ref mut eg) = &mut self.eg.0.lock().unwrap().ug {
                                     eg.set_state(ADSR::Attack, 0);
                                 }
@@ -755,7 +1152,7 @@ impl Proc for Seq {
                                 beat: 0,
                                 pos: 0.0,
                             };
-                            self.fill_queue(&base, &transport.measure);
+                            self.fill_queue(&base, &transport.measure_at(base.bar));
                         }
                     }
                 }
@@ -769,10 +1166,100 @@ impl Proc for Seq {
                 beat: 0,
                 pos: 0.0,
             };
-            self.fill_queue(&base, &transport.measure);
+            self.fill_queue(&base, &transport.measure_at(base.bar));
         }
         self.fill = false;
 
-        ((ol * el), (or * er))
+        let vel = self.velocity;
+        ((ol * el * vel), (or * er * vel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::utils::to_pos;
+
+    #[test]
+    fn seq_refuses_chords() {
+        let pattern = |s: &str| {
+            let msgs = Pattern::parse_str(s.to_string()).unwrap();
+            Aug::new(UGen::new(UG::Pat(Pattern::new(msgs))))
+        };
+        let transport = Transport::default();
+        let eg = AdsrEg::new(Aug::val(0.0), Aug::val(0.1), Aug::val(0.5), Aug::val(0.1));
+        let mut seq = Seq::new(
+            pattern("c4:4 loop"),
+            Aug::val(0.0),
+            Aug::val(0.0),
+            eg,
+            Tuning::EqualTemperament(440.0),
+            3,
+            &transport,
+        );
+
+        assert!(seq.set("pattern", pattern("[c4:4 e4:4] loop")).is_err());
+        assert!(seq
+            .set_str("pattern", "[c4:4 e4:4] loop".to_string())
+            .is_err());
+        assert!(seq.set("pattern", pattern("e4:4 loop")).is_ok());
+    }
+
+    // events queued for one pass of `pattern`
+    fn queue_of(pattern: &str) -> Vec<Event> {
+        let msgs = Pattern::parse_str(pattern.to_string()).unwrap();
+        let transport = Transport::default();
+        let mut seq = Seq {
+            pattern: Aug::new(UGen::new(UG::Pat(Pattern::new(msgs)))),
+            queue: VecDeque::new(),
+            osc: Aug::val(0.0),
+            osc_mod: Aug::val(0.0),
+            eg: Aug::val(0.0),
+            velocity: 1.0,
+            tuning: Tuning::EqualTemperament(440.0),
+            root: 3,
+            fill: false,
+            prev_beat: 255,
+            prev_pos: transport.pos.clone(),
+            beat_hook: |_| {},
+            event_hook: |_, _| {},
+        };
+        seq.fill_queue(&transport.pos, &transport.measure);
+        seq.queue.into_iter().map(|e| *e).collect()
+    }
+
+    #[test]
+    fn tied_note_has_one_onset() {
+        let events = queue_of("c4:8 ~ ~");
+        let measure = Transport::default().measure;
+        let step = Pattern::to_beats(&to_pos(8), &measure);
+
+        let onsets = events
+            .iter()
+            .filter(|e| matches!(e, Event::On(_, _, _)))
+            .count();
+        assert_eq!(onsets, 1);
+        match events.last() {
+            Some(Event::Off(pos)) => assert_eq!(Pattern::to_beats(pos, &measure), step * 3.0),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn envtable_peaks_at_the_middle_of_a_rise_and_fall() {
+        let table = Aug::new(UGen::new(UG::Tab(Table::new(vec![0.0, 1.0, 0.0]))));
+        // 0.1 seconds are 4410 samples
+        let mut eg = EnvTable::new(table, Aug::val(0.1), Aug::val(1.0));
+        let out = eg.proc_n(&mut Transport::default(), 6000);
+
+        let peak = (0..out.len())
+            .max_by(|a, b| out[*a].0.partial_cmp(&out[*b].0).unwrap())
+            .unwrap();
+        assert_eq!(peak, 2205);
+        assert!((out[peak].0 - 1.0).abs() < 1e-9);
+        assert!((out[1102].0 - 0.5).abs() < 1e-3);
+        assert!((out[3307].0 - 0.5).abs() < 1e-3);
+        // holding the gate does not start it again
+        assert!(out[4410..].iter().all(|s| *s == (0.0, 0.0)));
     }
 }
@@ -1,4 +1,6 @@
-use super::core::{Aug, Walk};
+use std::collections::HashSet;
+
+use super::core::{Aug, Operate, Osc, Walk, UG};
 
 pub fn collect_shared_ugs(ug: Aug) -> Vec<Aug> {
     let mut searched_units: Vec<Aug> = Vec::new();
@@ -25,3 +27,353 @@ pub fn collect_shared_ugs(ug: Aug) -> Vec<Aug> {
 
     shared_units
 }
+
+fn rewind_ug(ug: &Aug) {
+    let mut ug = ug.0.lock().unwrap();
+    ug.last_tick = 0;
+    ug.last_sig = (0.0, 0.0);
+    ug.ug.set_ph(0.0);
+}
+
+pub fn rewind_ugs(ug: Aug) {
+    let mut searched_units: Vec<Aug> = Vec::new();
+
+    rewind_ug(&ug);
+    ug.walk(&mut |u: &Aug| {
+        if searched_units.contains(u) {
+            false
+        } else {
+            searched_units.push(u.clone());
+            rewind_ug(u);
+            true
+        }
+    });
+}
+
+fn children(ug: &Aug) -> Vec<Aug> {
+    let mut children = Vec::new();
+    ug.walk(&mut |u: &Aug| {
+        children.push(u.clone());
+        false
+    });
+    children
+}
+
+fn find_cycle(ug: &Aug, path: &mut Vec<Aug>, searched_units: &mut Vec<Aug>) -> Option<Vec<usize>> {
+    if let Some(idx) = path.iter().position(|e| *e == *ug) {
+        let mut ids: Vec<usize> = path[idx..].iter().map(|u| u.0.lock().unwrap().id).collect();
+        ids.push(ug.0.lock().unwrap().id);
+        return Some(ids);
+    }
+    if searched_units.contains(ug) {
+        return None;
+    }
+
+    path.push(ug.clone());
+    for child in children(ug).iter() {
+        if let Some(ids) = find_cycle(child, path, searched_units) {
+            return Some(ids);
+        }
+    }
+    path.pop();
+    searched_units.push(ug.clone());
+    None
+}
+
+// returns unit ids along a loop, starting and ending at the same unit, if the graph has one
+pub fn detect_cycle(root: &Aug) -> Option<Vec<usize>> {
+    find_cycle(root, &mut Vec::new(), &mut Vec::new())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GraphStats {
+    // references to units, counting shared ones each time they are referred
+    pub total: usize,
+    pub unique: usize,
+    // the kinds of unique units
+    pub values: usize,
+    pub oscillators: usize,
+    pub procs: usize,
+    pub egs: usize,
+    pub tables: usize,
+    pub patterns: usize,
+}
+
+impl GraphStats {
+    fn count(&mut self, ug: &Aug, ids: &mut HashSet<usize>) -> bool {
+        self.total += 1;
+        let ug = ug.0.lock().unwrap();
+        if !ids.insert(ug.id) {
+            return false;
+        }
+        self.unique += 1;
+        match ug.ug {
+            UG::Val(_) => self.values += 1,
+            UG::Osc(_) => self.oscillators += 1,
+            UG::Proc(_) => self.procs += 1,
+            UG::Eg(_) => self.egs += 1,
+            UG::Tab(_) => self.tables += 1,
+            UG::Pat(_) => self.patterns += 1,
+        }
+        true
+    }
+}
+
+// shared subgraphs are walked once, so they add to `total` only by the references to them
+pub fn graph_stats(root: &Aug) -> GraphStats {
+    let mut stats = GraphStats::default();
+    let mut ids = HashSet::new();
+    stats.count(root, &mut ids);
+    root.walk(&mut |u: &Aug| stats.count(u, &mut ids));
+    stats
+}
+
+// peaks of every unit under `root` as (id, peak), in the order they are walked.
+// units only record peaks while metering is enabled with `set_peak_metering`
+pub fn peak_report(root: &Aug) -> Vec<(usize, f64)> {
+    let mut ids = HashSet::new();
+    let mut report = Vec::new();
+    let mut visit = |u: &Aug| {
+        let ug = u.0.lock().unwrap();
+        if ids.insert(ug.id) {
+            report.push((ug.id, ug.peak));
+            true
+        } else {
+            false
+        }
+    };
+    visit(root);
+    root.walk(&mut visit);
+    report
+}
+
+pub fn set_peak_metering(root: &Aug, enabled: bool) {
+    root.0.lock().unwrap().metering = enabled;
+    root.walk(&mut |u: &Aug| {
+        u.0.lock().unwrap().metering = enabled;
+        true
+    });
+}
+
+pub fn clear_peaks(root: &Aug) {
+    root.0.lock().unwrap().peak = 0.0;
+    root.walk(&mut |u: &Aug| {
+        u.0.lock().unwrap().peak = 0.0;
+        true
+    });
+}
+
+fn find_path(from: &Aug, to: &Aug, path: &mut Vec<Aug>, searched_units: &mut Vec<Aug>) -> bool {
+    path.push(from.clone());
+    if *from == *to {
+        return true;
+    }
+    if !searched_units.contains(from) {
+        searched_units.push(from.clone());
+        for child in children(from).iter() {
+            if find_path(child, to, path, searched_units) {
+                return true;
+            }
+        }
+    }
+    path.pop();
+    false
+}
+
+// the loop made by pointing slots of `parents` at `replacement`, as `detect_cycle` reports it
+fn closing_loop(replacement: &Aug, parents: &[Aug]) -> Option<Vec<usize>> {
+    if let Some(ids) = detect_cycle(replacement) {
+        return Some(ids);
+    }
+    for parent in parents.iter() {
+        let mut path = Vec::new();
+        if find_path(replacement, parent, &mut path, &mut Vec::new()) {
+            let mut ids: Vec<usize> = path.iter().map(|u| u.0.lock().unwrap().id).collect();
+            ids.push(replacement.0.lock().unwrap().id);
+            return Some(ids);
+        }
+    }
+    None
+}
+
+// points every slot holding the unit `target_id` at `replacement` and returns how many slots
+// were rewired. `root` itself is replaced when it is the target. units are collected before
+// rewiring, so a replacement wrapping the target (e.g. in `gain`) keeps referring to it.
+// nothing is rewired when it would connect units in a loop, and the loop is returned instead
+pub fn replace_node(
+    root: &mut Aug,
+    target_id: usize,
+    replacement: Aug,
+) -> Result<usize, Vec<usize>> {
+    if root.0.lock().unwrap().id == target_id {
+        if let Some(ids) = closing_loop(&replacement, &[]) {
+            return Err(ids);
+        }
+        *root = replacement;
+        return Ok(1);
+    }
+
+    let mut ids = HashSet::new();
+    let mut units = vec![root.clone()];
+    ids.insert(root.0.lock().unwrap().id);
+    root.walk(&mut |u: &Aug| {
+        if ids.insert(u.0.lock().unwrap().id) {
+            units.push(u.clone());
+            true
+        } else {
+            false
+        }
+    });
+
+    let mut hits = Vec::new();
+    for unit in units.iter() {
+        let ug = unit.0.lock().unwrap();
+        for pname in ug.params() {
+            if let Ok(slot) = ug.get(&pname) {
+                if slot != *unit && slot.0.lock().unwrap().id == target_id {
+                    hits.push((unit.clone(), pname));
+                }
+            }
+        }
+    }
+    let parents: Vec<Aug> = hits.iter().map(|(unit, _)| unit.clone()).collect();
+    if let Some(ids) = closing_loop(&replacement, &parents) {
+        return Err(ids);
+    }
+
+    let mut replaced = 0;
+    for (unit, pname) in hits.iter() {
+        if let Ok(true) = unit.0.lock().unwrap().set(pname, replacement.clone()) {
+            replaced += 1;
+        }
+    }
+    Ok(replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musical_time::time::Transport;
+    use crate::ugens::fx::LPFilter;
+    use crate::ugens::misc::{Add, Gain};
+    use crate::ugens::osc::Saw;
+    use crate::ugens::osc::Sine;
+
+    fn id(ug: &Aug) -> usize {
+        ug.0.lock().unwrap().id
+    }
+
+    #[test]
+    fn detects_a_feedback_loop() {
+        let mut gain = Gain::new(Aug::val(0.5), Aug::val(0.0));
+        let lpf = LPFilter::new(Aug::val(800.0), Aug::val(1.0), gain.clone());
+        gain.set("src", lpf.clone()).unwrap();
+
+        let path = detect_cycle(&lpf).unwrap();
+        assert_eq!(path, vec![id(&lpf), id(&gain), id(&lpf)]);
+
+        // break it so that the units can be dropped
+        gain.set("src", Aug::val(0.0)).unwrap();
+        assert!(detect_cycle(&lpf).is_none());
+    }
+
+    #[test]
+    fn shared_units_are_not_cycles() {
+        let shared = LPFilter::new(Aug::val(800.0), Aug::val(1.0), Aug::val(0.0));
+        let root = Add::new(vec![shared.clone(), Gain::new(Aug::val(0.5), shared)]);
+        assert!(detect_cycle(&root).is_none());
+    }
+
+    #[test]
+    fn graph_stats_counts_shared_units_once_as_unique() {
+        let shared = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let root = Add::new(vec![
+            shared.clone(),
+            LPFilter::new(Aug::val(1000.0), Aug::val(1.0), shared),
+        ]);
+        let stats = graph_stats(&root);
+        assert_eq!(stats.total, stats.unique + 1);
+        assert_eq!(stats.unique, 7);
+        assert_eq!(stats.oscillators, 1);
+        assert_eq!(stats.procs, 2);
+        assert_eq!(stats.values, 4);
+        assert_eq!(stats.egs + stats.tables + stats.patterns, 0);
+    }
+
+    #[test]
+    fn peak_report_points_at_the_loud_stage() {
+        let loud = Gain::new(Aug::val(2.0), Sine::new(Aug::val(0.0), Aug::val(880.0)));
+        let quiet = Gain::new(Aug::val(0.25), Sine::new(Aug::val(0.0), Aug::val(660.0)));
+        let mut root = Add::new(vec![loud.clone(), quiet.clone()]);
+
+        set_peak_metering(&root, true);
+        root.proc_n(&mut Transport::default(), 1000);
+        set_peak_metering(&root, false);
+
+        let report = peak_report(&root);
+        let peak = |ug: &Aug| report.iter().find(|(n, _)| *n == id(ug)).unwrap().1;
+        assert!(peak(&loud) > 1.0);
+        assert!(peak(&quiet) < 1.0 && peak(&quiet) > 0.2);
+        assert!(peak(&root) > 1.0);
+
+        clear_peaks(&root);
+        assert!(peak_report(&root).iter().all(|(_, p)| *p == 0.0));
+        // nothing is recorded with metering off
+        root.proc_n(&mut Transport::default(), 1000);
+        assert!(peak_report(&root).iter().all(|(_, p)| *p == 0.0));
+
+        // and metering is per unit, so other graphs are left alone
+        let other = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        set_peak_metering(&other, true);
+        root.proc_n(&mut Transport::default(), 1000);
+        assert!(peak_report(&root).iter().all(|(_, p)| *p == 0.0));
+    }
+
+    #[test]
+    fn replace_node_rewires_every_reference_to_a_shared_unit() {
+        let shared = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let lpf = LPFilter::new(Aug::val(1000.0), Aug::val(1.0), shared.clone());
+        let mut root = Add::new(vec![shared.clone(), lpf.clone()]);
+
+        let saw = Saw::new(Aug::val(0.0), Aug::val(220.0));
+        assert_eq!(replace_node(&mut root, id(&shared), saw.clone()), Ok(2));
+        assert!(lpf.0.lock().unwrap().get("src").unwrap() == saw);
+        let mut found = (0, 0);
+        root.walk(&mut |u: &Aug| {
+            if *u == saw {
+                found.0 += 1;
+            } else if *u == shared {
+                found.1 += 1;
+            }
+            true
+        });
+        assert_eq!(found, (2, 0));
+
+        // nothing refers to it anymore
+        assert_eq!(replace_node(&mut root, id(&shared), saw.clone()), Ok(0));
+        let root_id = id(&root);
+        assert_eq!(replace_node(&mut root, root_id, saw.clone()), Ok(1));
+        assert!(root == saw);
+    }
+
+    #[test]
+    fn replace_node_refuses_to_close_a_loop() {
+        let sine = Sine::new(Aug::val(0.0), Aug::val(440.0));
+        let lpf = LPFilter::new(Aug::val(1000.0), Aug::val(1.0), sine.clone());
+        let mut root = Gain::new(Aug::val(0.5), lpf.clone());
+
+        // the filter taking its own place as its source
+        assert_eq!(
+            replace_node(&mut root, id(&sine), lpf.clone()),
+            Err(vec![id(&lpf), id(&lpf)])
+        );
+        // or a unit reaching it
+        let wrapped = Gain::new(Aug::val(2.0), lpf.clone());
+        assert_eq!(
+            replace_node(&mut root, id(&sine), wrapped.clone()),
+            Err(vec![id(&wrapped), id(&lpf), id(&wrapped)])
+        );
+        assert!(lpf.0.lock().unwrap().get("src").unwrap() == sine);
+        assert!(detect_cycle(&root).is_none());
+    }
+}